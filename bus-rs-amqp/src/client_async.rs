@@ -0,0 +1,223 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use futures_util::StreamExt as _;
+use lapin::{
+    message::Delivery,
+    options::{
+        BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicPublishOptions,
+        BasicQosOptions, ConfirmSelectOptions, ExchangeDeclareOptions, QueueBindOptions,
+        QueueDeclareOptions,
+    },
+    types::{AMQPValue, FieldTable},
+    BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
+};
+
+use crate::to_client_error;
+
+const DEFAULT_BINDING_KEY: &str = "#";
+const DEFAULT_PREFETCH: u16 = 10;
+const PERSISTENT_DELIVERY_MODE: u8 = 2;
+
+/// Messages are published into the topic exchange with the message type as the routing key.
+/// Listener consumes from the queue bound to the exchange by `bind` keys (all messages by default) - the queue is
+/// durable when it's named, otherwise the exclusive queue is created for the listener.
+/// Message is acknowledged after it's handled and rejected (without requeue) when it's rejected by a layer.
+pub struct AmqpClientAsync {
+    // the connection is closed when it's dropped
+    _connection: Connection,
+    channel: Channel,
+    exchange: String,
+    queue: String,
+    binding_keys: Vec<String>,
+    prefetch: u16,
+}
+
+impl AmqpClientAsync {
+    pub async fn new(uri: &str, exchange: String) -> AmqpClientAsync {
+        let connection = Connection::connect(uri, ConnectionProperties::default())
+            .await
+            .unwrap();
+        let channel = connection.create_channel().await.unwrap();
+        channel
+            .exchange_declare(
+                &exchange,
+                ExchangeKind::Topic,
+                ExchangeDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .unwrap();
+        // published messages are confirmed by the broker
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await
+            .unwrap();
+        AmqpClientAsync {
+            _connection: connection,
+            channel,
+            exchange,
+            queue: String::new(),
+            binding_keys: Vec::new(),
+            prefetch: DEFAULT_PREFETCH,
+        }
+    }
+
+    /// Name of the durable queue consumed by the listener - listeners of the same queue compete for messages.
+    pub fn queue(mut self, queue: &str) -> Self {
+        self.queue = queue.to_string();
+        self
+    }
+
+    /// Bind the queue by the routing key pattern, e.g. message type name.
+    pub fn bind(mut self, binding_key: &str) -> Self {
+        self.binding_keys.push(binding_key.to_string());
+        self
+    }
+
+    /// Number of unacknowledged messages delivered to the listener at once.
+    pub fn prefetch(mut self, prefetch: u16) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for AmqpClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let options = match self.queue.is_empty() {
+            true => QueueDeclareOptions {
+                exclusive: true,
+                auto_delete: true,
+                ..Default::default()
+            },
+            false => QueueDeclareOptions {
+                durable: true,
+                ..Default::default()
+            },
+        };
+        let queue = self
+            .channel
+            .queue_declare(&self.queue, options, FieldTable::default())
+            .await
+            .map_err(to_client_error)?;
+
+        let default_keys = vec![DEFAULT_BINDING_KEY.to_string()];
+        let binding_keys = match self.binding_keys.is_empty() {
+            true => &default_keys,
+            false => &self.binding_keys,
+        };
+        for binding_key in binding_keys {
+            self.channel
+                .queue_bind(
+                    queue.name().as_str(),
+                    &self.exchange,
+                    binding_key,
+                    QueueBindOptions::default(),
+                    FieldTable::default(),
+                )
+                .await
+                .map_err(to_client_error)?;
+        }
+
+        self.channel
+            .basic_qos(self.prefetch, BasicQosOptions::default())
+            .await
+            .map_err(to_client_error)?;
+        let mut consumer = self
+            .channel
+            .basic_consume(
+                queue.name().as_str(),
+                "",
+                BasicConsumeOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .map_err(to_client_error)?;
+
+        while let Some(delivery) = consumer.next().await {
+            let delivery = delivery.map_err(to_client_error)?;
+            match recv_callback(to_raw_message(&delivery)).await {
+                Ok(()) => delivery.acker.ack(BasicAckOptions::default()).await,
+                Err(_) => {
+                    delivery
+                        .acker
+                        .nack(BasicNackOptions {
+                            requeue: false,
+                            ..Default::default()
+                        })
+                        .await
+                }
+            }
+            .map_err(to_client_error)?;
+        }
+        Err(ClientError::IO("connection is closed".to_string()))
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let mut headers = FieldTable::default();
+        for (key, value) in &msg.headers {
+            headers.insert(
+                key.clone().into(),
+                AMQPValue::LongString(value.clone().into()),
+            );
+        }
+        let properties = BasicProperties::default()
+            .with_type(msg.msg_type.clone().into())
+            .with_content_type("application/json".into())
+            .with_delivery_mode(PERSISTENT_DELIVERY_MODE)
+            .with_headers(headers);
+
+        let confirmation = self
+            .channel
+            .basic_publish(
+                &self.exchange,
+                &msg.msg_type,
+                BasicPublishOptions::default(),
+                msg.payload.as_bytes(),
+                properties,
+            )
+            .await
+            .map_err(to_client_error)?
+            .await
+            .map_err(to_client_error)?;
+        if confirmation.is_nack() {
+            return Err(ClientError::General(
+                "message is rejected by the broker".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Headers which are not strings (e.g. set by the broker) are skipped. Routing key is used as the type when
+/// the message has no type property.
+fn to_raw_message(delivery: &Delivery) -> RawMessage {
+    let msg_type = match delivery.properties.kind() {
+        Some(kind) => kind.to_string(),
+        None => delivery.routing_key.to_string(),
+    };
+    let mut headers = HashMap::new();
+    if let Some(table) = delivery.properties.headers() {
+        for (key, value) in table {
+            let value = match value {
+                AMQPValue::LongString(value) => value.to_string(),
+                AMQPValue::ShortString(value) => value.to_string(),
+                _ => continue,
+            };
+            headers.insert(key.to_string(), value);
+        }
+    }
+    RawMessage {
+        msg_type,
+        headers,
+        payload: String::from_utf8_lossy(&delivery.data).to_string(),
+    }
+}
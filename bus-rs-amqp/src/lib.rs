@@ -0,0 +1,13 @@
+mod client_async;
+
+pub use client_async::AmqpClientAsync;
+
+pub(crate) fn to_client_error(e: lapin::Error) -> bus_rs::ClientError {
+    match e {
+        lapin::Error::IOError(_)
+        | lapin::Error::InvalidConnectionState(_)
+        | lapin::Error::InvalidChannelState(_)
+        | lapin::Error::MissingHeartbeatError => bus_rs::ClientError::IO(e.to_string()),
+        _ => bus_rs::ClientError::General(e.to_string()),
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use bus_rs::RawMessage;
+use tokio::sync::mpsc;
+
+use crate::{
+    protocol::{is_valid_channel, ChannelKind, Frame},
+    store::{self, QueueStore},
+};
+
+pub(crate) type ConnectionId = u64;
+/// Frames to be written to the connection.
+pub(crate) type Outgoing = mpsc::UnboundedSender<Frame>;
+
+/// State of the channels shared by all connections - it's changed only by short operations, so one mutex is enough.
+/// Appends to the queue files are done under the lock to keep them in the order of the state changes, so the methods
+/// block and are called from the blocking pool by the server.
+#[derive(Clone)]
+pub(crate) struct Broker {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    data_dir: Option<PathBuf>,
+    next_connection: ConnectionId,
+    next_id: u64,
+    topics: HashMap<String, Vec<Subscriber>>,
+    queues: HashMap<String, Queue>,
+}
+
+struct Subscriber {
+    connection: ConnectionId,
+    outgoing: Outgoing,
+}
+
+/// Every consumer has at most one message in flight, so the next one is delivered after the previous one is settled.
+struct Consumer {
+    connection: ConnectionId,
+    outgoing: Outgoing,
+    in_flight: Option<(u64, RawMessage)>,
+}
+
+#[derive(Default)]
+struct Queue {
+    pending: VecDeque<(u64, RawMessage)>,
+    consumers: VecDeque<Consumer>,
+    store: Option<QueueStore>,
+}
+
+impl Broker {
+    /// Queues persisted in `data_dir` are restored.
+    pub(crate) fn open(data_dir: Option<PathBuf>) -> io::Result<Broker> {
+        let mut queues = HashMap::new();
+        let mut next_id = 1;
+        if let Some(dir) = &data_dir {
+            for name in store::stored_queues(dir)? {
+                let (store, messages) = QueueStore::open(dir, &name)?;
+                if let Some((id, _)) = messages.last() {
+                    next_id = next_id.max(id + 1);
+                }
+                let queue = Queue {
+                    pending: messages.into(),
+                    store: Some(store),
+                    ..Default::default()
+                };
+                queues.insert(name, queue);
+            }
+        }
+        Ok(Broker {
+            state: Arc::new(Mutex::new(State {
+                data_dir,
+                next_connection: 1,
+                next_id,
+                topics: HashMap::new(),
+                queues,
+            })),
+        })
+    }
+
+    pub(crate) fn connect(&self) -> ConnectionId {
+        let mut state = self.state.lock().unwrap();
+        let connection = state.next_connection;
+        state.next_connection += 1;
+        connection
+    }
+
+    /// Handle the frame received from the connection, responses are sent to `outgoing`.
+    pub(crate) fn handle(&self, connection: ConnectionId, outgoing: &Outgoing, frame: Frame) {
+        let mut state = self.state.lock().unwrap();
+        let response = match frame {
+            Frame::Publish {
+                kind,
+                channel,
+                message,
+            } => state.publish(kind, channel, message),
+            Frame::Subscribe { kind, channel } => {
+                state.subscribe(connection, outgoing, kind, channel);
+                return;
+            }
+            Frame::Ack { channel, id } | Frame::Nack { channel, id } => {
+                state.settle(connection, &channel, id);
+                return;
+            }
+            Frame::Deliver { .. } | Frame::Ok | Frame::Error { .. } => {
+                Err("unexpected frame".to_string())
+            }
+        };
+        let _ = outgoing.send(match response {
+            Ok(()) => Frame::Ok,
+            Err(reason) => Frame::Error { reason },
+        });
+    }
+
+    /// Subscriptions of the closed connection are removed and its messages in flight are delivered again.
+    pub(crate) fn disconnect(&self, connection: ConnectionId) {
+        let mut state = self.state.lock().unwrap();
+        for subscribers in state.topics.values_mut() {
+            subscribers.retain(|s| s.connection != connection);
+        }
+        for (name, queue) in state.queues.iter_mut() {
+            let mut requeued = Vec::new();
+            queue.consumers.retain_mut(|c| {
+                if c.connection != connection {
+                    return true;
+                }
+                requeued.extend(c.in_flight.take());
+                false
+            });
+            for message in requeued {
+                queue.pending.push_front(message);
+            }
+            queue.dispatch(name);
+        }
+    }
+}
+
+impl State {
+    fn publish(
+        &mut self,
+        kind: ChannelKind,
+        channel: String,
+        message: RawMessage,
+    ) -> Result<(), String> {
+        if !is_valid_channel(&channel) {
+            return Err(format!("invalid channel name {}", channel));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        match kind {
+            // topic messages are only for the current subscribers
+            ChannelKind::Topic => {
+                if let Some(subscribers) = self.topics.get_mut(&channel) {
+                    subscribers.retain(|s| {
+                        s.outgoing
+                            .send(Frame::Deliver {
+                                kind,
+                                channel: channel.clone(),
+                                id,
+                                message: message.clone(),
+                            })
+                            .is_ok()
+                    });
+                }
+            }
+            ChannelKind::Queue => {
+                let queue = self.queue(&channel)?;
+                if let Some(store) = &mut queue.store {
+                    store.push(id, &message).map_err(|e| e.to_string())?;
+                }
+                queue.pending.push_back((id, message));
+                queue.dispatch(&channel);
+            }
+        }
+        Ok(())
+    }
+
+    fn subscribe(
+        &mut self,
+        connection: ConnectionId,
+        outgoing: &Outgoing,
+        kind: ChannelKind,
+        channel: String,
+    ) {
+        if !is_valid_channel(&channel) {
+            let _ = outgoing.send(Frame::Error {
+                reason: format!("invalid channel name {}", channel),
+            });
+            return;
+        }
+        match kind {
+            ChannelKind::Topic => {
+                let _ = outgoing.send(Frame::Ok);
+                self.topics.entry(channel).or_default().push(Subscriber {
+                    connection,
+                    outgoing: outgoing.clone(),
+                });
+            }
+            ChannelKind::Queue => {
+                let queue = match self.queue(&channel) {
+                    Ok(queue) => queue,
+                    Err(reason) => {
+                        let _ = outgoing.send(Frame::Error { reason });
+                        return;
+                    }
+                };
+                // response goes before the first delivery
+                let _ = outgoing.send(Frame::Ok);
+                queue.consumers.push_back(Consumer {
+                    connection,
+                    outgoing: outgoing.clone(),
+                    in_flight: None,
+                });
+                queue.dispatch(&channel);
+            }
+        }
+    }
+
+    /// Acked and nacked messages are removed - nacked message is rejected by the consumer, so it's not delivered again.
+    fn settle(&mut self, connection: ConnectionId, channel: &str, id: u64) {
+        let Some(queue) = self.queues.get_mut(channel) else {
+            return;
+        };
+        let Some(consumer) = queue.consumers.iter_mut().find(|c| {
+            c.connection == connection
+                && matches!(c.in_flight, Some((in_flight, _)) if in_flight == id)
+        }) else {
+            return;
+        };
+        consumer.in_flight = None;
+        if let Some(store) = &mut queue.store {
+            let idle =
+                queue.pending.is_empty() && queue.consumers.iter().all(|c| c.in_flight.is_none());
+            let _ = if idle { store.clear() } else { store.done(id) };
+        }
+        queue.dispatch(channel);
+    }
+
+    fn queue(&mut self, channel: &str) -> Result<&mut Queue, String> {
+        if !self.queues.contains_key(channel) {
+            let mut queue = Queue::default();
+            if let Some(dir) = &self.data_dir {
+                let (store, _) = QueueStore::open(dir, channel).map_err(|e| e.to_string())?;
+                queue.store = Some(store);
+            }
+            self.queues.insert(channel.to_string(), queue);
+        }
+        Ok(self.queues.get_mut(channel).unwrap())
+    }
+}
+
+impl Queue {
+    /// Deliver pending messages to idle consumers, in round-robin order.
+    fn dispatch(&mut self, channel: &str) {
+        while !self.pending.is_empty() {
+            let Some(position) = self.consumers.iter().position(|c| c.in_flight.is_none()) else {
+                return;
+            };
+            let mut consumer = self.consumers.remove(position).unwrap();
+            let (id, message) = self.pending.pop_front().unwrap();
+            let deliver = Frame::Deliver {
+                kind: ChannelKind::Queue,
+                channel: channel.to_string(),
+                id,
+                message: message.clone(),
+            };
+            if consumer.outgoing.send(deliver).is_err() {
+                // connection is closed - the consumer is dropped
+                self.pending.push_front((id, message));
+                continue;
+            }
+            consumer.in_flight = Some((id, message));
+            self.consumers.push_back(consumer);
+        }
+    }
+}
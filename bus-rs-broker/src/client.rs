@@ -0,0 +1,129 @@
+use std::{io, net::TcpStream};
+
+use bus_rs::{ClientError, RawMessage};
+use bus_rs_socket::frame::{read_frame, write_frame};
+
+use crate::{
+    closed_by_broker,
+    protocol::{ChannelKind, Frame},
+    to_client_error, RequestError,
+};
+
+/// Client of the bus-rs broker (`BrokerServer`), connected over TCP. Publisher waits until the broker accepts the message.
+/// Listener of the queue acks the message after it's handled and nacks the message rejected by a layer,
+/// messages not settled before the connection is lost are delivered again.
+pub struct BrokerClient {
+    addr: String,
+    channel: String,
+    kind: ChannelKind,
+    stream: Option<TcpStream>,
+}
+
+impl BrokerClient {
+    pub fn new(addr: &str, channel: String) -> BrokerClient {
+        BrokerClient {
+            addr: addr.to_string(),
+            channel,
+            kind: ChannelKind::Topic,
+            stream: None,
+        }
+    }
+
+    /// Kind of the channel. Default is `ChannelKind::Topic`.
+    pub fn kind(mut self, kind: ChannelKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    fn request(&mut self, frame: &Frame) -> Result<Frame, RequestError> {
+        // the broker could be restarted - closed connection is replaced before the frame is written
+        if self.stream.as_ref().is_some_and(is_closed) {
+            self.stream = None;
+        }
+        if self.stream.is_none() {
+            self.stream = Some(TcpStream::connect(&self.addr).map_err(RequestError::NotSent)?);
+        }
+        let stream = self.stream.as_mut().unwrap();
+        let result = match write_frame(stream, frame) {
+            Ok(()) => read_frame(stream).map_err(RequestError::Sent),
+            Err(e) => Err(RequestError::NotSent(e)),
+        };
+        match result {
+            Ok(Some(response)) => Ok(response),
+            Ok(None) => {
+                self.stream = None;
+                Err(RequestError::Sent(closed_by_broker()))
+            }
+            Err(e) => {
+                self.stream = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Broker doesn't send anything to the idle connection of the publisher, so readable connection is closed.
+fn is_closed(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return true;
+    }
+    let mut buf = [0; 1];
+    let readable =
+        !matches!(stream.peek(&mut buf), Err(e) if e.kind() == io::ErrorKind::WouldBlock);
+    stream.set_nonblocking(false).is_err() || readable
+}
+
+impl bus_rs::Client for BrokerClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let mut stream = TcpStream::connect(&self.addr).map_err(to_client_error)?;
+        let subscribe = Frame::Subscribe {
+            kind: self.kind,
+            channel: self.channel.clone(),
+        };
+        write_frame(&mut stream, &subscribe).map_err(to_client_error)?;
+
+        loop {
+            let frame = read_frame(&mut stream).map_err(to_client_error)?;
+            match frame {
+                Some(Frame::Deliver {
+                    kind,
+                    channel,
+                    id,
+                    message,
+                }) => {
+                    let result = recv_callback(message);
+                    if kind == ChannelKind::Queue {
+                        let settle = match result {
+                            Ok(()) => Frame::Ack { channel, id },
+                            Err(_) => Frame::Nack { channel, id },
+                        };
+                        write_frame(&mut stream, &settle).map_err(to_client_error)?;
+                    }
+                }
+                Some(Frame::Error { reason }) => return Err(ClientError::General(reason)),
+                Some(_) => {}
+                None => return Err(to_client_error(closed_by_broker())),
+            }
+        }
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let publish = Frame::Publish {
+            kind: self.kind,
+            channel: self.channel.clone(),
+            message: msg.clone(),
+        };
+        let response = match self.request(&publish) {
+            Err(RequestError::NotSent(_)) => self.request(&publish),
+            result => result,
+        }?;
+        match response {
+            Frame::Ok => Ok(()),
+            Frame::Error { reason } => Err(ClientError::General(reason)),
+            _ => Err(ClientError::General("unexpected frame".to_string())),
+        }
+    }
+}
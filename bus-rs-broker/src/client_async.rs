@@ -0,0 +1,136 @@
+use std::{io, sync::Arc};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use bus_rs_socket::frame::{read_frame_async, write_frame_async};
+use tokio::net::TcpStream;
+
+use crate::{
+    closed_by_broker,
+    protocol::{ChannelKind, Frame},
+    to_client_error, RequestError,
+};
+
+/// Async version of `BrokerClient`.
+pub struct BrokerClientAsync {
+    addr: String,
+    channel: String,
+    kind: ChannelKind,
+    stream: Option<TcpStream>,
+}
+
+impl BrokerClientAsync {
+    pub fn new(addr: &str, channel: String) -> BrokerClientAsync {
+        BrokerClientAsync {
+            addr: addr.to_string(),
+            channel,
+            kind: ChannelKind::Topic,
+            stream: None,
+        }
+    }
+
+    /// Kind of the channel. Default is `ChannelKind::Topic`.
+    pub fn kind(mut self, kind: ChannelKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    async fn request(&mut self, frame: &Frame) -> Result<Frame, RequestError> {
+        // the broker could be restarted - closed connection is replaced before the frame is written
+        if self.stream.as_ref().is_some_and(is_closed) {
+            self.stream = None;
+        }
+        if self.stream.is_none() {
+            let stream = TcpStream::connect(&self.addr)
+                .await
+                .map_err(RequestError::NotSent)?;
+            self.stream = Some(stream);
+        }
+        let stream = self.stream.as_mut().unwrap();
+        let result = match write_frame_async(stream, frame).await {
+            Ok(()) => read_frame_async(stream).await.map_err(RequestError::Sent),
+            Err(e) => Err(RequestError::NotSent(e)),
+        };
+        match result {
+            Ok(Some(response)) => Ok(response),
+            Ok(None) => {
+                self.stream = None;
+                Err(RequestError::Sent(closed_by_broker()))
+            }
+            Err(e) => {
+                self.stream = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Broker doesn't send anything to the idle connection of the publisher, so readable connection is closed.
+fn is_closed(stream: &TcpStream) -> bool {
+    let mut buf = [0; 1];
+    !matches!(stream.try_read(&mut buf), Err(e) if e.kind() == io::ErrorKind::WouldBlock)
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for BrokerClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let mut stream = TcpStream::connect(&self.addr)
+            .await
+            .map_err(to_client_error)?;
+        let subscribe = Frame::Subscribe {
+            kind: self.kind,
+            channel: self.channel.clone(),
+        };
+        write_frame_async(&mut stream, &subscribe)
+            .await
+            .map_err(to_client_error)?;
+
+        loop {
+            let frame = read_frame_async(&mut stream)
+                .await
+                .map_err(to_client_error)?;
+            match frame {
+                Some(Frame::Deliver {
+                    kind,
+                    channel,
+                    id,
+                    message,
+                }) => {
+                    let result = recv_callback(message).await;
+                    if kind == ChannelKind::Queue {
+                        let settle = match result {
+                            Ok(()) => Frame::Ack { channel, id },
+                            Err(_) => Frame::Nack { channel, id },
+                        };
+                        write_frame_async(&mut stream, &settle)
+                            .await
+                            .map_err(to_client_error)?;
+                    }
+                }
+                Some(Frame::Error { reason }) => return Err(ClientError::General(reason)),
+                Some(_) => {}
+                None => return Err(to_client_error(closed_by_broker())),
+            }
+        }
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let publish = Frame::Publish {
+            kind: self.kind,
+            channel: self.channel.clone(),
+            message: msg.clone(),
+        };
+        let response = match self.request(&publish).await {
+            Err(RequestError::NotSent(_)) => self.request(&publish).await,
+            result => result,
+        }?;
+        match response {
+            Frame::Ok => Ok(()),
+            Frame::Error { reason } => Err(ClientError::General(reason)),
+            _ => Err(ClientError::General("unexpected frame".to_string())),
+        }
+    }
+}
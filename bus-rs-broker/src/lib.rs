@@ -0,0 +1,37 @@
+mod broker;
+mod client;
+mod client_async;
+mod protocol;
+mod server;
+mod store;
+
+pub use client::BrokerClient;
+pub use client_async::BrokerClientAsync;
+pub use protocol::ChannelKind;
+pub use server::BrokerServer;
+
+pub(crate) fn to_client_error(e: std::io::Error) -> bus_rs::ClientError {
+    bus_rs::ClientError::IO(e.to_string())
+}
+
+/// Error of the request. Publish can be sent again only when its frame wasn't written -
+/// the written one could be already accepted by the broker.
+pub(crate) enum RequestError {
+    NotSent(std::io::Error),
+    Sent(std::io::Error),
+}
+
+impl From<RequestError> for bus_rs::ClientError {
+    fn from(value: RequestError) -> Self {
+        match value {
+            RequestError::NotSent(e) | RequestError::Sent(e) => to_client_error(e),
+        }
+    }
+}
+
+pub(crate) fn closed_by_broker() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::ConnectionAborted,
+        "connection closed by the broker",
+    )
+}
@@ -0,0 +1,33 @@
+use bus_rs_broker::BrokerServer;
+use clap::Parser;
+
+/// Broker hosting bus-rs topics and queues over TCP and WebSocket.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Address of the TCP listener
+    #[arg(long, default_value = "0.0.0.0:7070")]
+    tcp: String,
+    /// Address of the WebSocket listener, WebSocket is disabled when it's not set
+    #[arg(long)]
+    ws: Option<String>,
+    /// Directory where queues are persisted, queues are kept only in memory when it's not set
+    #[arg(long)]
+    data_dir: Option<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    let mut server = BrokerServer::new().tcp(&args.tcp);
+    if let Some(addr) = &args.ws {
+        server = server.ws(addr);
+    }
+    if let Some(dir) = &args.data_dir {
+        server = server.data_dir(dir);
+    }
+    if let Err(e) = server.run().await {
+        eprintln!("broker failed: {}", e);
+        std::process::exit(1);
+    }
+}
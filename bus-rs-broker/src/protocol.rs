@@ -0,0 +1,59 @@
+use std::io;
+
+use bus_rs::RawMessage;
+use serde::{Deserialize, Serialize};
+
+/// Topic delivers every message to all its subscribers, queue delivers every message to one of its consumers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelKind {
+    Topic,
+    Queue,
+}
+
+/// Frames of the wire protocol - see `protocol.md` of the crate.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub(crate) enum Frame {
+    Publish {
+        kind: ChannelKind,
+        channel: String,
+        message: RawMessage,
+    },
+    Subscribe {
+        kind: ChannelKind,
+        channel: String,
+    },
+    Ack {
+        channel: String,
+        id: u64,
+    },
+    Nack {
+        channel: String,
+        id: u64,
+    },
+    Deliver {
+        kind: ChannelKind,
+        channel: String,
+        id: u64,
+        message: RawMessage,
+    },
+    Ok,
+    Error {
+        reason: String,
+    },
+}
+
+/// Channel names are used as file names of the persisted queues.
+pub(crate) fn is_valid_channel(channel: &str) -> bool {
+    !channel.is_empty()
+        && !channel.starts_with('.')
+        && channel
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Frame of the WebSocket message - TCP frames are read by `bus_rs_socket::frame`, with the same JSON body.
+pub(crate) fn decode(body: &[u8]) -> io::Result<Frame> {
+    serde_json::from_slice(body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,160 @@
+use std::{io, path::PathBuf};
+
+use bus_rs_socket::frame::{read_frame_async, write_frame_async};
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    broker::{Broker, ConnectionId, Outgoing},
+    protocol::{self, Frame},
+};
+
+/// Broker hosting named topics and queues. TCP clients use length-prefixed frames, WebSocket clients send one frame
+/// per text message - see `protocol.md` of the crate.
+#[derive(Default)]
+pub struct BrokerServer {
+    tcp: Option<String>,
+    ws: Option<String>,
+    data_dir: Option<PathBuf>,
+}
+
+impl BrokerServer {
+    pub fn new() -> BrokerServer {
+        BrokerServer::default()
+    }
+
+    /// Address of the TCP listener, e.g. `0.0.0.0:7070`.
+    pub fn tcp(mut self, addr: &str) -> Self {
+        self.tcp = Some(addr.to_string());
+        self
+    }
+
+    /// Address of the WebSocket listener, e.g. `0.0.0.0:7071`.
+    pub fn ws(mut self, addr: &str) -> Self {
+        self.ws = Some(addr.to_string());
+        self
+    }
+
+    /// Directory where messages of the queues are persisted. Without it, queues are kept only in memory.
+    /// Topics are never persisted - their messages are delivered only to the current subscribers.
+    pub fn data_dir(mut self, dir: &str) -> Self {
+        self.data_dir = Some(PathBuf::from(dir));
+        self
+    }
+
+    /// Bind the listeners and serve connections until the error of the listener.
+    pub async fn run(self) -> io::Result<()> {
+        // queue files are compacted when they're opened
+        let data_dir = self.data_dir;
+        let broker = tokio::task::spawn_blocking(move || Broker::open(data_dir))
+            .await
+            .unwrap()?;
+        let tcp = match &self.tcp {
+            Some(addr) => Some(TcpListener::bind(addr).await?),
+            None => None,
+        };
+        let ws = match &self.ws {
+            Some(addr) => Some(TcpListener::bind(addr).await?),
+            None => None,
+        };
+
+        let tcp_broker = broker.clone();
+        let tcp = async move {
+            match tcp {
+                Some(listener) => loop {
+                    let (stream, _) = listener.accept().await?;
+                    tokio::spawn(tcp_connection(stream, tcp_broker.clone()));
+                },
+                None => std::future::pending().await,
+            }
+        };
+        let ws = async move {
+            match ws {
+                Some(listener) => loop {
+                    let (stream, _) = listener.accept().await?;
+                    tokio::spawn(ws_connection(stream, broker.clone()));
+                },
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            result = tcp => result,
+            result = ws => result,
+        }
+    }
+}
+
+/// Connection is closed on the invalid frame.
+async fn tcp_connection(stream: TcpStream, broker: Broker) {
+    let (mut reader, mut writer) = stream.into_split();
+    let (outgoing, mut frames) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(frame) = frames.recv().await {
+            if write_frame_async(&mut writer, &frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let connection = broker.connect();
+    while let Ok(Some(frame)) = read_frame_async(&mut reader).await {
+        handle(&broker, connection, &outgoing, frame).await;
+    }
+    disconnect(&broker, connection).await;
+}
+
+/// Connection is closed on the invalid frame.
+async fn ws_connection(stream: TcpStream, broker: Broker) {
+    let Ok(stream) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let (mut writer, mut reader) = stream.split();
+    let (outgoing, mut frames) = mpsc::unbounded_channel::<Frame>();
+    tokio::spawn(async move {
+        while let Some(frame) = frames.recv().await {
+            let Ok(text) = serde_json::to_string(&frame) else {
+                continue;
+            };
+            if writer.send(Message::Text(text)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let connection = broker.connect();
+    while let Some(Ok(message)) = reader.next().await {
+        let body = match message {
+            Message::Text(text) => text.into_bytes(),
+            Message::Binary(body) => body,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let Ok(frame) = protocol::decode(&body) else {
+            break;
+        };
+        handle(&broker, connection, &outgoing, frame).await;
+    }
+    disconnect(&broker, connection).await;
+}
+
+/// Queue files are written while the broker state is locked, so frames are handled on the blocking pool -
+/// async workers serving other connections are not blocked by the disk or by the lock. The frame is awaited,
+/// so frames of one connection are still handled in order.
+async fn handle(broker: &Broker, connection: ConnectionId, outgoing: &Outgoing, frame: Frame) {
+    let broker = broker.clone();
+    let outgoing = outgoing.clone();
+    tokio::task::spawn_blocking(move || broker.handle(connection, &outgoing, frame))
+        .await
+        .unwrap();
+}
+
+async fn disconnect(broker: &Broker, connection: ConnectionId) {
+    let broker = broker.clone();
+    tokio::task::spawn_blocking(move || broker.disconnect(connection))
+        .await
+        .unwrap();
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use bus_rs::RawMessage;
+use serde::{Deserialize, Serialize};
+
+const QUEUE_EXTENSION: &str = "log";
+
+/// One line of the queue file.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Record {
+    Push { id: u64, message: RawMessage },
+    Done { id: u64 },
+}
+
+/// Messages of the queue which are not settled yet - pushed messages and settled ids are appended to `<dir>/<queue>.log`.
+/// The file is compacted when the queue is opened and truncated when the queue becomes empty.
+pub(crate) struct QueueStore {
+    file: File,
+}
+
+impl QueueStore {
+    /// Open the queue file and return messages which were not settled, in the order they were pushed.
+    pub(crate) fn open(
+        dir: &Path,
+        queue: &str,
+    ) -> io::Result<(QueueStore, Vec<(u64, RawMessage)>)> {
+        fs::create_dir_all(dir)?;
+        let path = queue_path(dir, queue);
+        let messages = match File::open(&path) {
+            Ok(file) => read_pending(file)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        // only pending messages are kept, so the file doesn't grow across restarts
+        let temp_path = path.with_extension("tmp");
+        let mut temp = File::create(&temp_path)?;
+        for (id, message) in &messages {
+            write_record(
+                &mut temp,
+                &Record::Push {
+                    id: *id,
+                    message: message.clone(),
+                },
+            )?;
+        }
+        temp.sync_all()?;
+        fs::rename(temp_path, &path)?;
+
+        let file = OpenOptions::new().append(true).open(path)?;
+        Ok((QueueStore { file }, messages))
+    }
+
+    pub(crate) fn push(&mut self, id: u64, message: &RawMessage) -> io::Result<()> {
+        write_record(
+            &mut self.file,
+            &Record::Push {
+                id,
+                message: message.clone(),
+            },
+        )
+    }
+
+    pub(crate) fn done(&mut self, id: u64) -> io::Result<()> {
+        write_record(&mut self.file, &Record::Done { id })
+    }
+
+    /// Drop all records - called when nothing is pending or in flight.
+    pub(crate) fn clear(&mut self) -> io::Result<()> {
+        self.file.set_len(0)
+    }
+}
+
+/// Names of the queues persisted in the directory.
+pub(crate) fn stored_queues(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut queues = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(QUEUE_EXTENSION) {
+            continue;
+        }
+        if let Some(queue) = path.file_stem().and_then(|s| s.to_str()) {
+            queues.push(queue.to_string());
+        }
+    }
+    Ok(queues)
+}
+
+fn queue_path(dir: &Path, queue: &str) -> PathBuf {
+    dir.join(format!("{}.{}", queue, QUEUE_EXTENSION))
+}
+
+fn read_pending(file: File) -> io::Result<Vec<(u64, RawMessage)>> {
+    let mut messages = BTreeMap::new();
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        // the record written only partially (e.g. the process crashed) is dropped
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+        match serde_json::from_str(&line)? {
+            Record::Push { id, message } => {
+                messages.insert(id, message);
+            }
+            Record::Done { id } => {
+                messages.remove(&id);
+            }
+        }
+    }
+    Ok(messages.into_iter().collect())
+}
+
+fn write_record(file: &mut File, record: &Record) -> io::Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
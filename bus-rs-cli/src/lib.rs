@@ -0,0 +1,7 @@
+mod output;
+mod stats;
+mod transport;
+
+pub use output::{parse_headers, pretty, Filter};
+pub use stats::Stats;
+pub use transport::{Mode, Transport, TransportArgs};
@@ -0,0 +1,146 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bus_rs::{
+    builder::{self, Builder},
+    publisher_async::PublisherAsync,
+    ClientCallbackFnAsync, RawMessage,
+};
+use bus_rs_cli::{parse_headers, pretty, Filter, Mode, Stats, TransportArgs};
+use clap::{Parser, Subcommand};
+
+/// Publish, tail and inspect bus-rs traffic.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(flatten)]
+    transport: TransportArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Publish a message
+    Publish {
+        /// Type of the message
+        #[arg(long = "type")]
+        msg_type: String,
+        /// JSON payload of the message
+        #[arg(long)]
+        payload: String,
+        /// Header of the message as name=value, can be repeated
+        #[arg(long = "header")]
+        headers: Vec<String>,
+    },
+    /// Print received messages
+    Tail {
+        /// Print only messages of the type, can be repeated
+        #[arg(long = "type")]
+        msg_types: Vec<String>,
+        /// Print only messages with the header as name=value, can be repeated
+        #[arg(long = "header")]
+        headers: Vec<String>,
+        /// Print every message on one line
+        #[arg(long)]
+        compact: bool,
+    },
+    /// Print numbers and rates of received messages per type
+    Stats {
+        /// Seconds between reports
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    match cli.command {
+        Command::Publish {
+            msg_type,
+            payload,
+            headers,
+        } => {
+            serde_json::from_str::<serde_json::Value>(&payload)
+                .map_err(|e| format!("payload is not valid JSON: {}", e))?;
+            let raw_msg = RawMessage {
+                msg_type,
+                headers: parse_headers(&headers)?,
+                payload,
+            };
+            let client = cli.transport.client(Mode::Publish).await?;
+            let publisher: PublisherAsync = builder::pubsub_async(client).build();
+            // sending returns when the transport accepted the message (e.g. MQTT broker acknowledged it),
+            // so the process doesn't exit before the message is delivered
+            publisher
+                .publish_raw(raw_msg)
+                .await
+                .map_err(|e| format!("{:?}", e))
+        }
+        Command::Tail {
+            msg_types,
+            headers,
+            compact,
+        } => {
+            let filter = Filter {
+                msg_types,
+                headers: parse_headers(&headers)?,
+            };
+            let callback: Arc<ClientCallbackFnAsync> = Arc::new(move |raw_msg: RawMessage| {
+                if filter.matches(&raw_msg) {
+                    if compact {
+                        println!("{}", serde_json::to_string(&raw_msg).unwrap());
+                    } else {
+                        println!("{}", pretty(&raw_msg));
+                    }
+                }
+                Box::pin(async { Ok(()) })
+            });
+            receive(&cli.transport, callback).await
+        }
+        Command::Stats { interval } => {
+            let stats = Arc::new(Mutex::new(Stats::new()));
+            let interval = Duration::from_secs(interval.max(1));
+            tokio::spawn({
+                let stats = stats.clone();
+                async move {
+                    let mut last_report = Instant::now();
+                    loop {
+                        tokio::time::sleep(interval).await;
+                        let lines = stats.lock().unwrap().report(last_report.elapsed());
+                        last_report = Instant::now();
+                        println!("{}\n", lines.join("\n"));
+                    }
+                }
+            });
+            let callback: Arc<ClientCallbackFnAsync> = Arc::new(move |raw_msg: RawMessage| {
+                stats.lock().unwrap().record(&raw_msg);
+                Box::pin(async { Ok(()) })
+            });
+            receive(&cli.transport, callback).await
+        }
+    }
+}
+
+/// Receive until the receiver fails or Ctrl+C is pressed, then remove the consumer of the process.
+async fn receive(
+    transport: &TransportArgs,
+    callback: Arc<ClientCallbackFnAsync>,
+) -> Result<(), String> {
+    let mut client = transport.client(Mode::Receive).await?;
+    let result = tokio::select! {
+        result = client.receiver(callback) => result.map_err(|e| format!("{:?}", e)),
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    };
+    transport.remove_consumer()?;
+    result
+}
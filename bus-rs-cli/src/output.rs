@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use bus_rs::RawMessage;
+use serde_json::{json, Value};
+
+/// Filter of the tailed messages - message matches when its type is one of `msg_types` (or they're empty)
+/// and it has all `headers`.
+#[derive(Clone, Debug, Default)]
+pub struct Filter {
+    pub msg_types: Vec<String>,
+    pub headers: HashMap<String, String>,
+}
+
+impl Filter {
+    pub fn matches(&self, raw_msg: &RawMessage) -> bool {
+        (self.msg_types.is_empty() || self.msg_types.contains(&raw_msg.msg_type))
+            && self
+                .headers
+                .iter()
+                .all(|(name, value)| raw_msg.headers.get(name) == Some(value))
+    }
+}
+
+/// Headers given as `name=value`.
+pub fn parse_headers(headers: &[String]) -> Result<HashMap<String, String>, String> {
+    headers
+        .iter()
+        .map(|header| match header.split_once('=') {
+            Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+            _ => Err(format!("invalid header {} - expected name=value", header)),
+        })
+        .collect()
+}
+
+/// Indented JSON of the message - JSON payload is embedded as the JSON value, not as the escaped string.
+pub fn pretty(raw_msg: &RawMessage) -> String {
+    let payload = serde_json::from_str::<Value>(&raw_msg.payload)
+        .unwrap_or_else(|_| Value::String(raw_msg.payload.clone()));
+    let mut headers: Vec<(&String, &String)> = raw_msg.headers.iter().collect();
+    headers.sort();
+    let headers: serde_json::Map<String, Value> = headers
+        .into_iter()
+        .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+        .collect();
+    let msg = json!({
+        "msg_type": raw_msg.msg_type,
+        "headers": headers,
+        "payload": payload,
+    });
+    serde_json::to_string_pretty(&msg).unwrap()
+}
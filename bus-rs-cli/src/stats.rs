@@ -0,0 +1,42 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use bus_rs::RawMessage;
+
+/// Numbers of received messages per type, since the last report.
+#[derive(Default)]
+pub struct Stats {
+    counts: BTreeMap<String, u64>,
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    pub fn record(&mut self, raw_msg: &RawMessage) {
+        *self.counts.entry(raw_msg.msg_type.clone()).or_default() += 1;
+    }
+
+    /// Lines with counts and rates of the message types and the total - counts are reset afterwards.
+    pub fn report(&mut self, elapsed: Duration) -> Vec<String> {
+        let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+        let total: u64 = self.counts.values().sum();
+        let mut lines: Vec<String> = self
+            .counts
+            .iter()
+            .map(|(msg_type, count)| line(msg_type, *count, seconds))
+            .collect();
+        lines.push(line("total", total, seconds));
+        self.counts.clear();
+        lines
+    }
+}
+
+fn line(name: &str, count: u64, seconds: f64) -> String {
+    format!(
+        "{:<32} {:>8} msgs {:>10.1} msg/s",
+        name,
+        count,
+        count as f64 / seconds
+    )
+}
@@ -0,0 +1,148 @@
+use bus_rs::ClientAsync;
+use bus_rs_amqp::AmqpClientAsync;
+use bus_rs_broker::{BrokerClientAsync, ChannelKind};
+use bus_rs_file::{FileLogClientAsync, StartFrom};
+use bus_rs_kafka::KafkaClientAsync;
+use bus_rs_mqtt::MqttClientAsync;
+use bus_rs_nats::{NatsClientAsync, NatsJetStreamClientAsync};
+use bus_rs_postgres::{PostgresNotifyClientAsync, PostgresQueueClientAsync};
+use bus_rs_redis::{RedisClientAsync, RedisQueueClientAsync};
+use bus_rs_socket::SocketClientAsync;
+use bus_rs_sqlite::{SqliteClientAsync, StartFrom as SqliteStartFrom};
+use bus_rs_webhook::{Endpoint, WebhookClientAsync, WebhookIngressAsync};
+use clap::{Args, ValueEnum};
+
+/// Consumer (group) of the receiving client, so the other consumers don't miss messages.
+const CONSUMER: &str = "bus-rs-cli";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Transport {
+    Redis,
+    RedisQueue,
+    PostgresNotify,
+    PostgresQueue,
+    Sqlite,
+    File,
+    Mqtt,
+    Amqp,
+    Nats,
+    NatsJetstream,
+    Kafka,
+    Socket,
+    BrokerTopic,
+    BrokerQueue,
+    Webhook,
+}
+
+/// Some transports use different clients for publishing and receiving.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Publish,
+    Receive,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct TransportArgs {
+    /// Transport of the bus
+    #[arg(long, short, value_enum)]
+    pub transport: Transport,
+    /// Address of the transport - URL, connection string, `host:port`, database file or log directory.
+    /// Webhook receives on this address
+    #[arg(long, short)]
+    pub url: String,
+    /// Channel, topic, subject, exchange or queue - path of the webhook endpoint when receiving.
+    /// Not used by socket
+    #[arg(long, short)]
+    pub channel: Option<String>,
+    /// Table of postgres-queue
+    #[arg(long, default_value = "bus_queue")]
+    pub table: String,
+    /// Stream of nats-jetstream
+    #[arg(long, default_value = "BUS")]
+    pub stream: String,
+}
+
+impl TransportArgs {
+    /// Client of the transport. Receiving from queues which have no consumer groups (redis-queue, postgres-queue,
+    /// broker-queue) takes the messages from other consumers.
+    pub async fn client(&self, mode: Mode) -> Result<Box<dyn ClientAsync + Send + Sync>, String> {
+        let url = self.url.as_str();
+        let client: Box<dyn ClientAsync + Send + Sync> = match self.transport {
+            Transport::Redis => match mode {
+                Mode::Publish => Box::new(RedisClientAsync::new_sender(url, self.channel()?).await),
+                Mode::Receive => {
+                    Box::new(RedisClientAsync::new_receiver(url, self.channel()?).await)
+                }
+            },
+            Transport::RedisQueue => {
+                Box::new(RedisQueueClientAsync::new(url, self.channel()?).await)
+            }
+            Transport::PostgresNotify => {
+                Box::new(PostgresNotifyClientAsync::new(url, self.channel()?).await)
+            }
+            Transport::PostgresQueue => {
+                Box::new(PostgresQueueClientAsync::new(url, &self.table, self.channel()?).await)
+            }
+            Transport::Sqlite => Box::new(
+                SqliteClientAsync::new(url, self.channel()?)
+                    .consumer(&sqlite_consumer())
+                    .start_from(SqliteStartFrom::Latest),
+            ),
+            Transport::File => Box::new(
+                FileLogClientAsync::new(url, self.channel()?)
+                    .consumer(CONSUMER)
+                    .start_from(StartFrom::Latest),
+            ),
+            Transport::Mqtt => {
+                let (host, port) = url
+                    .rsplit_once(':')
+                    .and_then(|(host, port)| Some((host, port.parse().ok()?)))
+                    .ok_or_else(|| format!("invalid address {} - expected host:port", url))?;
+                Box::new(MqttClientAsync::new(host, port, self.channel()?))
+            }
+            Transport::Amqp => Box::new(AmqpClientAsync::new(url, self.channel()?).await),
+            Transport::Nats => Box::new(NatsClientAsync::new(url, self.channel()?).await),
+            Transport::NatsJetstream => Box::new(
+                NatsJetStreamClientAsync::new(url, &self.stream, self.channel()?)
+                    .await
+                    .durable(CONSUMER),
+            ),
+            Transport::Kafka => {
+                Box::new(KafkaClientAsync::new(url, self.channel()?).group_id(CONSUMER))
+            }
+            Transport::Socket => Box::new(SocketClientAsync::new(url)),
+            Transport::BrokerTopic => Box::new(BrokerClientAsync::new(url, self.channel()?)),
+            Transport::BrokerQueue => {
+                Box::new(BrokerClientAsync::new(url, self.channel()?).kind(ChannelKind::Queue))
+            }
+            Transport::Webhook => match mode {
+                Mode::Publish => Box::new(WebhookClientAsync::new(vec![Endpoint::new(url)])),
+                Mode::Receive => Box::new(WebhookIngressAsync::new(url, &self.channel()?)),
+            },
+        };
+        Ok(client)
+    }
+
+    /// Remove the state left in the transport by the receiving client of this process - the cursor of sqlite,
+    /// so it doesn't hold back purging of consumed messages.
+    pub fn remove_consumer(&self) -> Result<(), String> {
+        match self.transport {
+            Transport::Sqlite => SqliteClientAsync::new(&self.url, self.channel()?)
+                .consumer(&sqlite_consumer())
+                .remove_consumer()
+                .map_err(|e| format!("{:?}", e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn channel(&self) -> Result<String, String> {
+        self.channel
+            .clone()
+            .ok_or_else(|| format!("--channel is required by {:?} transport", self.transport))
+    }
+}
+
+/// Cursor of sqlite belongs to the process, so concurrent tails don't share it.
+fn sqlite_consumer() -> String {
+    format!("{}-{}", CONSUMER, std::process::id())
+}
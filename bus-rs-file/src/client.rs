@@ -0,0 +1,108 @@
+use std::{io, path::PathBuf, slice, thread, time::Duration};
+
+use bus_rs::{ClientError, RawMessage};
+
+use crate::{
+    log::{
+        self, LogReader, LogWriter, DEFAULT_CONSUMER, DEFAULT_POLL_INTERVAL, DEFAULT_SEGMENT_SIZE,
+    },
+    to_client_error, StartFrom,
+};
+
+/// Append-only log of the channel in the local directory (`<dir>/<channel>`), split into segment files.
+/// Every consumer commits the offset of handled messages, so it continues after restart where it stopped
+/// (at-least-once delivery). Consumer without committed offset starts according to `start_from`, so the log can be
+/// replayed by a new consumer. Only one process should publish into the channel.
+pub struct FileLogClient {
+    dir: PathBuf,
+    consumer: String,
+    start_from: StartFrom,
+    poll_interval: Duration,
+    segment_size: u64,
+    writer: Option<LogWriter>,
+}
+
+impl FileLogClient {
+    pub fn new(dir: &str, channel: String) -> FileLogClient {
+        let dir = PathBuf::from(dir).join(channel);
+        log::create_dirs(&dir).unwrap();
+        FileLogClient {
+            dir,
+            consumer: DEFAULT_CONSUMER.to_string(),
+            start_from: StartFrom::Earliest,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            writer: None,
+        }
+    }
+
+    /// Name of the consumer whose offset is committed by the receiver.
+    pub fn consumer(mut self, consumer: &str) -> Self {
+        self.consumer = consumer.to_string();
+        self
+    }
+
+    /// Position where the consumer starts when it has no committed offset yet. Default is `StartFrom::Earliest`.
+    pub fn start_from(mut self, start_from: StartFrom) -> Self {
+        self.start_from = start_from;
+        self
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Size in bytes after which a new segment file is started.
+    pub fn segment_size(mut self, segment_size: u64) -> Self {
+        self.segment_size = segment_size;
+        self
+    }
+
+    fn writer(&mut self) -> io::Result<&mut LogWriter> {
+        if self.writer.is_none() {
+            self.writer = Some(LogWriter::open(&self.dir, self.segment_size)?);
+        }
+        Ok(self.writer.as_mut().unwrap())
+    }
+}
+
+impl bus_rs::Client for FileLogClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let position = log::start_position(&self.dir, &self.consumer, self.start_from)
+            .map_err(to_client_error)?;
+        let mut reader = LogReader::open(&self.dir, position);
+        loop {
+            let Some(record) = reader.next().map_err(to_client_error)? else {
+                thread::sleep(self.poll_interval);
+                continue;
+            };
+
+            let offset = record.offset;
+            // on Err the offset is committed anyway, so the record doesn't block the next ones -
+            // it stays in the file and can be read again by a new consumer
+            let _ = recv_callback(record.into());
+            log::commit_offset(&self.dir, &self.consumer, offset + 1).map_err(to_client_error)?;
+        }
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        self.writer()
+            .and_then(|writer| writer.append(slice::from_ref(msg)))
+            .map_err(to_client_error)
+    }
+
+    fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+        // batch is written at once, so the messages share its result
+        let result = self.writer().and_then(|writer| writer.append(msgs));
+        msgs.iter()
+            .map(|_| match &result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(ClientError::IO(e.to_string())),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,107 @@
+use std::{io, path::PathBuf, slice, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+
+use crate::{
+    log::{
+        self, LogReader, LogWriter, DEFAULT_CONSUMER, DEFAULT_POLL_INTERVAL, DEFAULT_SEGMENT_SIZE,
+    },
+    to_client_error, StartFrom,
+};
+
+/// Async version of `FileLogClient`. File operations are blocking - they're short writes and reads of the local files.
+pub struct FileLogClientAsync {
+    dir: PathBuf,
+    consumer: String,
+    start_from: StartFrom,
+    poll_interval: Duration,
+    segment_size: u64,
+    writer: Option<LogWriter>,
+}
+
+impl FileLogClientAsync {
+    pub fn new(dir: &str, channel: String) -> FileLogClientAsync {
+        let dir = PathBuf::from(dir).join(channel);
+        log::create_dirs(&dir).unwrap();
+        FileLogClientAsync {
+            dir,
+            consumer: DEFAULT_CONSUMER.to_string(),
+            start_from: StartFrom::Earliest,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            writer: None,
+        }
+    }
+
+    /// Name of the consumer whose offset is committed by the receiver.
+    pub fn consumer(mut self, consumer: &str) -> Self {
+        self.consumer = consumer.to_string();
+        self
+    }
+
+    /// Position where the consumer starts when it has no committed offset yet. Default is `StartFrom::Earliest`.
+    pub fn start_from(mut self, start_from: StartFrom) -> Self {
+        self.start_from = start_from;
+        self
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Size in bytes after which a new segment file is started.
+    pub fn segment_size(mut self, segment_size: u64) -> Self {
+        self.segment_size = segment_size;
+        self
+    }
+
+    fn writer(&mut self) -> io::Result<&mut LogWriter> {
+        if self.writer.is_none() {
+            self.writer = Some(LogWriter::open(&self.dir, self.segment_size)?);
+        }
+        Ok(self.writer.as_mut().unwrap())
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for FileLogClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let position = log::start_position(&self.dir, &self.consumer, self.start_from)
+            .map_err(to_client_error)?;
+        let mut reader = LogReader::open(&self.dir, position);
+        loop {
+            let Some(record) = reader.next().map_err(to_client_error)? else {
+                tokio::time::sleep(self.poll_interval).await;
+                continue;
+            };
+
+            let offset = record.offset;
+            // on Err the offset is committed anyway, so the record doesn't block the next ones -
+            // it stays in the file and can be read again by a new consumer
+            let _ = recv_callback(record.into()).await;
+            log::commit_offset(&self.dir, &self.consumer, offset + 1).map_err(to_client_error)?;
+        }
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        self.writer()
+            .and_then(|writer| writer.append(slice::from_ref(msg)))
+            .map_err(to_client_error)
+    }
+
+    async fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+        // batch is written at once, so the messages share its result
+        let result = self.writer().and_then(|writer| writer.append(msgs));
+        msgs.iter()
+            .map(|_| match &result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(ClientError::IO(e.to_string())),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,21 @@
+mod client;
+mod client_async;
+mod log;
+
+pub use client::FileLogClient;
+pub use client_async::FileLogClientAsync;
+
+pub(crate) fn to_client_error(e: std::io::Error) -> bus_rs::ClientError {
+    bus_rs::ClientError::IO(e.to_string())
+}
+
+/// Position where the consumer without committed offset starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StartFrom {
+    /// The oldest message kept in the log.
+    Earliest,
+    /// Only messages appended after the consumer is started.
+    Latest,
+    /// Message with the given offset (offsets of a channel start from 0).
+    Offset(u64),
+}
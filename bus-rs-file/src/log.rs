@@ -0,0 +1,278 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bus_rs::RawMessage;
+use serde::{Deserialize, Serialize};
+
+use crate::StartFrom;
+
+pub(crate) const DEFAULT_CONSUMER: &str = "default";
+pub(crate) const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+pub(crate) const DEFAULT_SEGMENT_SIZE: u64 = 16 * 1024 * 1024;
+const SEGMENT_EXTENSION: &str = "log";
+const CONSUMERS_DIR: &str = "consumers";
+
+/// One line of the segment file.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Record {
+    pub(crate) offset: u64,
+    /// Milliseconds since UNIX epoch, when the message was appended.
+    pub(crate) timestamp: u64,
+    pub(crate) msg_type: String,
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) payload: String,
+}
+
+impl From<Record> for RawMessage {
+    fn from(record: Record) -> Self {
+        RawMessage {
+            msg_type: record.msg_type,
+            headers: record.headers,
+            payload: record.payload,
+        }
+    }
+}
+
+/// Appends messages to the last segment of the channel - a new segment is started when the last one exceeds `segment_size`.
+pub(crate) struct LogWriter {
+    dir: PathBuf,
+    segment_size: u64,
+    next_offset: u64,
+    file: File,
+    size: u64,
+}
+
+impl LogWriter {
+    pub(crate) fn open(dir: &Path, segment_size: u64) -> io::Result<LogWriter> {
+        let (base_offset, next_offset, valid_size) = match segments(dir)?.last() {
+            Some(&base_offset) => {
+                let path = segment_path(dir, base_offset);
+                let (next_offset, valid_size) = scan_segment(&path, base_offset)?;
+                (base_offset, next_offset, valid_size)
+            }
+            None => (0, 0, 0),
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(segment_path(dir, base_offset))?;
+        // the record written only partially (e.g. the process crashed) is dropped
+        file.set_len(valid_size)?;
+        Ok(LogWriter {
+            dir: dir.to_path_buf(),
+            segment_size,
+            next_offset,
+            file,
+            size: valid_size,
+        })
+    }
+
+    pub(crate) fn append(&mut self, msgs: &[RawMessage]) -> io::Result<()> {
+        if self.size >= self.segment_size {
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(segment_path(&self.dir, self.next_offset))?;
+            self.size = 0;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let mut buffer = Vec::new();
+        for (i, msg) in msgs.iter().enumerate() {
+            let record = Record {
+                offset: self.next_offset + i as u64,
+                timestamp,
+                msg_type: msg.msg_type.clone(),
+                headers: msg.headers.clone(),
+                payload: msg.payload.clone(),
+            };
+            serde_json::to_writer(&mut buffer, &record)?;
+            buffer.push(b'\n');
+        }
+        // whole batch is written at once, so readers never see only a part of the record
+        self.file.write_all(&buffer)?;
+        self.next_offset += msgs.len() as u64;
+        self.size += buffer.len() as u64;
+        Ok(())
+    }
+}
+
+/// Reads records of the channel from the given offset, across the segments.
+pub(crate) struct LogReader {
+    dir: PathBuf,
+    position: u64,
+    segment: Option<Segment>,
+}
+
+struct Segment {
+    base_offset: u64,
+    reader: BufReader<File>,
+    /// Newer segment exists, so nothing is appended to this one anymore.
+    sealed: bool,
+}
+
+impl LogReader {
+    pub(crate) fn open(dir: &Path, position: u64) -> LogReader {
+        LogReader {
+            dir: dir.to_path_buf(),
+            position,
+            segment: None,
+        }
+    }
+
+    /// Next record of the log or `None` when all appended records are read.
+    pub(crate) fn next(&mut self) -> io::Result<Option<Record>> {
+        loop {
+            if self.segment.is_none() {
+                let Some(base_offset) = find_segment(&self.dir, self.position)? else {
+                    return Ok(None);
+                };
+                let file = File::open(segment_path(&self.dir, base_offset))?;
+                self.segment = Some(Segment {
+                    base_offset,
+                    reader: BufReader::new(file),
+                    sealed: false,
+                });
+            }
+            let segment = self.segment.as_mut().unwrap();
+
+            let start = segment.reader.stream_position()?;
+            let mut line = String::new();
+            let read = segment.reader.read_line(&mut line)?;
+            if read == 0 || !line.ends_with('\n') {
+                // the record is not written completely yet - read it again later
+                segment.reader.seek(SeekFrom::Start(start))?;
+                if segment.sealed {
+                    // only partially written record (e.g. the process crashed) can remain at the end
+                    let next_base = next_segment(&self.dir, segment.base_offset)?;
+                    self.position = self.position.max(next_base);
+                    self.segment = None;
+                    continue;
+                }
+                // read the rest of the segment once more, when the writer moved to the newer one
+                if next_segment(&self.dir, segment.base_offset)? > segment.base_offset {
+                    segment.sealed = true;
+                    continue;
+                }
+                return Ok(None);
+            }
+
+            let record: Record = serde_json::from_str(&line)?;
+            if record.offset < self.position {
+                continue;
+            }
+            self.position = record.offset + 1;
+            return Ok(Some(record));
+        }
+    }
+}
+
+/// Offset of the message where the consumer continues.
+pub(crate) fn start_position(dir: &Path, consumer: &str, start_from: StartFrom) -> io::Result<u64> {
+    if let Some(offset) = committed_offset(dir, consumer)? {
+        return Ok(offset);
+    }
+    match start_from {
+        StartFrom::Earliest => Ok(segments(dir)?.first().copied().unwrap_or_default()),
+        StartFrom::Latest => match segments(dir)?.last() {
+            Some(&base_offset) => {
+                scan_segment(&segment_path(dir, base_offset), base_offset).map(|(next, _)| next)
+            }
+            None => Ok(0),
+        },
+        StartFrom::Offset(offset) => Ok(offset),
+    }
+}
+
+/// Store the offset of the next message to read by the consumer.
+pub(crate) fn commit_offset(dir: &Path, consumer: &str, offset: u64) -> io::Result<()> {
+    let consumers_dir = dir.join(CONSUMERS_DIR);
+    let temp_path = consumers_dir.join(format!("{}.tmp", consumer));
+    fs::write(&temp_path, offset.to_string())?;
+    // rename is atomic, so the offset is never lost by the crash during the write
+    fs::rename(temp_path, consumers_dir.join(consumer))
+}
+
+pub(crate) fn create_dirs(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir.join(CONSUMERS_DIR))
+}
+
+fn committed_offset(dir: &Path, consumer: &str) -> io::Result<Option<u64>> {
+    match fs::read_to_string(dir.join(CONSUMERS_DIR).join(consumer)) {
+        Ok(offset) => offset
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Base offsets of the segments, in ascending order.
+fn segments(dir: &Path) -> io::Result<Vec<u64>> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+        if let Some(base_offset) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse().ok())
+        {
+            segments.push(base_offset);
+        }
+    }
+    segments.sort();
+    Ok(segments)
+}
+
+/// Segment containing the offset - the first one when older segments were removed.
+fn find_segment(dir: &Path, offset: u64) -> io::Result<Option<u64>> {
+    let segments = segments(dir)?;
+    Ok(segments
+        .iter()
+        .rev()
+        .find(|&&base| base <= offset)
+        .or(segments.first())
+        .copied())
+}
+
+fn next_segment(dir: &Path, base_offset: u64) -> io::Result<u64> {
+    Ok(segments(dir)?
+        .into_iter()
+        .find(|&base| base > base_offset)
+        .unwrap_or(base_offset))
+}
+
+fn segment_path(dir: &Path, base_offset: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", base_offset, SEGMENT_EXTENSION))
+}
+
+/// Offset after the last complete record of the segment and the size of complete records.
+fn scan_segment(path: &Path, base_offset: u64) -> io::Result<(u64, u64)> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut next_offset = base_offset;
+    let mut valid_size = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 || !line.ends_with('\n') {
+            return Ok((next_offset, valid_size));
+        }
+        let record: Record = serde_json::from_str(&line)?;
+        next_offset = record.offset + 1;
+        valid_size += read as u64;
+    }
+}
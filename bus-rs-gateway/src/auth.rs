@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+/// Message types the client may subscribe to and publish - `*` allows all of them.
+#[derive(Clone, Debug, Default)]
+pub struct Permissions {
+    pub subscribe: Vec<String>,
+    pub publish: Vec<String>,
+}
+
+impl Permissions {
+    pub fn can_subscribe(&self, msg_type: &str) -> bool {
+        allows(&self.subscribe, msg_type)
+    }
+
+    pub fn can_publish(&self, msg_type: &str) -> bool {
+        allows(&self.publish, msg_type)
+    }
+}
+
+/// Verifies the token of the connecting client - it's taken from `Authorization: Bearer <token>` header
+/// or from `token` query parameter (browsers can't set headers of WebSocket requests).
+pub trait Authenticator: Send + Sync {
+    /// Permissions of the client or `None` when the token is not valid.
+    fn authenticate(&self, token: &str) -> Option<Permissions>;
+}
+
+/// Authenticator with the fixed set of tokens.
+#[derive(Default)]
+pub struct TokenAuthenticator {
+    tokens: HashMap<String, Permissions>,
+}
+
+impl TokenAuthenticator {
+    pub fn new() -> TokenAuthenticator {
+        TokenAuthenticator::default()
+    }
+
+    pub fn token(mut self, token: &str, permissions: Permissions) -> Self {
+        self.tokens.insert(token.to_string(), permissions);
+        self
+    }
+}
+
+impl Authenticator for TokenAuthenticator {
+    fn authenticate(&self, token: &str) -> Option<Permissions> {
+        self.tokens.get(token).cloned()
+    }
+}
+
+fn allows(msg_types: &[String], msg_type: &str) -> bool {
+    msg_types.iter().any(|t| t == "*" || t == msg_type)
+}
@@ -0,0 +1,20 @@
+use bus_rs::RawMessage;
+use serde::{Deserialize, Serialize};
+
+/// Frame sent by the browser client, as JSON text message.
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub(crate) enum ClientFrame {
+    Subscribe { msg_types: Vec<String> },
+    Unsubscribe { msg_types: Vec<String> },
+    Publish { message: RawMessage },
+}
+
+/// Frame sent to the browser client - every client frame is answered by `ok` or `error`, in the order they were received.
+#[derive(Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub(crate) enum ServerFrame<'a> {
+    Ok,
+    Error { reason: String },
+    Message { message: &'a RawMessage },
+}
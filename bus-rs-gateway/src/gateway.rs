@@ -0,0 +1,198 @@
+use std::{collections::HashSet, io, sync::Arc};
+
+use bus_rs::{publisher_async::PublisherAsync, LayerError, PubSubLayer, RawMessage};
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast::{self, error::RecvError},
+};
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::StatusCode,
+    Message,
+};
+
+use crate::{
+    auth::{Authenticator, Permissions},
+    frame::{ClientFrame, ServerFrame},
+};
+
+/// Messages waiting for the slowest client - the client which falls behind more misses the oldest of them.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Gateway bridging WebSocket clients (e.g. browser dashboards) onto the bus. Authenticated clients subscribe
+/// to message types allowed by their permissions and receive matching messages of the listener with `GatewayLayer`.
+/// Messages published by the clients are republished through the publisher.
+pub struct WsGateway {
+    addr: String,
+    authenticator: Box<dyn Authenticator>,
+    publisher: Option<PublisherAsync>,
+    messages: broadcast::Sender<Arc<RawMessage>>,
+}
+
+impl WsGateway {
+    pub fn new(addr: &str, authenticator: Box<dyn Authenticator>) -> WsGateway {
+        let (messages, _) = broadcast::channel(CHANNEL_CAPACITY);
+        WsGateway {
+            addr: addr.to_string(),
+            authenticator,
+            publisher: None,
+            messages,
+        }
+    }
+
+    /// Publisher of the messages sent by the clients. Without it, clients can only subscribe.
+    pub fn publisher(mut self, publisher: PublisherAsync) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// Layer forwarding messages to the subscribed clients - it's added to the listener whose messages should be forwarded.
+    pub fn layer(&self) -> GatewayLayer {
+        GatewayLayer {
+            messages: self.messages.clone(),
+        }
+    }
+
+    /// Bind the address and serve clients until the error of the listener.
+    pub async fn run(self) -> io::Result<()> {
+        let listener = TcpListener::bind(&self.addr).await?;
+        let gateway = Arc::new(self);
+        loop {
+            let (stream, _) = listener.accept().await?;
+            tokio::spawn(connection(stream, gateway.clone()));
+        }
+    }
+
+    async fn handle(
+        &self,
+        text: &str,
+        permissions: &Permissions,
+        subscribed: &mut HashSet<String>,
+    ) -> ServerFrame<'static> {
+        let frame: ClientFrame = match serde_json::from_str(text) {
+            Ok(frame) => frame,
+            Err(e) => return error(format!("invalid frame: {}", e)),
+        };
+        match frame {
+            ClientFrame::Subscribe { msg_types } => {
+                if let Some(msg_type) = msg_types.iter().find(|t| !permissions.can_subscribe(t)) {
+                    return error(format!("not allowed to subscribe to {}", msg_type));
+                }
+                subscribed.extend(msg_types);
+                ServerFrame::Ok
+            }
+            ClientFrame::Unsubscribe { msg_types } => {
+                for msg_type in msg_types {
+                    subscribed.remove(&msg_type);
+                }
+                ServerFrame::Ok
+            }
+            ClientFrame::Publish { message } => {
+                if !permissions.can_publish(&message.msg_type) {
+                    return error(format!("not allowed to publish {}", message.msg_type));
+                }
+                let Some(publisher) = &self.publisher else {
+                    return error("publishing is not enabled".to_string());
+                };
+                match publisher.publish_raw(message).await {
+                    Ok(()) => ServerFrame::Ok,
+                    Err(e) => error(format!("{:?}", e)),
+                }
+            }
+        }
+    }
+}
+
+/// Forwards every message which went through the listener (or publisher) to the gateway.
+pub struct GatewayLayer {
+    messages: broadcast::Sender<Arc<RawMessage>>,
+}
+
+impl PubSubLayer for GatewayLayer {
+    fn before(&self, _raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        Ok(())
+    }
+
+    fn after(&self, raw_msg: &RawMessage) {
+        // error means there is no connected client
+        let _ = self.messages.send(Arc::new(raw_msg.clone()));
+    }
+}
+
+// error of the handshake callback is given by tungstenite
+#[allow(clippy::result_large_err)]
+async fn connection(stream: TcpStream, gateway: Arc<WsGateway>) {
+    let mut permissions = None;
+    let authenticate = |request: &Request, response: Response| match token(request)
+        .and_then(|token| gateway.authenticator.authenticate(token))
+    {
+        Some(granted) => {
+            permissions = Some(granted);
+            Ok(response)
+        }
+        None => {
+            let mut response = ErrorResponse::new(Some("invalid token".to_string()));
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(response)
+        }
+    };
+    let Ok(stream) = tokio_tungstenite::accept_hdr_async(stream, authenticate).await else {
+        return;
+    };
+    let Some(permissions) = permissions else {
+        return;
+    };
+
+    let (mut writer, mut reader) = stream.split();
+    let mut messages = gateway.messages.subscribe();
+    let mut subscribed = HashSet::new();
+    loop {
+        let text = tokio::select! {
+            message = reader.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let response = gateway.handle(&text, &permissions, &mut subscribed).await;
+                    to_text(&response)
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            raw_msg = messages.recv() => match raw_msg {
+                Ok(raw_msg) if subscribed.contains("*") || subscribed.contains(&raw_msg.msg_type) => {
+                    to_text(&ServerFrame::Message { message: &raw_msg })
+                }
+                Ok(_) => continue,
+                // slow client misses messages instead of holding back the others
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+        };
+        if writer.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+}
+
+fn token(request: &Request) -> Option<&str> {
+    if let Some(token) = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        return Some(token);
+    }
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|param| param.strip_prefix("token="))
+}
+
+fn error(reason: String) -> ServerFrame<'static> {
+    ServerFrame::Error { reason }
+}
+
+fn to_text(frame: &ServerFrame) -> String {
+    serde_json::to_string(frame).unwrap()
+}
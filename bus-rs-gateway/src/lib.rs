@@ -0,0 +1,6 @@
+mod auth;
+mod frame;
+mod gateway;
+
+pub use auth::{Authenticator, Permissions, TokenAuthenticator};
+pub use gateway::{GatewayLayer, WsGateway};
@@ -0,0 +1,124 @@
+use std::{sync::Mutex, time::Duration};
+
+use bus_rs::{ClientError, RawMessage};
+use rdkafka::{
+    consumer::{BaseConsumer, CommitMode, Consumer},
+    message::DeliveryResult,
+    producer::{BaseProducer, BaseRecord, Producer, ProducerContext},
+    ClientContext,
+};
+
+use crate::{
+    consumer_config, producer_config, to_client_error, to_headers, to_raw_message, PartitionKey,
+    DEFAULT_GROUP_ID,
+};
+
+const POLL_TIMEOUT: Duration = Duration::from_secs(1);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Listener joins the consumer group and commits the offset of the message after it's handled - the message rejected by
+/// a layer is not committed, its offset is committed with the next handled message.
+/// Record key is taken from the message by `partition_key`, so per-entity order is kept.
+pub struct KafkaClient {
+    brokers: String,
+    topic: String,
+    group_id: String,
+    partition_key: Option<PartitionKey>,
+    producer: BaseProducer<DeliveryContext>,
+}
+
+/// Keeps the delivery report of the sent message - it's reported while the producer is flushed.
+#[derive(Default)]
+struct DeliveryContext {
+    report: Mutex<Option<Result<(), ClientError>>>,
+}
+
+impl ClientContext for DeliveryContext {}
+
+impl ProducerContext for DeliveryContext {
+    type DeliveryOpaque = ();
+
+    fn delivery(&self, delivery_result: &DeliveryResult<'_>, _delivery_opaque: ()) {
+        let report = match delivery_result {
+            Ok(_) => Ok(()),
+            Err((e, _)) => Err(to_client_error(e.clone())),
+        };
+        *self.report.lock().unwrap() = Some(report);
+    }
+}
+
+impl KafkaClient {
+    pub fn new(brokers: &str, topic: String) -> KafkaClient {
+        KafkaClient {
+            brokers: brokers.to_string(),
+            topic,
+            group_id: DEFAULT_GROUP_ID.to_string(),
+            partition_key: None,
+            producer: producer_config(brokers)
+                .create_with_context(DeliveryContext::default())
+                .unwrap(),
+        }
+    }
+
+    /// Consumer group of the listener - listeners of the same group share partitions of the topic.
+    pub fn group_id(mut self, group_id: &str) -> Self {
+        self.group_id = group_id.to_string();
+        self
+    }
+
+    pub fn partition_key(mut self, partition_key: PartitionKey) -> Self {
+        self.partition_key = Some(partition_key);
+        self
+    }
+}
+
+impl bus_rs::Client for KafkaClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let consumer: BaseConsumer = consumer_config(&self.brokers, &self.group_id)
+            .create()
+            .map_err(to_client_error)?;
+        consumer
+            .subscribe(&[&self.topic])
+            .map_err(to_client_error)?;
+
+        loop {
+            let Some(record) = consumer.poll(POLL_TIMEOUT) else {
+                continue;
+            };
+            let record = record.map_err(to_client_error)?;
+            if recv_callback(to_raw_message(&record)).is_ok() {
+                consumer
+                    .commit_message(&record, CommitMode::Async)
+                    .map_err(to_client_error)?;
+            }
+        }
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let key = self.partition_key.as_ref().and_then(|p| p.key(msg));
+        let mut record = BaseRecord::to(&self.topic)
+            .payload(&msg.payload)
+            .headers(to_headers(msg));
+        if let Some(key) = &key {
+            record = record.key(key);
+        }
+        *self.producer.context().report.lock().unwrap() = None;
+        self.producer
+            .send(record)
+            .map_err(|(e, _)| to_client_error(e))?;
+        // wait until the message is delivered (or rejected) and return its delivery report
+        self.producer
+            .flush(DELIVERY_TIMEOUT)
+            .map_err(to_client_error)?;
+        self.producer
+            .context()
+            .report
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| Err(ClientError::IO("message was not delivered".to_string())))
+    }
+}
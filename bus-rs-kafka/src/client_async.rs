@@ -0,0 +1,86 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use rdkafka::{
+    consumer::{CommitMode, Consumer, StreamConsumer},
+    producer::{FutureProducer, FutureRecord},
+};
+
+use crate::{
+    consumer_config, producer_config, to_client_error, to_headers, to_raw_message, PartitionKey,
+    DEFAULT_GROUP_ID,
+};
+
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Async version of `KafkaClient`.
+pub struct KafkaClientAsync {
+    brokers: String,
+    topic: String,
+    group_id: String,
+    partition_key: Option<PartitionKey>,
+    producer: FutureProducer,
+}
+
+impl KafkaClientAsync {
+    pub fn new(brokers: &str, topic: String) -> KafkaClientAsync {
+        KafkaClientAsync {
+            brokers: brokers.to_string(),
+            topic,
+            group_id: DEFAULT_GROUP_ID.to_string(),
+            partition_key: None,
+            producer: producer_config(brokers).create().unwrap(),
+        }
+    }
+
+    /// Consumer group of the listener - listeners of the same group share partitions of the topic.
+    pub fn group_id(mut self, group_id: &str) -> Self {
+        self.group_id = group_id.to_string();
+        self
+    }
+
+    pub fn partition_key(mut self, partition_key: PartitionKey) -> Self {
+        self.partition_key = Some(partition_key);
+        self
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for KafkaClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let consumer: StreamConsumer = consumer_config(&self.brokers, &self.group_id)
+            .create()
+            .map_err(to_client_error)?;
+        consumer
+            .subscribe(&[&self.topic])
+            .map_err(to_client_error)?;
+
+        loop {
+            let record = consumer.recv().await.map_err(to_client_error)?;
+            if recv_callback(to_raw_message(&record)).await.is_ok() {
+                consumer
+                    .commit_message(&record, CommitMode::Async)
+                    .map_err(to_client_error)?;
+            }
+        }
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let key = self.partition_key.as_ref().and_then(|p| p.key(msg));
+        let mut record = FutureRecord::to(&self.topic)
+            .payload(&msg.payload)
+            .headers(to_headers(msg));
+        if let Some(key) = &key {
+            record = record.key(key);
+        }
+        self.producer
+            .send(record, QUEUE_TIMEOUT)
+            .await
+            .map(|_| ())
+            .map_err(|(e, _)| to_client_error(e))
+    }
+}
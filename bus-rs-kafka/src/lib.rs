@@ -0,0 +1,101 @@
+mod client;
+mod client_async;
+
+pub use client::KafkaClient;
+pub use client_async::KafkaClientAsync;
+
+use std::collections::HashMap;
+
+use bus_rs::RawMessage;
+use rdkafka::{
+    message::{Header, Headers, OwnedHeaders},
+    ClientConfig, Message,
+};
+
+/// Header with the type of the message.
+pub const MSG_TYPE_HEADER: &str = "bus-msg-type";
+pub(crate) const DEFAULT_GROUP_ID: &str = "bus-rs";
+
+/// Source of the record key - records with the same key are written into the same partition, so their order is kept.
+#[derive(Clone, Debug)]
+pub enum PartitionKey {
+    /// Field of the JSON payload, nested field is separated by dots (e.g. `customer.id`).
+    Field(String),
+    /// Header of the message.
+    Header(String),
+}
+
+impl PartitionKey {
+    pub(crate) fn key(&self, msg: &RawMessage) -> Option<String> {
+        match self {
+            PartitionKey::Field(field) => {
+                let payload: serde_json::Value = serde_json::from_str(&msg.payload).ok()?;
+                let pointer = format!("/{}", field.replace('.', "/"));
+                match payload.pointer(&pointer)? {
+                    serde_json::Value::String(value) => Some(value.clone()),
+                    serde_json::Value::Null => None,
+                    value => Some(value.to_string()),
+                }
+            }
+            PartitionKey::Header(header) => msg.headers.get(header).cloned(),
+        }
+    }
+}
+
+pub(crate) fn to_client_error(e: rdkafka::error::KafkaError) -> bus_rs::ClientError {
+    bus_rs::ClientError::IO(e.to_string())
+}
+
+pub(crate) fn producer_config(brokers: &str) -> ClientConfig {
+    let mut config = ClientConfig::new();
+    config.set("bootstrap.servers", brokers);
+    config
+}
+
+pub(crate) fn consumer_config(brokers: &str, group_id: &str) -> ClientConfig {
+    let mut config = ClientConfig::new();
+    config
+        .set("bootstrap.servers", brokers)
+        .set("group.id", group_id)
+        // offsets are committed after the message is handled
+        .set("enable.auto.commit", "false")
+        .set("auto.offset.reset", "earliest");
+    config
+}
+
+pub(crate) fn to_headers(msg: &RawMessage) -> OwnedHeaders {
+    let mut headers = OwnedHeaders::new().insert(Header {
+        key: MSG_TYPE_HEADER,
+        value: Some(&msg.msg_type),
+    });
+    for (key, value) in &msg.headers {
+        headers = headers.insert(Header {
+            key,
+            value: Some(value),
+        });
+    }
+    headers
+}
+
+/// Topic is used as the type when the record has no type header (e.g. it's produced by other client).
+pub(crate) fn to_raw_message(record: &impl Message) -> RawMessage {
+    let mut msg_type = record.topic().to_string();
+    let mut headers = HashMap::new();
+    for header in record.headers().iter().flat_map(|headers| headers.iter()) {
+        let value = String::from_utf8_lossy(header.value.unwrap_or_default()).to_string();
+        if header.key == MSG_TYPE_HEADER {
+            msg_type = value;
+        } else {
+            headers.insert(header.key.to_string(), value);
+        }
+    }
+    let payload = record
+        .payload()
+        .map(|payload| String::from_utf8_lossy(payload).to_string())
+        .unwrap_or_default();
+    RawMessage {
+        msg_type,
+        headers,
+        payload,
+    }
+}
@@ -1,16 +1,44 @@
 use proc_macro;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, parse_quote, punctuated::Punctuated, DeriveInput, Ident, Token};
 
+/// Register struct as a bus message. Use `#[message(schema)]` to derive JSON Schema
+/// of the message as well (requires `schema` feature of `bus-rs`).
 #[proc_macro_attribute]
 pub fn message(
-    _attr: proc_macro::TokenStream,
+    attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let item = parse_macro_input!(input as DeriveInput);
+    let options = parse_macro_input!(attr with Punctuated::<Ident, Token![,]>::parse_terminated);
+    let mut item = parse_macro_input!(input as DeriveInput);
     let type_name = item.clone().ident;
     let type_name_str = type_name.to_string();
 
+    let mut schema_fn = quote!();
+    for option in options {
+        match option.to_string().as_str() {
+            "schema" => {
+                item.attrs.insert(
+                    0,
+                    parse_quote!(#[derive(bus_rs::schema::schemars::JsonSchema)]),
+                );
+                item.attrs
+                    .push(parse_quote!(#[schemars(crate = "bus_rs::schema::schemars")]));
+                schema_fn = quote!(
+                    fn schema() -> Option<serde_json::Value> {
+                        let schema = bus_rs::schema::schemars::schema_for!(#type_name);
+                        Some(serde_json::to_value(schema).unwrap())
+                    }
+                );
+            }
+            _ => {
+                return syn::Error::new(option.span(), "unknown message option")
+                    .to_compile_error()
+                    .into()
+            }
+        }
+    }
+
     let output = quote!(
         #item
 
@@ -18,6 +46,8 @@ pub fn message(
             fn name() -> &'static str {
                 #type_name_str
             }
+
+            #schema_fn
         }
 
         impl Into<bus_rs::RawMessage> for #type_name {
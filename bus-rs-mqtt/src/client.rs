@@ -0,0 +1,242 @@
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use bus_rs::{ClientError, RawMessage};
+
+use crate::{
+    connection::{
+        encode_v4, encode_v5, is_topic_filter, Event, MqttConfig, Packet, Shared, RECONNECT_DELAY,
+        REQUESTS_CAPACITY,
+    },
+    to_client_error, MqttVersion, QoS,
+};
+
+/// Channel is the MQTT topic - listener can subscribe to the topic filter with `+`/`#` wildcards.
+/// The connection is opened with the first send/receive and it's reconnected in the background thread.
+/// Received messages are acknowledged after they're handled. Send returns when the message is acknowledged
+/// by the broker (PubAck for QoS 1, PubComp for QoS 2) or written to the connection (QoS 0).
+pub struct MqttClient {
+    config: MqttConfig,
+    channel: String,
+    // events receiver is not Sync - mutex is only a wrapper, the connection is used by `&mut self`
+    connection: Mutex<Option<Connection>>,
+}
+
+impl MqttClient {
+    pub fn new(host: &str, port: u16, channel: String) -> MqttClient {
+        MqttClient {
+            config: MqttConfig::new(host, port),
+            channel,
+            connection: Mutex::new(None),
+        }
+    }
+
+    /// Client id has to be unique for the broker - it's generated by default.
+    pub fn client_id(mut self, client_id: &str) -> Self {
+        self.config.client_id = client_id.to_string();
+        self
+    }
+
+    /// Protocol version, default is `MqttVersion::V311`.
+    pub fn version(mut self, version: MqttVersion) -> Self {
+        self.config.version = version;
+        self
+    }
+
+    /// QoS of published messages and of the subscription, default is `QoS::AtLeastOnce`.
+    pub fn qos(mut self, qos: QoS) -> Self {
+        self.config.qos = qos;
+        self
+    }
+
+    pub fn credentials(mut self, username: &str, password: &str) -> Self {
+        self.config.credentials = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    /// How long send waits for the acknowledgement of the message, default is 10 seconds.
+    pub fn ack_timeout(mut self, ack_timeout: Duration) -> Self {
+        self.config.ack_timeout = ack_timeout;
+        self
+    }
+
+    fn connection(&mut self) -> &mut Connection {
+        self.connection
+            .get_mut()
+            .unwrap()
+            .get_or_insert_with(|| Connection::open(&self.config))
+    }
+}
+
+impl bus_rs::Client for MqttClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let qos = self.config.qos;
+        let channel = self.channel.clone();
+        let connection = self.connection();
+        if connection.shared.lock().unwrap().start_receiving() {
+            connection.subscribe(&channel, qos)?;
+        }
+        loop {
+            let event = connection
+                .events
+                .recv()
+                .map_err(|_| ClientError::IO("connection is closed".to_string()))?;
+            match event {
+                Event::Connected => connection.subscribe(&channel, qos)?,
+                Event::Message(delivery) => {
+                    // message which can't be decoded is skipped
+                    if let Some(raw_message) = delivery.message {
+                        // Err is dropped and the packet is acknowledged anyway -
+                        // MQTT has no negative acknowledgement
+                        let _ = recv_callback(raw_message);
+                    }
+                    connection.ack(&delivery.packet)?;
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        if is_topic_filter(&self.channel) {
+            return Err(ClientError::General(format!(
+                "can't publish to the topic filter {}",
+                self.channel
+            )));
+        }
+        let qos = self.config.qos;
+        let ack_timeout = self.config.ack_timeout;
+        let channel = self.channel.clone();
+        self.connection().publish(&channel, qos, msg, ack_timeout)
+    }
+}
+
+enum Handle {
+    V4(rumqttc::Client),
+    V5(rumqttc::v5::Client),
+}
+
+struct Connection {
+    handle: Handle,
+    events: mpsc::Receiver<Event>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Connection {
+    fn open(config: &MqttConfig) -> Connection {
+        let (sender, events) = mpsc::channel();
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let handle = match config.version {
+            MqttVersion::V311 => {
+                let (client, mut connection) =
+                    rumqttc::Client::new(config.v4_options(), REQUESTS_CAPACITY);
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    // iterator ends when the client is dropped
+                    for notification in connection.iter() {
+                        let event = match notification {
+                            Ok(event) => shared.lock().unwrap().on_v4_event(event),
+                            Err(e) => {
+                                shared.lock().unwrap().on_error(e);
+                                // the next iteration reconnects
+                                thread::sleep(RECONNECT_DELAY);
+                                continue;
+                            }
+                        };
+                        if let Some(event) = event {
+                            if sender.send(event).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                });
+                Handle::V4(client)
+            }
+            MqttVersion::V5 => {
+                let (client, mut connection) =
+                    rumqttc::v5::Client::new(config.v5_options(), REQUESTS_CAPACITY);
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    for notification in connection.iter() {
+                        let event = match notification {
+                            Ok(event) => shared.lock().unwrap().on_v5_event(event),
+                            Err(e) => {
+                                shared.lock().unwrap().on_error(e);
+                                thread::sleep(RECONNECT_DELAY);
+                                continue;
+                            }
+                        };
+                        if let Some(event) = event {
+                            if sender.send(event).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                });
+                Handle::V5(client)
+            }
+        };
+        Connection {
+            handle,
+            events,
+            shared,
+        }
+    }
+
+    fn subscribe(&self, topic: &str, qos: QoS) -> Result<(), ClientError> {
+        match &self.handle {
+            Handle::V4(client) => client.subscribe(topic, qos.into()).map_err(to_client_error),
+            Handle::V5(client) => client.subscribe(topic, qos.into()).map_err(to_client_error),
+        }
+    }
+
+    fn publish(
+        &self,
+        topic: &str,
+        qos: QoS,
+        msg: &RawMessage,
+        ack_timeout: Duration,
+    ) -> Result<(), ClientError> {
+        let (sender, ack) = mpsc::channel();
+        self.shared
+            .lock()
+            .unwrap()
+            .wait_ack(Box::new(move |result| {
+                let _ = sender.send(result);
+            }));
+        let requested = match &self.handle {
+            Handle::V4(client) => client
+                .publish(topic, qos.into(), false, encode_v4(msg))
+                .map_err(to_client_error),
+            Handle::V5(client) => {
+                let (payload, properties) = encode_v5(msg);
+                client
+                    .publish_with_properties(topic, qos.into(), false, payload, properties)
+                    .map_err(to_client_error)
+            }
+        };
+        if let Err(e) = requested {
+            self.shared.lock().unwrap().cancel_ack();
+            return Err(e);
+        }
+        ack.recv_timeout(ack_timeout)
+            .unwrap_or_else(|_| Err(self.shared.lock().unwrap().ack_timeout_error(ack_timeout)))
+    }
+
+    fn ack(&self, packet: &Packet) -> Result<(), ClientError> {
+        match (&self.handle, packet) {
+            (Handle::V4(client), Packet::V4(publish)) => {
+                client.ack(publish).map_err(to_client_error)
+            }
+            (Handle::V5(client), Packet::V5(publish)) => {
+                client.ack(publish).map_err(to_client_error)
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{
+    connection::{
+        encode_v4, encode_v5, is_topic_filter, Event, MqttConfig, Packet, Shared, RECONNECT_DELAY,
+        REQUESTS_CAPACITY,
+    },
+    to_client_error, MqttVersion, QoS,
+};
+
+/// Async version of `MqttClient` - the connection is driven by the background task.
+pub struct MqttClientAsync {
+    config: MqttConfig,
+    channel: String,
+    connection: Option<Connection>,
+}
+
+impl MqttClientAsync {
+    pub fn new(host: &str, port: u16, channel: String) -> MqttClientAsync {
+        MqttClientAsync {
+            config: MqttConfig::new(host, port),
+            channel,
+            connection: None,
+        }
+    }
+
+    /// Client id has to be unique for the broker - it's generated by default.
+    pub fn client_id(mut self, client_id: &str) -> Self {
+        self.config.client_id = client_id.to_string();
+        self
+    }
+
+    /// Protocol version, default is `MqttVersion::V311`.
+    pub fn version(mut self, version: MqttVersion) -> Self {
+        self.config.version = version;
+        self
+    }
+
+    /// QoS of published messages and of the subscription, default is `QoS::AtLeastOnce`.
+    pub fn qos(mut self, qos: QoS) -> Self {
+        self.config.qos = qos;
+        self
+    }
+
+    pub fn credentials(mut self, username: &str, password: &str) -> Self {
+        self.config.credentials = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    /// How long send waits for the acknowledgement of the message, default is 10 seconds.
+    pub fn ack_timeout(mut self, ack_timeout: Duration) -> Self {
+        self.config.ack_timeout = ack_timeout;
+        self
+    }
+
+    fn connection(&mut self) -> &mut Connection {
+        self.connection
+            .get_or_insert_with(|| Connection::open(&self.config))
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for MqttClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let qos = self.config.qos;
+        let channel = self.channel.clone();
+        let connection = self.connection();
+        let connected = connection.shared.lock().unwrap().start_receiving();
+        if connected {
+            connection.subscribe(&channel, qos).await?;
+        }
+        loop {
+            let event = connection
+                .events
+                .recv()
+                .await
+                .ok_or_else(|| ClientError::IO("connection is closed".to_string()))?;
+            match event {
+                Event::Connected => connection.subscribe(&channel, qos).await?,
+                Event::Message(delivery) => {
+                    // message which can't be decoded is skipped
+                    if let Some(raw_message) = delivery.message {
+                        // Err is dropped and the packet is acknowledged anyway -
+                        // MQTT has no negative acknowledgement
+                        let _ = recv_callback(raw_message).await;
+                    }
+                    connection.ack(&delivery.packet).await?;
+                }
+            }
+        }
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        if is_topic_filter(&self.channel) {
+            return Err(ClientError::General(format!(
+                "can't publish to the topic filter {}",
+                self.channel
+            )));
+        }
+        let qos = self.config.qos;
+        let ack_timeout = self.config.ack_timeout;
+        let channel = self.channel.clone();
+        self.connection()
+            .publish(&channel, qos, msg, ack_timeout)
+            .await
+    }
+}
+
+enum Handle {
+    V4(rumqttc::AsyncClient),
+    V5(rumqttc::v5::AsyncClient),
+}
+
+struct Connection {
+    handle: Handle,
+    events: mpsc::UnboundedReceiver<Event>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Connection {
+    fn open(config: &MqttConfig) -> Connection {
+        let (sender, events) = mpsc::unbounded_channel();
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let handle = match config.version {
+            MqttVersion::V311 => {
+                let (client, mut event_loop) =
+                    rumqttc::AsyncClient::new(config.v4_options(), REQUESTS_CAPACITY);
+                let shared = shared.clone();
+                tokio::spawn(async move {
+                    loop {
+                        let event = match event_loop.poll().await {
+                            Ok(event) => shared.lock().unwrap().on_v4_event(event),
+                            // the client is dropped
+                            Err(rumqttc::ConnectionError::RequestsDone) => break,
+                            Err(e) => {
+                                shared.lock().unwrap().on_error(e);
+                                // the next poll reconnects
+                                tokio::time::sleep(RECONNECT_DELAY).await;
+                                continue;
+                            }
+                        };
+                        if let Some(event) = event {
+                            if sender.send(event).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                });
+                Handle::V4(client)
+            }
+            MqttVersion::V5 => {
+                use rumqttc::v5::ConnectionError;
+
+                let (client, mut event_loop) =
+                    rumqttc::v5::AsyncClient::new(config.v5_options(), REQUESTS_CAPACITY);
+                let shared = shared.clone();
+                tokio::spawn(async move {
+                    loop {
+                        let event = match event_loop.poll().await {
+                            Ok(event) => shared.lock().unwrap().on_v5_event(event),
+                            Err(ConnectionError::RequestsDone) => break,
+                            Err(e) => {
+                                shared.lock().unwrap().on_error(e);
+                                tokio::time::sleep(RECONNECT_DELAY).await;
+                                continue;
+                            }
+                        };
+                        if let Some(event) = event {
+                            if sender.send(event).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                });
+                Handle::V5(client)
+            }
+        };
+        Connection {
+            handle,
+            events,
+            shared,
+        }
+    }
+
+    async fn subscribe(&self, topic: &str, qos: QoS) -> Result<(), ClientError> {
+        match &self.handle {
+            Handle::V4(client) => client
+                .subscribe(topic, qos.into())
+                .await
+                .map_err(to_client_error),
+            Handle::V5(client) => client
+                .subscribe(topic, qos.into())
+                .await
+                .map_err(to_client_error),
+        }
+    }
+
+    async fn publish(
+        &self,
+        topic: &str,
+        qos: QoS,
+        msg: &RawMessage,
+        ack_timeout: Duration,
+    ) -> Result<(), ClientError> {
+        let (sender, ack) = oneshot::channel();
+        self.shared
+            .lock()
+            .unwrap()
+            .wait_ack(Box::new(move |result| {
+                let _ = sender.send(result);
+            }));
+        let requested = match &self.handle {
+            Handle::V4(client) => client
+                .publish(topic, qos.into(), false, encode_v4(msg))
+                .await
+                .map_err(to_client_error),
+            Handle::V5(client) => {
+                let (payload, properties) = encode_v5(msg);
+                client
+                    .publish_with_properties(topic, qos.into(), false, payload, properties)
+                    .await
+                    .map_err(to_client_error)
+            }
+        };
+        if let Err(e) = requested {
+            self.shared.lock().unwrap().cancel_ack();
+            return Err(e);
+        }
+        match tokio::time::timeout(ack_timeout, ack).await {
+            Ok(Ok(result)) => result,
+            _ => Err(self.shared.lock().unwrap().ack_timeout_error(ack_timeout)),
+        }
+    }
+
+    async fn ack(&self, packet: &Packet) -> Result<(), ClientError> {
+        match (&self.handle, packet) {
+            (Handle::V4(client), Packet::V4(publish)) => {
+                client.ack(publish).await.map_err(to_client_error)
+            }
+            (Handle::V5(client), Packet::V5(publish)) => {
+                client.ack(publish).await.map_err(to_client_error)
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bus_rs::{ClientError, RawMessage};
+use rumqttc::v5::mqttbytes::v5::{PubAckReason, PubRecReason, PublishProperties};
+
+use crate::{MqttVersion, QoS};
+
+/// User property with the type of the message (MQTT 5).
+pub(crate) const MSG_TYPE_PROPERTY: &str = "bus-msg-type";
+pub(crate) const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+pub(crate) const REQUESTS_CAPACITY: usize = 100;
+pub(crate) const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+static CLIENT_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) struct MqttConfig {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) client_id: String,
+    pub(crate) version: MqttVersion,
+    pub(crate) qos: QoS,
+    pub(crate) credentials: Option<(String, String)>,
+    pub(crate) ack_timeout: Duration,
+}
+
+impl MqttConfig {
+    pub(crate) fn new(host: &str, port: u16) -> MqttConfig {
+        // client id has to be unique for the broker
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let client_id = format!(
+            "bus-rs-{}-{}-{}",
+            std::process::id(),
+            started_at,
+            CLIENT_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        );
+        MqttConfig {
+            host: host.to_string(),
+            port,
+            client_id,
+            version: MqttVersion::V311,
+            qos: QoS::AtLeastOnce,
+            credentials: None,
+            ack_timeout: DEFAULT_ACK_TIMEOUT,
+        }
+    }
+
+    pub(crate) fn v4_options(&self) -> rumqttc::MqttOptions {
+        let mut options = rumqttc::MqttOptions::new(&self.client_id, &self.host, self.port);
+        // messages are acknowledged after they're handled
+        options.set_manual_acks(true);
+        if let Some((username, password)) = &self.credentials {
+            options.set_credentials(username, password);
+        }
+        options
+    }
+
+    pub(crate) fn v5_options(&self) -> rumqttc::v5::MqttOptions {
+        let mut options = rumqttc::v5::MqttOptions::new(&self.client_id, &self.host, self.port);
+        options.set_manual_acks(true);
+        if let Some((username, password)) = &self.credentials {
+            options.set_credentials(username, password);
+        }
+        options
+    }
+}
+
+/// Called with the result of the publish when it's acknowledged.
+pub(crate) type AckFn = Box<dyn FnOnce(Result<(), ClientError>) + Send>;
+
+/// State of the connection shared by the client and its event loop.
+#[derive(Default)]
+pub(crate) struct Shared {
+    /// Events are forwarded only when the receiver is started, so they're not collected by a publisher.
+    receiving: bool,
+    connected: bool,
+    /// Publishes which are requested, but not written yet - the event loop writes them in the order of requests.
+    queued: VecDeque<AckFn>,
+    /// Written publishes waiting for PubAck (QoS 1) or PubComp (QoS 2) by the packet id.
+    in_flight: HashMap<u16, VecDeque<AckFn>>,
+    last_error: Option<String>,
+}
+
+impl Shared {
+    /// Returns true when the connection is already established - the receiver has to subscribe now,
+    /// because `Event::Connected` was not forwarded.
+    pub(crate) fn start_receiving(&mut self) -> bool {
+        self.receiving = true;
+        self.connected
+    }
+
+    /// Wait for the acknowledgement of the next requested publish.
+    pub(crate) fn wait_ack(&mut self, ack: AckFn) {
+        self.queued.push_back(ack);
+    }
+
+    /// The last publish wasn't requested (e.g. its topic is invalid), so it won't be written.
+    pub(crate) fn cancel_ack(&mut self) {
+        self.queued.pop_back();
+    }
+
+    pub(crate) fn ack_timeout_error(&self, ack_timeout: Duration) -> ClientError {
+        let mut error = format!("publish is not acknowledged in {:?}", ack_timeout);
+        if let Some(last_error) = &self.last_error {
+            error = format!("{}: {}", error, last_error);
+        }
+        ClientError::IO(error)
+    }
+
+    /// Returns the event which should be forwarded to the receiver.
+    pub(crate) fn on_v4_event(&mut self, event: rumqttc::Event) -> Option<Event> {
+        use rumqttc::{Event as MqttEvent, Outgoing, Packet};
+
+        match event {
+            MqttEvent::Incoming(Packet::ConnAck(_)) => return self.on_connected(),
+            MqttEvent::Incoming(Packet::Publish(publish)) => {
+                return self.forward(Event::Message(Box::new(decode_v4(publish))))
+            }
+            MqttEvent::Incoming(Packet::PubAck(puback)) => self.acked(puback.pkid, Ok(())),
+            MqttEvent::Incoming(Packet::PubComp(pubcomp)) => self.acked(pubcomp.pkid, Ok(())),
+            MqttEvent::Outgoing(Outgoing::Publish(pkid)) => self.written(pkid),
+            MqttEvent::Outgoing(Outgoing::AwaitAck(pkid)) => self.delayed(pkid),
+            _ => {}
+        }
+        None
+    }
+
+    /// Returns the event which should be forwarded to the receiver.
+    pub(crate) fn on_v5_event(&mut self, event: rumqttc::v5::Event) -> Option<Event> {
+        use rumqttc::{
+            v5::{mqttbytes::v5::Packet, Event as MqttEvent},
+            Outgoing,
+        };
+
+        match event {
+            MqttEvent::Incoming(Packet::ConnAck(_)) => return self.on_connected(),
+            MqttEvent::Incoming(Packet::Publish(publish)) => {
+                return self.forward(Event::Message(Box::new(decode_v5(publish))))
+            }
+            MqttEvent::Incoming(Packet::PubAck(puback)) => {
+                let result = match puback.reason {
+                    PubAckReason::Success | PubAckReason::NoMatchingSubscribers => Ok(()),
+                    reason => Err(rejected_error(reason)),
+                };
+                self.acked(puback.pkid, result)
+            }
+            // PubComp of the rejected publish is not expected, it's resolved by PubRec
+            MqttEvent::Incoming(Packet::PubRec(pubrec)) => match pubrec.reason {
+                PubRecReason::Success | PubRecReason::NoMatchingSubscribers => {}
+                reason => self.acked(pubrec.pkid, Err(rejected_error(reason))),
+            },
+            MqttEvent::Incoming(Packet::PubComp(pubcomp)) => self.acked(pubcomp.pkid, Ok(())),
+            MqttEvent::Outgoing(Outgoing::Publish(pkid)) => self.written(pkid),
+            MqttEvent::Outgoing(Outgoing::AwaitAck(pkid)) => self.delayed(pkid),
+            _ => {}
+        }
+        None
+    }
+
+    /// Requested publishes are sent after the reconnection - the error is reported when they're not acknowledged in time.
+    pub(crate) fn on_error(&mut self, e: impl Display) {
+        self.connected = false;
+        self.last_error = Some(e.to_string());
+    }
+
+    fn on_connected(&mut self) -> Option<Event> {
+        self.connected = true;
+        self.last_error = None;
+        self.forward(Event::Connected)
+    }
+
+    fn forward(&self, event: Event) -> Option<Event> {
+        self.receiving.then_some(event)
+    }
+
+    fn written(&mut self, pkid: u16) {
+        // QoS 0 publish is not acknowledged by the broker
+        if pkid == 0 {
+            if let Some(ack) = self.queued.pop_front() {
+                ack(Ok(()));
+            }
+            return;
+        }
+        // publish is written again after the reconnection
+        if self.in_flight.contains_key(&pkid) {
+            return;
+        }
+        if let Some(ack) = self.queued.pop_front() {
+            self.in_flight.entry(pkid).or_default().push_back(ack);
+        }
+    }
+
+    /// Packet id is still used by not acknowledged publish - the publish is written after that one is acknowledged.
+    fn delayed(&mut self, pkid: u16) {
+        if let Some(ack) = self.queued.pop_front() {
+            self.in_flight.entry(pkid).or_default().push_back(ack);
+        }
+    }
+
+    fn acked(&mut self, pkid: u16, result: Result<(), ClientError>) {
+        let Some(acks) = self.in_flight.get_mut(&pkid) else {
+            return;
+        };
+        if let Some(ack) = acks.pop_front() {
+            ack(result);
+        }
+        if acks.is_empty() {
+            self.in_flight.remove(&pkid);
+        }
+    }
+}
+
+fn rejected_error(reason: impl std::fmt::Debug) -> ClientError {
+    ClientError::General(format!("publish is rejected by the broker: {:?}", reason))
+}
+
+/// Event forwarded from the event loop to the client.
+pub(crate) enum Event {
+    /// Connection is (re)established - the subscription has to be renewed, because the session isn't kept by the broker.
+    Connected,
+    Message(Box<Delivery>),
+}
+
+/// Received message with the packet which is acknowledged after the message is handled.
+pub(crate) struct Delivery {
+    pub(crate) message: Option<RawMessage>,
+    pub(crate) packet: Packet,
+}
+
+pub(crate) enum Packet {
+    V4(rumqttc::Publish),
+    V5(rumqttc::v5::mqttbytes::v5::Publish),
+}
+
+impl From<QoS> for rumqttc::QoS {
+    fn from(qos: QoS) -> Self {
+        match qos {
+            QoS::AtMostOnce => rumqttc::QoS::AtMostOnce,
+            QoS::AtLeastOnce => rumqttc::QoS::AtLeastOnce,
+            QoS::ExactlyOnce => rumqttc::QoS::ExactlyOnce,
+        }
+    }
+}
+
+impl From<QoS> for rumqttc::v5::mqttbytes::QoS {
+    fn from(qos: QoS) -> Self {
+        match qos {
+            QoS::AtMostOnce => rumqttc::v5::mqttbytes::QoS::AtMostOnce,
+            QoS::AtLeastOnce => rumqttc::v5::mqttbytes::QoS::AtLeastOnce,
+            QoS::ExactlyOnce => rumqttc::v5::mqttbytes::QoS::ExactlyOnce,
+        }
+    }
+}
+
+/// Topic filter with wildcards can be used only for subscribing.
+pub(crate) fn is_topic_filter(channel: &str) -> bool {
+    channel.contains(['+', '#'])
+}
+
+pub(crate) fn encode_v4(msg: &RawMessage) -> Vec<u8> {
+    let str_msg: String = msg.into();
+    str_msg.into_bytes()
+}
+
+pub(crate) fn encode_v5(msg: &RawMessage) -> (Vec<u8>, PublishProperties) {
+    let mut user_properties = vec![(MSG_TYPE_PROPERTY.to_string(), msg.msg_type.clone())];
+    user_properties.extend(msg.headers.clone());
+    let properties = PublishProperties {
+        user_properties,
+        ..Default::default()
+    };
+    (msg.payload.clone().into_bytes(), properties)
+}
+
+/// Message of other publisher than bus-rs can't be decoded in MQTT 3.1.1.
+pub(crate) fn decode_v4(publish: rumqttc::Publish) -> Delivery {
+    Delivery {
+        message: serde_json::from_slice(&publish.payload).ok(),
+        packet: Packet::V4(publish),
+    }
+}
+
+/// Topic is used as the type when the message has no type property (e.g. it's published by a device).
+pub(crate) fn decode_v5(publish: rumqttc::v5::mqttbytes::v5::Publish) -> Delivery {
+    let mut msg_type = String::from_utf8_lossy(&publish.topic).to_string();
+    let mut headers = HashMap::new();
+    for (key, value) in publish
+        .properties
+        .iter()
+        .flat_map(|properties| properties.user_properties.iter())
+    {
+        if key == MSG_TYPE_PROPERTY {
+            msg_type = value.clone();
+        } else {
+            headers.insert(key.clone(), value.clone());
+        }
+    }
+    Delivery {
+        message: Some(RawMessage {
+            msg_type,
+            headers,
+            payload: String::from_utf8_lossy(&publish.payload).to_string(),
+        }),
+        packet: Packet::V5(publish),
+    }
+}
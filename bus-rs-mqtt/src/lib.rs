@@ -0,0 +1,26 @@
+mod client;
+mod client_async;
+mod connection;
+
+pub use client::MqttClient;
+pub use client_async::MqttClientAsync;
+
+pub(crate) fn to_client_error(e: impl std::fmt::Display) -> bus_rs::ClientError {
+    bus_rs::ClientError::IO(e.to_string())
+}
+
+/// Quality of service used for publishing and subscribing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QoS {
+    AtMostOnce,
+    AtLeastOnce,
+    ExactlyOnce,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MqttVersion {
+    /// MQTT 3.1.1 - whole `RawMessage` is serialized into the payload.
+    V311,
+    /// MQTT 5 - payload of `RawMessage` is the payload of the packet and headers are sent as user properties.
+    V5,
+}
@@ -0,0 +1,93 @@
+use std::{sync::Arc, time::Duration};
+
+use async_nats::Request;
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use futures_util::StreamExt as _;
+
+use crate::{to_client_error, to_headers, to_raw_request};
+
+/// Core NATS - messages are delivered only to connected listeners (fire-and-forget).
+/// Subject of the listener can contain `*`/`>` wildcards. Listeners in the same queue group compete for messages.
+pub struct NatsClientAsync {
+    client: async_nats::Client,
+    subject: String,
+    queue_group: Option<String>,
+}
+
+impl NatsClientAsync {
+    pub async fn new(url: &str, subject: String) -> NatsClientAsync {
+        let client = async_nats::connect(url).await.unwrap();
+        NatsClientAsync {
+            client,
+            subject,
+            queue_group: None,
+        }
+    }
+
+    pub fn queue_group(mut self, queue_group: &str) -> Self {
+        self.queue_group = Some(queue_group.to_string());
+        self
+    }
+
+    /// Send the request to the subject of the client and wait for the reply.
+    /// Responder replies by `reply` with `REPLY_TO_HEADER` of the request.
+    pub async fn request(
+        &self,
+        msg: &RawMessage,
+        timeout: Duration,
+    ) -> Result<RawMessage, ClientError> {
+        let request = Request::new()
+            .headers(to_headers(msg))
+            .payload(msg.payload.clone().into())
+            .timeout(Some(timeout));
+        let reply = self
+            .client
+            .send_request(self.subject.clone(), request)
+            .await
+            .map_err(to_client_error)?;
+        Ok(to_raw_request(&reply))
+    }
+
+    /// Publish the reply to the subject from `REPLY_TO_HEADER` of the received request.
+    pub async fn reply(&self, reply_to: &str, msg: &RawMessage) -> Result<(), ClientError> {
+        self.publish(reply_to.to_string(), msg).await
+    }
+
+    async fn publish(&self, subject: String, msg: &RawMessage) -> Result<(), ClientError> {
+        self.client
+            .publish_with_headers(subject, to_headers(msg), msg.payload.clone().into())
+            .await
+            .map_err(to_client_error)?;
+        // the message is buffered by the client until it's flushed
+        self.client.flush().await.map_err(to_client_error)
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for NatsClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let mut subscriber = match &self.queue_group {
+            Some(queue_group) => {
+                self.client
+                    .queue_subscribe(self.subject.clone(), queue_group.clone())
+                    .await
+            }
+            None => self.client.subscribe(self.subject.clone()).await,
+        }
+        .map_err(to_client_error)?;
+
+        while let Some(message) = subscriber.next().await {
+            // Err is dropped - core NATS is at-most-once, the message can't be redelivered
+            let _ = recv_callback(to_raw_request(&message)).await;
+        }
+        Err(ClientError::IO("connection is closed".to_string()))
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        self.publish(self.subject.clone(), msg).await
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use async_nats::jetstream::{
+    self,
+    consumer::{pull, AckPolicy},
+    stream, AckKind,
+};
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use futures_util::StreamExt as _;
+
+use crate::{to_client_error, to_headers, to_raw_message};
+
+const DEFAULT_DURABLE: &str = "default";
+
+/// JetStream - messages are persisted in the stream (created when it doesn't exist) and listeners consume them by
+/// the durable consumer, so they continue after restart where they stopped. Message is acknowledged after it's handled
+/// and terminated (not redelivered) when it's rejected by a layer. Listeners of the same durable consumer compete for messages.
+pub struct NatsJetStreamClientAsync {
+    context: jetstream::Context,
+    stream: String,
+    subject: String,
+    durable: String,
+}
+
+impl NatsJetStreamClientAsync {
+    pub async fn new(url: &str, stream: &str, subject: String) -> NatsJetStreamClientAsync {
+        let client = async_nats::connect(url).await.unwrap();
+        let context = jetstream::new(client);
+        context
+            .get_or_create_stream(stream::Config {
+                name: stream.to_string(),
+                subjects: vec![subject.clone()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        NatsJetStreamClientAsync {
+            context,
+            stream: stream.to_string(),
+            subject,
+            durable: DEFAULT_DURABLE.to_string(),
+        }
+    }
+
+    /// Name of the durable consumer used by the receiver.
+    pub fn durable(mut self, durable: &str) -> Self {
+        self.durable = durable.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for NatsJetStreamClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let stream = self
+            .context
+            .get_stream(&self.stream)
+            .await
+            .map_err(to_client_error)?;
+        let consumer = stream
+            .get_or_create_consumer(
+                &self.durable,
+                pull::Config {
+                    durable_name: Some(self.durable.clone()),
+                    filter_subject: self.subject.clone(),
+                    ack_policy: AckPolicy::Explicit,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(to_client_error)?;
+        let mut messages = consumer.messages().await.map_err(to_client_error)?;
+
+        while let Some(message) = messages.next().await {
+            let message = message.map_err(to_client_error)?;
+            match recv_callback(to_raw_message(&message)).await {
+                Ok(()) => message.ack().await,
+                Err(_) => message.ack_with(AckKind::Term).await,
+            }
+            .map_err(to_client_error)?;
+        }
+        Err(ClientError::IO("connection is closed".to_string()))
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        // the message is sent when it's acknowledged by the server
+        self.context
+            .publish_with_headers(
+                self.subject.clone(),
+                to_headers(msg),
+                msg.payload.clone().into(),
+            )
+            .await
+            .map_err(to_client_error)?
+            .await
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+}
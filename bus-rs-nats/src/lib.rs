@@ -0,0 +1,62 @@
+mod client_async;
+mod jetstream_async;
+
+pub use client_async::NatsClientAsync;
+pub use jetstream_async::NatsJetStreamClientAsync;
+
+use async_nats::{HeaderMap, Message};
+use bus_rs::RawMessage;
+
+/// Header with the type of the message.
+pub const MSG_TYPE_HEADER: &str = "bus-msg-type";
+/// Header of the received request with the subject for the reply - pass it to `NatsClientAsync::reply`.
+/// The header is not sent, so forwarded headers don't carry the reply subject of other request.
+pub const REPLY_TO_HEADER: &str = "nats-reply-to";
+
+pub(crate) fn to_client_error(e: impl std::fmt::Display) -> bus_rs::ClientError {
+    bus_rs::ClientError::IO(e.to_string())
+}
+
+pub(crate) fn to_headers(msg: &RawMessage) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(MSG_TYPE_HEADER, msg.msg_type.as_str());
+    for (key, value) in &msg.headers {
+        if key != REPLY_TO_HEADER {
+            headers.insert(key.as_str(), value.as_str());
+        }
+    }
+    headers
+}
+
+/// Subject is used as the type when the message has no type header (e.g. it's published by other NATS client).
+pub(crate) fn to_raw_message(message: &Message) -> RawMessage {
+    let mut msg_type = message.subject.to_string();
+    let mut headers = std::collections::HashMap::new();
+    for (key, values) in message.headers.iter().flat_map(|headers| headers.iter()) {
+        let Some(value) = values.first() else {
+            continue;
+        };
+        if key.to_string() == MSG_TYPE_HEADER {
+            msg_type = value.to_string();
+        } else {
+            headers.insert(key.to_string(), value.to_string());
+        }
+    }
+    RawMessage {
+        msg_type,
+        headers,
+        payload: String::from_utf8_lossy(&message.payload).to_string(),
+    }
+}
+
+/// Core NATS message with the reply subject in `REPLY_TO_HEADER`. JetStream messages must not use it -
+/// their reply subject is the ack subject of the server.
+pub(crate) fn to_raw_request(message: &Message) -> RawMessage {
+    let mut raw_msg = to_raw_message(message);
+    if let Some(reply) = &message.reply {
+        raw_msg
+            .headers
+            .insert(REPLY_TO_HEADER.to_string(), reply.to_string());
+    }
+    raw_msg
+}
@@ -0,0 +1,48 @@
+mod notify;
+mod notify_async;
+mod queue;
+mod queue_async;
+
+pub use notify::PostgresNotifyClient;
+pub use notify_async::PostgresNotifyClientAsync;
+pub use queue::PostgresQueueClient;
+pub use queue_async::PostgresQueueClientAsync;
+
+use bus_rs::ClientError;
+use futures_util::StreamExt as _;
+use tokio::sync::mpsc;
+use tokio_postgres::{AsyncMessage, Notification};
+
+pub(crate) fn to_client_error(e: postgres::Error) -> ClientError {
+    if e.is_closed() {
+        return ClientError::IO(e.to_string());
+    }
+    ClientError::General(e.to_string())
+}
+
+/// Quote the name of the channel or table, so any name can be used in SQL.
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Connect and drive the connection in the background task - notifications are forwarded to the returned receiver.
+pub(crate) async fn connect_async(
+    params: &str,
+) -> (
+    tokio_postgres::Client,
+    mpsc::UnboundedReceiver<Notification>,
+) {
+    let (client, mut connection) = tokio_postgres::connect(params, tokio_postgres::NoTls)
+        .await
+        .unwrap();
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut messages = futures_util::stream::poll_fn(move |cx| connection.poll_message(cx));
+        while let Some(Ok(message)) = messages.next().await {
+            if let AsyncMessage::Notification(notification) = message {
+                let _ = sender.send(notification);
+            }
+        }
+    });
+    (client, receiver)
+}
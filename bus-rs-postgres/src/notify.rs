@@ -0,0 +1,69 @@
+use std::sync::{Arc, Mutex};
+
+use bus_rs::{report_error, ClientError, ErrorHookFn, RawMessage};
+use fallible_iterator::FallibleIterator;
+use postgres::NoTls;
+
+use crate::{quote_identifier, to_client_error};
+
+/// Lightweight transport on LISTEN/NOTIFY - messages are not persisted, so only connected listeners
+/// receive them. Size of the message is limited by NOTIFY payload (8000 bytes by default).
+pub struct PostgresNotifyClient {
+    // postgres client is not Sync - mutex is only a wrapper, the client is used by `&mut self`
+    client: Mutex<postgres::Client>,
+    channel: String,
+    error_hook: Option<Arc<ErrorHookFn>>,
+}
+
+impl PostgresNotifyClient {
+    pub fn new(params: &str, channel: String) -> PostgresNotifyClient {
+        let client = postgres::Client::connect(params, NoTls).unwrap();
+        PostgresNotifyClient {
+            client: Mutex::new(client),
+            channel,
+            error_hook: None,
+        }
+    }
+}
+
+impl bus_rs::Client for PostgresNotifyClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let client = self.client.get_mut().unwrap();
+        client
+            .batch_execute(&format!("LISTEN {}", quote_identifier(&self.channel)))
+            .map_err(to_client_error)?;
+
+        let mut notifications = client.notifications();
+        let mut notifications = notifications.blocking_iter();
+        while let Some(notification) = notifications.next().map_err(to_client_error)? {
+            let raw_message = match RawMessage::decode(notification.payload()) {
+                Ok(raw_message) => raw_message,
+                Err(e) => {
+                    let content = RawMessage::undecodable(notification.payload());
+                    report_error(&self.error_hook, &content, &e);
+                    continue;
+                }
+            };
+            // Err is dropped - NOTIFY is fire-and-forget, the notification can't be redelivered
+            let _ = recv_callback(raw_message);
+        }
+        Err(ClientError::IO("connection is closed".to_string()))
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let str_msg: String = msg.into();
+        self.client
+            .get_mut()
+            .unwrap()
+            .execute("SELECT pg_notify($1, $2)", &[&self.channel, &str_msg])
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+
+    fn set_error_hook(&mut self, error_hook: Arc<ErrorHookFn>) {
+        self.error_hook = Some(error_hook);
+    }
+}
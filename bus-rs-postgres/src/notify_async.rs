@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bus_rs::{report_error, ClientCallbackFnAsync, ClientError, ErrorHookFn, RawMessage};
+use tokio::sync::mpsc;
+use tokio_postgres::Notification;
+
+use crate::{connect_async, quote_identifier, to_client_error};
+
+/// Async version of `PostgresNotifyClient` - messages are sent by NOTIFY and not persisted.
+pub struct PostgresNotifyClientAsync {
+    client: tokio_postgres::Client,
+    notifications: mpsc::UnboundedReceiver<Notification>,
+    channel: String,
+    error_hook: Option<Arc<ErrorHookFn>>,
+}
+
+impl PostgresNotifyClientAsync {
+    pub async fn new(params: &str, channel: String) -> PostgresNotifyClientAsync {
+        let (client, notifications) = connect_async(params).await;
+        PostgresNotifyClientAsync {
+            client,
+            notifications,
+            channel,
+            error_hook: None,
+        }
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for PostgresNotifyClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        self.client
+            .batch_execute(&format!("LISTEN {}", quote_identifier(&self.channel)))
+            .await
+            .map_err(to_client_error)?;
+
+        while let Some(notification) = self.notifications.recv().await {
+            let raw_message = match RawMessage::decode(notification.payload()) {
+                Ok(raw_message) => raw_message,
+                Err(e) => {
+                    let content = RawMessage::undecodable(notification.payload());
+                    report_error(&self.error_hook, &content, &e);
+                    continue;
+                }
+            };
+            // Err is dropped - NOTIFY is fire-and-forget, the notification can't be redelivered
+            let _ = recv_callback(raw_message).await;
+        }
+        Err(ClientError::IO("connection is closed".to_string()))
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let str_msg: String = msg.into();
+        self.client
+            .execute("SELECT pg_notify($1, $2)", &[&self.channel, &str_msg])
+            .await
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+
+    fn set_error_hook(&mut self, error_hook: Arc<ErrorHookFn>) {
+        self.error_hook = Some(error_hook);
+    }
+}
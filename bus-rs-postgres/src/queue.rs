@@ -0,0 +1,144 @@
+use std::{sync::Mutex, time::Duration};
+
+use bus_rs::{ClientError, RawMessage};
+use fallible_iterator::FallibleIterator;
+use postgres::NoTls;
+
+use crate::{quote_identifier, to_client_error};
+
+pub(crate) const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Durable queue in the table - every message is received by exactly one of the listeners.
+/// The message is claimed with `FOR UPDATE SKIP LOCKED` and deleted in the same transaction when
+/// it's handled, so it's received again when the listener crashes in the meantime.
+/// Listeners are woken up by NOTIFY on `<table>:<channel>` and poll the table every `poll_interval`.
+/// The table name is quoted - it's used as it is (case-sensitive) and can't contain the schema.
+pub struct PostgresQueueClient {
+    // postgres client is not Sync - mutex is only a wrapper, the client is used by `&mut self`
+    client: Mutex<postgres::Client>,
+    queries: QueueQueries,
+    channel: String,
+    poll_interval: Duration,
+}
+
+impl PostgresQueueClient {
+    pub fn new(params: &str, table: &str, channel: String) -> PostgresQueueClient {
+        let mut client = postgres::Client::connect(params, NoTls).unwrap();
+        let queries = QueueQueries::new(table, &channel);
+        client.batch_execute(&queries.create_table).unwrap();
+        PostgresQueueClient {
+            client: Mutex::new(client),
+            queries,
+            channel,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+}
+
+impl bus_rs::Client for PostgresQueueClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let client = self.client.get_mut().unwrap();
+        client
+            .batch_execute(&self.queries.listen)
+            .map_err(to_client_error)?;
+
+        loop {
+            let mut transaction = client.transaction().map_err(to_client_error)?;
+            let row = transaction
+                .query_opt(&self.queries.claim, &[&self.channel])
+                .map_err(to_client_error)?;
+            let Some(row) = row else {
+                transaction.rollback().map_err(to_client_error)?;
+                // wait for the new message
+                client
+                    .notifications()
+                    .timeout_iter(self.poll_interval)
+                    .next()
+                    .map_err(to_client_error)?;
+                continue;
+            };
+
+            // on Err the row is deleted and committed like a handled one - rollback would
+            // return it to the queue and redeliver it forever
+            let _ = recv_callback(to_raw_message(&row));
+            transaction.commit().map_err(to_client_error)?;
+        }
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let headers = serde_json::to_string(&msg.headers).unwrap();
+        self.client
+            .get_mut()
+            .unwrap()
+            .execute(
+                &self.queries.insert,
+                &[
+                    &self.channel,
+                    &msg.msg_type,
+                    &headers,
+                    &msg.payload,
+                    &self.queries.wakeup_channel,
+                ],
+            )
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+}
+
+pub(crate) struct QueueQueries {
+    pub(crate) create_table: String,
+    pub(crate) listen: String,
+    pub(crate) claim: String,
+    pub(crate) insert: String,
+    pub(crate) wakeup_channel: String,
+}
+
+impl QueueQueries {
+    pub(crate) fn new(table: &str, channel: &str) -> Self {
+        let wakeup_channel = format!("{}:{}", table, channel);
+        let index = quote_identifier(&format!("{}_channel_id", table));
+        let table = quote_identifier(table);
+        QueueQueries {
+            create_table: format!(
+                "CREATE TABLE IF NOT EXISTS {table} (
+                    id BIGSERIAL PRIMARY KEY,
+                    channel TEXT NOT NULL,
+                    msg_type TEXT NOT NULL,
+                    headers TEXT NOT NULL,
+                    payload TEXT NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+                );
+                CREATE INDEX IF NOT EXISTS {index} ON {table} (channel, id);"
+            ),
+            listen: format!("LISTEN {}", quote_identifier(&wakeup_channel)),
+            claim: format!(
+                "DELETE FROM {table} WHERE id = (
+                    SELECT id FROM {table} WHERE channel = $1 ORDER BY id FOR UPDATE SKIP LOCKED LIMIT 1
+                ) RETURNING msg_type, headers, payload"
+            ),
+            insert: format!(
+                "WITH inserted AS (
+                    INSERT INTO {table} (channel, msg_type, headers, payload) VALUES ($1, $2, $3, $4) RETURNING id
+                ) SELECT pg_notify($5, '') FROM inserted"
+            ),
+            wakeup_channel,
+        }
+    }
+}
+
+pub(crate) fn to_raw_message(row: &postgres::Row) -> RawMessage {
+    let headers: String = row.get(1);
+    RawMessage {
+        msg_type: row.get(0),
+        headers: serde_json::from_str(&headers).unwrap_or_default(),
+        payload: row.get(2),
+    }
+}
@@ -0,0 +1,91 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use tokio::sync::mpsc;
+use tokio_postgres::Notification;
+
+use crate::{
+    connect_async,
+    queue::{to_raw_message, QueueQueries, DEFAULT_POLL_INTERVAL},
+    to_client_error,
+};
+
+/// Async version of `PostgresQueueClient` - every message is received by exactly one of the listeners.
+pub struct PostgresQueueClientAsync {
+    client: tokio_postgres::Client,
+    notifications: mpsc::UnboundedReceiver<Notification>,
+    queries: QueueQueries,
+    channel: String,
+    poll_interval: Duration,
+}
+
+impl PostgresQueueClientAsync {
+    pub async fn new(params: &str, table: &str, channel: String) -> PostgresQueueClientAsync {
+        let (client, notifications) = connect_async(params).await;
+        let queries = QueueQueries::new(table, &channel);
+        client.batch_execute(&queries.create_table).await.unwrap();
+        PostgresQueueClientAsync {
+            client,
+            notifications,
+            queries,
+            channel,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for PostgresQueueClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        self.client
+            .batch_execute(&self.queries.listen)
+            .await
+            .map_err(to_client_error)?;
+
+        loop {
+            let transaction = self.client.transaction().await.map_err(to_client_error)?;
+            let row = transaction
+                .query_opt(&self.queries.claim, &[&self.channel])
+                .await
+                .map_err(to_client_error)?;
+            let Some(row) = row else {
+                transaction.rollback().await.map_err(to_client_error)?;
+                // wait for the new message
+                let _ = tokio::time::timeout(self.poll_interval, self.notifications.recv()).await;
+                continue;
+            };
+
+            // on Err the row is deleted and committed like a handled one - rollback would
+            // return it to the queue and redeliver it forever
+            let _ = recv_callback(to_raw_message(&row)).await;
+            transaction.commit().await.map_err(to_client_error)?;
+        }
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let headers = serde_json::to_string(&msg.headers).unwrap();
+        self.client
+            .execute(
+                &self.queries.insert,
+                &[
+                    &self.channel,
+                    &msg.msg_type,
+                    &headers,
+                    &msg.payload,
+                    &self.queries.wakeup_channel,
+                ],
+            )
+            .await
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+}
@@ -1,6 +1,8 @@
 use bus_rs::ClientError;
 use redis::Commands;
 
+use crate::{to_batch_results, to_client_error};
+
 pub struct RedisClient {
     connection: Box<redis::Connection>,
     channel: String,
@@ -18,7 +20,10 @@ impl RedisClient {
 }
 
 impl bus_rs::Client for RedisClient {
-    fn receiver(&mut self, recv_callback: &dyn Fn(bus_rs::RawMessage)) -> Result<(), ClientError> {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(bus_rs::RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
         let mut pubsub = self.connection.as_pubsub();
         pubsub.subscribe(self.channel.as_str()).unwrap();
 
@@ -30,7 +35,8 @@ impl bus_rs::Client for RedisClient {
                 return Err(ClientError::General(e.to_string()));
             })?;
             let raw_message = bus_rs::RawMessage::from(msg.get_payload::<String>().unwrap());
-            recv_callback(raw_message);
+            // Err is dropped - redis pub/sub doesn't keep the message, so it can't be redelivered
+            let _ = recv_callback(raw_message);
         }
     }
 
@@ -39,13 +45,16 @@ impl bus_rs::Client for RedisClient {
         let result: Result<(), redis::RedisError> =
             self.connection.publish(self.channel.as_str(), str_msg);
 
-        let _ = result.or_else(|e| {
-            if e.is_io_error() {
-                return Err(ClientError::IO(e.to_string()));
-            }
-            return Err(ClientError::General(e.to_string()));
-        });
+        result.map_err(to_client_error)
+    }
 
-        Ok(())
+    fn send_batch(&mut self, msgs: &[bus_rs::RawMessage]) -> Vec<Result<(), ClientError>> {
+        let mut pipe = redis::pipe();
+        for msg in msgs {
+            let str_msg: String = msg.into();
+            pipe.publish(self.channel.as_str(), str_msg);
+        }
+        let replies: redis::RedisResult<Vec<redis::Value>> = pipe.query(self.connection.as_mut());
+        to_batch_results(msgs.len(), replies)
     }
 }
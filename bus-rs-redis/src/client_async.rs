@@ -5,6 +5,8 @@ use redis::AsyncCommands;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use crate::{to_batch_results, to_client_error};
+
 pub struct RedisClientAsync {
     pubsub: Option<Box<redis::aio::PubSub>>,
     connection: Option<Arc<Mutex<redis::aio::Connection>>>,
@@ -56,6 +58,8 @@ impl bus_rs::ClientAsync for RedisClientAsync {
             loop {
                 let msg = pubsub_stream.next().await.unwrap();
                 let raw_message = bus_rs::RawMessage::from(msg.get_payload::<String>().unwrap());
+                // Err is dropped - redis pub/sub doesn't keep the message,
+                // so it can't be redelivered
                 let _ = recv_callback(raw_message).await;
             }
         }
@@ -66,13 +70,31 @@ impl bus_rs::ClientAsync for RedisClientAsync {
         if let Some(connection) = &mut self.connection {
             let mut connection = connection.lock().await;
             let str_msg: String = msg.into();
-            let _: i32 = connection
+            let result: Result<i32, redis::RedisError> = connection
                 .publish(self.channel.to_string(), str_msg)
-                .await
-                .unwrap();
+                .await;
 
-            return Ok(());
+            return result.map(|_| ()).map_err(to_client_error);
         }
         Err(ClientError::NotAssignedConnection)
     }
+
+    async fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+        let Some(connection) = &mut self.connection else {
+            return msgs
+                .iter()
+                .map(|_| Err(ClientError::NotAssignedConnection))
+                .collect();
+        };
+
+        let mut pipe = redis::pipe();
+        for msg in msgs {
+            let str_msg: String = msg.into();
+            pipe.publish(self.channel.as_str(), str_msg);
+        }
+        let mut connection = connection.lock().await;
+        let replies: redis::RedisResult<Vec<redis::Value>> =
+            pipe.query_async(&mut *connection).await;
+        to_batch_results(msgs.len(), replies)
+    }
 }
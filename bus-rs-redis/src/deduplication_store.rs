@@ -0,0 +1,54 @@
+use std::{sync::Mutex, time::Duration};
+
+use bus_rs::{deduplication::DeduplicationStore, ClientError};
+
+use crate::to_client_error;
+
+/// Processed message ids kept as redis keys (`<prefix>:<id>`) which expire after `ttl`. Ids are claimed by `SET NX`,
+/// so listeners sharing the store can't claim the same id.
+pub struct RedisDeduplicationStore {
+    connection: Mutex<redis::Connection>,
+    prefix: String,
+    ttl: Duration,
+}
+
+impl RedisDeduplicationStore {
+    pub fn new(addr: &str, prefix: &str, ttl: Duration) -> RedisDeduplicationStore {
+        let redis_client = redis::Client::open(addr).unwrap();
+        let conn = redis_client.get_connection().unwrap();
+        RedisDeduplicationStore {
+            connection: Mutex::new(conn),
+            prefix: prefix.to_string(),
+            ttl,
+        }
+    }
+
+    fn key(&self, id: &str) -> String {
+        format!("{}:{}", self.prefix, id)
+    }
+}
+
+impl DeduplicationStore for RedisDeduplicationStore {
+    fn claim(&self, id: &str) -> Result<bool, ClientError> {
+        let mut connection = self.connection.lock().unwrap();
+        let ttl = self.ttl.as_secs().max(1);
+        // SET NX replies nil when the key already exists
+        let reply: Option<String> = redis::cmd("SET")
+            .arg(self.key(id))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl)
+            .query(&mut *connection)
+            .map_err(to_client_error)?;
+        Ok(reply.is_some())
+    }
+
+    fn release(&self, id: &str) -> Result<(), ClientError> {
+        let mut connection = self.connection.lock().unwrap();
+        redis::cmd("DEL")
+            .arg(self.key(id))
+            .query(&mut *connection)
+            .map_err(to_client_error)
+    }
+}
@@ -1,5 +1,50 @@
 mod client;
 mod client_async;
+#[cfg(feature = "deduplication")]
+mod deduplication_store;
+mod queue;
+mod queue_async;
+mod scheduler;
+mod scheduler_async;
 
 pub use client::RedisClient;
 pub use client_async::RedisClientAsync;
+#[cfg(feature = "deduplication")]
+pub use deduplication_store::RedisDeduplicationStore;
+pub use queue::RedisQueueClient;
+pub use queue_async::RedisQueueClientAsync;
+pub use scheduler::RedisScheduler;
+pub use scheduler_async::RedisSchedulerAsync;
+
+pub(crate) fn to_client_error(e: redis::RedisError) -> bus_rs::ClientError {
+    if e.is_io_error() {
+        return bus_rs::ClientError::IO(e.to_string());
+    }
+    bus_rs::ClientError::General(e.to_string())
+}
+
+/// Results of the pipelined `PUBLISH` commands - every reply (number of receivers) belongs to one message.
+/// Failure of the whole pipeline (e.g. connection error) is the result of every message.
+pub(crate) fn to_batch_results(
+    count: usize,
+    replies: redis::RedisResult<Vec<redis::Value>>,
+) -> Vec<Result<(), bus_rs::ClientError>> {
+    match replies {
+        Ok(replies) => (0..count)
+            .map(|i| match replies.get(i) {
+                Some(redis::Value::Int(_)) => Ok(()),
+                Some(reply) => Err(bus_rs::ClientError::General(format!(
+                    "unexpected reply: {:?}",
+                    reply
+                ))),
+                None => Err(bus_rs::ClientError::General("missing reply".to_string())),
+            })
+            .collect(),
+        Err(e) => (0..count)
+            .map(|_| match e.is_io_error() {
+                true => Err(bus_rs::ClientError::IO(e.to_string())),
+                false => Err(bus_rs::ClientError::General(e.to_string())),
+            })
+            .collect(),
+    }
+}
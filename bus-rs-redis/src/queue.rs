@@ -0,0 +1,226 @@
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use bus_rs::{report_error, ClientError, ErrorHookFn, RawMessage};
+use redis::{Commands, Direction};
+
+use crate::{scheduler::to_score, to_client_error};
+
+// Item of the queue is prefixed by unique sequence number, so the same message can wait
+// in the queue (and in the processing list) more than once.
+pub(crate) const PUSH_SCRIPT: &str = r"
+local id = redis.call('INCR', KEYS[2])
+redis.call('LPUSH', KEYS[1], id .. ':' .. ARGV[1])
+";
+
+// Items of the processing list without a lease (worker crashed right after taking it) get one,
+// items with expired lease are moved back to the queue - they're the next to be taken.
+pub(crate) const RECOVER_SCRIPT: &str = r"
+local items = redis.call('LRANGE', KEYS[2], 0, -1)
+for _, item in ipairs(items) do
+    if not redis.call('ZSCORE', KEYS[3], item) then
+        redis.call('ZADD', KEYS[3], ARGV[2], item)
+    end
+end
+local expired = redis.call('ZRANGEBYSCORE', KEYS[3], '-inf', ARGV[1])
+for _, item in ipairs(expired) do
+    redis.call('ZREM', KEYS[3], item)
+    if redis.call('LREM', KEYS[2], 1, item) > 0 then
+        redis.call('RPUSH', KEYS[1], item)
+    end
+end
+return #expired
+";
+
+/// How long BLMOVE waits for an item - the receiver checks expired leases in between.
+pub(crate) const BLOCK_TIMEOUT_SECS: usize = 1;
+
+pub(crate) const DEFAULT_VISIBILITY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Work queue on redis lists - every message is received by exactly one of the listeners.
+/// Received message is moved to the processing list (`<queue>:processing`) and leased for
+/// the visibility timeout. The lease is renewed while the callback runs, so a slow message isn't taken
+/// by another worker. It's removed when the callback returns - messages of crashed workers
+/// are moved back to the queue when their lease expires. Message which can't be decoded is reported
+/// to the error hook of the listener and removed.
+pub struct RedisQueueClient {
+    redis_client: redis::Client,
+    connection: Box<redis::Connection>,
+    queue: String,
+    visibility_timeout: Duration,
+    error_hook: Option<Arc<ErrorHookFn>>,
+}
+
+impl RedisQueueClient {
+    pub fn new(addr: &str, queue: String) -> RedisQueueClient {
+        let redis_client = redis::Client::open(addr).unwrap();
+        let conn = redis_client.get_connection().unwrap();
+        RedisQueueClient {
+            redis_client,
+            connection: Box::new(conn),
+            queue,
+            visibility_timeout: DEFAULT_VISIBILITY_TIMEOUT,
+            error_hook: None,
+        }
+    }
+
+    pub fn visibility_timeout(mut self, visibility_timeout: Duration) -> Self {
+        self.visibility_timeout = visibility_timeout;
+        self
+    }
+
+    /// Move messages with expired lease back to the queue. Returns number of recovered messages.
+    pub fn recover(&mut self) -> Result<usize, ClientError> {
+        let keys = QueueKeys::new(&self.queue);
+        let now = SystemTime::now();
+        redis::Script::new(RECOVER_SCRIPT)
+            .key(&self.queue)
+            .key(keys.processing)
+            .key(keys.leases)
+            .arg(to_score(now))
+            .arg(to_score(now + self.visibility_timeout))
+            .invoke(self.connection.as_mut())
+            .map_err(to_client_error)
+    }
+
+    fn take(&mut self) -> Result<Option<String>, ClientError> {
+        let keys = QueueKeys::new(&self.queue);
+        let item: Option<String> = redis::cmd("BLMOVE")
+            .arg(&self.queue)
+            .arg(&keys.processing)
+            .arg(Direction::Right)
+            .arg(Direction::Left)
+            .arg(BLOCK_TIMEOUT_SECS)
+            .query(self.connection.as_mut())
+            .map_err(to_client_error)?;
+        if let Some(item) = &item {
+            let lease = to_score(SystemTime::now() + self.visibility_timeout);
+            let _: usize = self
+                .connection
+                .zadd(keys.leases, item, lease)
+                .map_err(to_client_error)?;
+        }
+        Ok(item)
+    }
+
+    fn ack(&mut self, item: &str) -> Result<(), ClientError> {
+        let keys = QueueKeys::new(&self.queue);
+        redis::pipe()
+            .atomic()
+            .lrem(keys.processing, 1, item)
+            .ignore()
+            .zrem(keys.leases, item)
+            .ignore()
+            .query(self.connection.as_mut())
+            .map_err(to_client_error)
+    }
+}
+
+impl bus_rs::Client for RedisQueueClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let recover_interval = recover_interval(self.visibility_timeout);
+        let mut recovered_at = Instant::now();
+        self.recover()?;
+        // leases are renewed from other thread while the callback runs, so it needs own connection
+        let mut renew_connection = self
+            .redis_client
+            .get_connection()
+            .map_err(to_client_error)?;
+
+        loop {
+            if recovered_at.elapsed() >= recover_interval {
+                self.recover()?;
+                recovered_at = Instant::now();
+            }
+
+            let Some(item) = self.take()? else {
+                continue;
+            };
+            match to_raw_message(&item) {
+                Ok(raw_message) => {
+                    let (done, stop) = mpsc::channel::<()>();
+                    let keys = QueueKeys::new(&self.queue);
+                    let visibility_timeout = self.visibility_timeout;
+                    let renew_connection = &mut renew_connection;
+                    let item = &item;
+                    thread::scope(|scope| {
+                        scope.spawn(move || {
+                            while stop.recv_timeout(renew_interval(visibility_timeout))
+                                == Err(mpsc::RecvTimeoutError::Timeout)
+                            {
+                                let lease = to_score(SystemTime::now() + visibility_timeout);
+                                // failed renewal doesn't interrupt the handling, the next one may succeed
+                                let _: Result<usize, _> =
+                                    renew_lease(&keys, item, lease).query(renew_connection);
+                            }
+                        });
+                        // on Err the item is acked (removed) like a handled one - requeue would
+                        // redeliver it forever
+                        let _ = recv_callback(raw_message);
+                        drop(done);
+                    });
+                }
+                Err(e) => report_error(&self.error_hook, &RawMessage::undecodable(&item), &e),
+            }
+            self.ack(&item)?;
+        }
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let str_msg: String = msg.into();
+        redis::Script::new(PUSH_SCRIPT)
+            .key(&self.queue)
+            .key(QueueKeys::new(&self.queue).sequence)
+            .arg(str_msg)
+            .invoke(self.connection.as_mut())
+            .map_err(to_client_error)
+    }
+
+    fn set_error_hook(&mut self, error_hook: Arc<ErrorHookFn>) {
+        self.error_hook = Some(error_hook);
+    }
+}
+
+pub(crate) struct QueueKeys {
+    pub(crate) processing: String,
+    pub(crate) leases: String,
+    pub(crate) sequence: String,
+}
+
+impl QueueKeys {
+    pub(crate) fn new(queue: &str) -> Self {
+        QueueKeys {
+            processing: format!("{}:processing", queue),
+            leases: format!("{}:leases", queue),
+            sequence: format!("{}:seq", queue),
+        }
+    }
+}
+
+pub(crate) fn recover_interval(visibility_timeout: Duration) -> Duration {
+    (visibility_timeout / 2).max(Duration::from_secs(BLOCK_TIMEOUT_SECS as u64))
+}
+
+/// Extend the lease of the item which is being handled. Lease which was already recovered is not created again.
+pub(crate) fn renew_lease(keys: &QueueKeys, item: &str, lease: u64) -> redis::Cmd {
+    let mut cmd = redis::cmd("ZADD");
+    cmd.arg(&keys.leases).arg("XX").arg(lease).arg(item);
+    cmd
+}
+
+/// Lease is renewed well before it expires.
+pub(crate) fn renew_interval(visibility_timeout: Duration) -> Duration {
+    visibility_timeout / 3
+}
+
+/// Strip the sequence number of the queue item.
+pub(crate) fn to_raw_message(item: &str) -> Result<RawMessage, ClientError> {
+    let (_, msg) = item.split_once(':').unwrap_or(("", item));
+    RawMessage::decode(msg)
+}
@@ -0,0 +1,162 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use async_trait::async_trait;
+use bus_rs::{report_error, ClientCallbackFnAsync, ClientError, ErrorHookFn, RawMessage};
+use redis::{AsyncCommands, Direction};
+
+use crate::{
+    queue::{
+        recover_interval, renew_interval, renew_lease, to_raw_message, QueueKeys,
+        BLOCK_TIMEOUT_SECS, DEFAULT_VISIBILITY_TIMEOUT, PUSH_SCRIPT, RECOVER_SCRIPT,
+    },
+    scheduler::to_score,
+    to_client_error,
+};
+
+/// Async version of `RedisQueueClient` - every message is received by exactly one of the listeners.
+/// The lease is renewed while the callback runs.
+pub struct RedisQueueClientAsync {
+    connection: redis::aio::Connection,
+    queue: String,
+    visibility_timeout: Duration,
+    error_hook: Option<Arc<ErrorHookFn>>,
+}
+
+impl RedisQueueClientAsync {
+    pub async fn new(addr: &str, queue: String) -> RedisQueueClientAsync {
+        let redis_client = redis::Client::open(addr).unwrap();
+        let conn = redis_client.get_async_connection().await.unwrap();
+        RedisQueueClientAsync {
+            connection: conn,
+            queue,
+            visibility_timeout: DEFAULT_VISIBILITY_TIMEOUT,
+            error_hook: None,
+        }
+    }
+
+    pub fn visibility_timeout(mut self, visibility_timeout: Duration) -> Self {
+        self.visibility_timeout = visibility_timeout;
+        self
+    }
+
+    /// Move messages with expired lease back to the queue. Returns number of recovered messages.
+    pub async fn recover(&mut self) -> Result<usize, ClientError> {
+        let keys = QueueKeys::new(&self.queue);
+        let now = SystemTime::now();
+        redis::Script::new(RECOVER_SCRIPT)
+            .key(&self.queue)
+            .key(keys.processing)
+            .key(keys.leases)
+            .arg(to_score(now))
+            .arg(to_score(now + self.visibility_timeout))
+            .invoke_async(&mut self.connection)
+            .await
+            .map_err(to_client_error)
+    }
+
+    async fn take(&mut self) -> Result<Option<String>, ClientError> {
+        let keys = QueueKeys::new(&self.queue);
+        let item: Option<String> = redis::cmd("BLMOVE")
+            .arg(&self.queue)
+            .arg(&keys.processing)
+            .arg(Direction::Right)
+            .arg(Direction::Left)
+            .arg(BLOCK_TIMEOUT_SECS)
+            .query_async(&mut self.connection)
+            .await
+            .map_err(to_client_error)?;
+        if let Some(item) = &item {
+            let lease = to_score(SystemTime::now() + self.visibility_timeout);
+            let _: usize = self
+                .connection
+                .zadd(keys.leases, item, lease)
+                .await
+                .map_err(to_client_error)?;
+        }
+        Ok(item)
+    }
+
+    async fn renew_lease(&mut self, item: &str) -> Result<(), ClientError> {
+        let lease = to_score(SystemTime::now() + self.visibility_timeout);
+        renew_lease(&QueueKeys::new(&self.queue), item, lease)
+            .query_async::<_, usize>(&mut self.connection)
+            .await
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+
+    async fn ack(&mut self, item: &str) -> Result<(), ClientError> {
+        let keys = QueueKeys::new(&self.queue);
+        redis::pipe()
+            .atomic()
+            .lrem(keys.processing, 1, item)
+            .ignore()
+            .zrem(keys.leases, item)
+            .ignore()
+            .query_async(&mut self.connection)
+            .await
+            .map_err(to_client_error)
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for RedisQueueClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let recover_interval = recover_interval(self.visibility_timeout);
+        let mut recovered_at = Instant::now();
+        self.recover().await?;
+
+        loop {
+            if recovered_at.elapsed() >= recover_interval {
+                self.recover().await?;
+                recovered_at = Instant::now();
+            }
+
+            let Some(item) = self.take().await? else {
+                continue;
+            };
+            match to_raw_message(&item) {
+                Ok(raw_message) => {
+                    // on Err the item is acked (removed) like a handled one - requeue would
+                    // redeliver it forever
+                    let callback = recv_callback(raw_message);
+                    tokio::pin!(callback);
+                    let mut renew = tokio::time::interval(renew_interval(self.visibility_timeout));
+                    renew.tick().await;
+                    loop {
+                        tokio::select! {
+                            _ = &mut callback => break,
+                            // failed renewal doesn't interrupt the handling, the next one may succeed
+                            _ = renew.tick() => {
+                                let _ = self.renew_lease(&item).await;
+                            }
+                        }
+                    }
+                }
+                Err(e) => report_error(&self.error_hook, &RawMessage::undecodable(&item), &e),
+            }
+            self.ack(&item).await?;
+        }
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let str_msg: String = msg.into();
+        redis::Script::new(PUSH_SCRIPT)
+            .key(&self.queue)
+            .key(QueueKeys::new(&self.queue).sequence)
+            .arg(str_msg)
+            .invoke_async(&mut self.connection)
+            .await
+            .map_err(to_client_error)
+    }
+
+    fn set_error_hook(&mut self, error_hook: Arc<ErrorHookFn>) {
+        self.error_hook = Some(error_hook);
+    }
+}
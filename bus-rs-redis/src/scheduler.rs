@@ -0,0 +1,100 @@
+use std::{
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bus_rs::{scheduler::Scheduler, ClientError, RawMessage};
+
+use crate::to_client_error;
+
+// Member of the sorted set is prefixed by unique sequence number, so the same message
+// can be scheduled more than once.
+pub(crate) const SCHEDULE_SCRIPT: &str = r"
+local id = redis.call('INCR', KEYS[2])
+redis.call('ZADD', KEYS[1], ARGV[1], id .. ':' .. ARGV[2])
+";
+
+// Due messages are removed and published atomically, so many schedulers can work on the same set.
+pub(crate) const DISPATCH_SCRIPT: &str = r"
+local due = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, ARGV[2])
+for _, member in ipairs(due) do
+    redis.call('ZREM', KEYS[1], member)
+    local separator = string.find(member, ':', 1, true)
+    redis.call('PUBLISH', ARGV[3], string.sub(member, separator + 1))
+end
+return #due
+";
+
+pub(crate) const DISPATCH_LIMIT: usize = 100;
+
+/// Scheduler which parks messages in the sorted set `<channel>:scheduled` scored by their due time.
+/// `run` (or `dispatch_due`) moves due messages onto the channel.
+pub struct RedisScheduler {
+    connection: Box<redis::Connection>,
+    channel: String,
+}
+
+impl RedisScheduler {
+    pub fn new(addr: &str, channel: String) -> RedisScheduler {
+        let redis_client = redis::Client::open(addr).unwrap();
+        let conn = redis_client.get_connection().unwrap();
+        RedisScheduler {
+            connection: Box::new(conn),
+            channel,
+        }
+    }
+
+    /// Publish messages which are due. Returns number of published messages.
+    pub fn dispatch_due(&mut self) -> Result<usize, ClientError> {
+        let mut dispatched = 0;
+        loop {
+            let count: usize = redis::Script::new(DISPATCH_SCRIPT)
+                .key(scheduled_key(&self.channel))
+                .arg(to_score(SystemTime::now()))
+                .arg(DISPATCH_LIMIT)
+                .arg(self.channel.as_str())
+                .invoke(self.connection.as_mut())
+                .map_err(to_client_error)?;
+            dispatched += count;
+            if count < DISPATCH_LIMIT {
+                return Ok(dispatched);
+            }
+        }
+    }
+
+    /// Dispatch due messages every `poll_interval`. Returns only on error.
+    pub fn run(&mut self, poll_interval: Duration) -> Result<(), ClientError> {
+        loop {
+            self.dispatch_due()?;
+            thread::sleep(poll_interval);
+        }
+    }
+}
+
+impl Scheduler for RedisScheduler {
+    fn schedule(&mut self, msg: &RawMessage, due: SystemTime) -> Result<(), ClientError> {
+        let str_msg: String = msg.into();
+        redis::Script::new(SCHEDULE_SCRIPT)
+            .key(scheduled_key(&self.channel))
+            .key(sequence_key(&self.channel))
+            .arg(to_score(due))
+            .arg(str_msg)
+            .invoke(self.connection.as_mut())
+            .map_err(to_client_error)
+    }
+}
+
+pub(crate) fn scheduled_key(channel: &str) -> String {
+    format!("{}:scheduled", channel)
+}
+
+pub(crate) fn sequence_key(channel: &str) -> String {
+    format!("{}:scheduled:seq", channel)
+}
+
+/// Due time as milliseconds since the epoch.
+pub(crate) fn to_score(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
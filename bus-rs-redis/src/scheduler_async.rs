@@ -0,0 +1,70 @@
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use bus_rs::{scheduler::SchedulerAsync, ClientError, RawMessage};
+
+use crate::{
+    scheduler::{
+        scheduled_key, sequence_key, to_score, DISPATCH_LIMIT, DISPATCH_SCRIPT, SCHEDULE_SCRIPT,
+    },
+    to_client_error,
+};
+
+/// Async version of `RedisScheduler` - messages are parked in the sorted set `<channel>:scheduled`.
+pub struct RedisSchedulerAsync {
+    connection: redis::aio::Connection,
+    channel: String,
+}
+
+impl RedisSchedulerAsync {
+    pub async fn new(addr: &str, channel: String) -> RedisSchedulerAsync {
+        let redis_client = redis::Client::open(addr).unwrap();
+        let conn = redis_client.get_async_connection().await.unwrap();
+        RedisSchedulerAsync {
+            connection: conn,
+            channel,
+        }
+    }
+
+    /// Publish messages which are due. Returns number of published messages.
+    pub async fn dispatch_due(&mut self) -> Result<usize, ClientError> {
+        let mut dispatched = 0;
+        loop {
+            let count: usize = redis::Script::new(DISPATCH_SCRIPT)
+                .key(scheduled_key(&self.channel))
+                .arg(to_score(SystemTime::now()))
+                .arg(DISPATCH_LIMIT)
+                .arg(self.channel.as_str())
+                .invoke_async(&mut self.connection)
+                .await
+                .map_err(to_client_error)?;
+            dispatched += count;
+            if count < DISPATCH_LIMIT {
+                return Ok(dispatched);
+            }
+        }
+    }
+
+    /// Dispatch due messages every `poll_interval`. Returns only on error.
+    pub async fn run(&mut self, poll_interval: Duration) -> Result<(), ClientError> {
+        loop {
+            self.dispatch_due().await?;
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulerAsync for RedisSchedulerAsync {
+    async fn schedule(&mut self, msg: &RawMessage, due: SystemTime) -> Result<(), ClientError> {
+        let str_msg: String = msg.into();
+        redis::Script::new(SCHEDULE_SCRIPT)
+            .key(scheduled_key(&self.channel))
+            .key(sequence_key(&self.channel))
+            .arg(to_score(due))
+            .arg(str_msg)
+            .invoke_async(&mut self.connection)
+            .await
+            .map_err(to_client_error)
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    thread,
+};
+
+use bus_rs::{ClientError, RawMessage};
+
+use crate::{
+    frame::{read_frame, write_frame},
+    to_client_error, SocketAddress,
+};
+
+/// Point-to-point transport without a broker - the listener binds the address and accepts connections of many publishers,
+/// publisher connects to the listener. Messages are sent as length-prefixed frames and they're not acknowledged,
+/// so messages in flight are lost when the listener stops.
+pub struct SocketClient {
+    address: SocketAddress,
+    stream: Option<Stream>,
+}
+
+impl SocketClient {
+    pub fn new(addr: &str) -> SocketClient {
+        SocketClient {
+            address: SocketAddress::parse(addr).unwrap(),
+            stream: None,
+        }
+    }
+
+    fn write(&mut self, msg: &RawMessage) -> io::Result<()> {
+        if self.stream.is_none() {
+            let stream = match &self.address {
+                SocketAddress::Tcp(addr) => Stream::Tcp(TcpStream::connect(addr)?),
+                #[cfg(unix)]
+                SocketAddress::Unix(path) => Stream::Unix(UnixStream::connect(path)?),
+            };
+            self.stream = Some(stream);
+        }
+        let result = write_frame(self.stream.as_mut().unwrap(), msg);
+        if result.is_err() {
+            self.stream = None;
+        }
+        result
+    }
+}
+
+impl bus_rs::Client for SocketClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        // connections are read by own threads, messages are handled one by one here
+        let (sender, messages) = mpsc::channel();
+        match &self.address {
+            SocketAddress::Tcp(addr) => {
+                let listener = TcpListener::bind(addr).map_err(to_client_error)?;
+                thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        let sender = sender.clone();
+                        thread::spawn(move || read_connection(stream, sender));
+                    }
+                });
+            }
+            #[cfg(unix)]
+            SocketAddress::Unix(path) => {
+                // socket file of the previous listener
+                let _ = fs::remove_file(path);
+                let listener = UnixListener::bind(path).map_err(to_client_error)?;
+                thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        let sender = sender.clone();
+                        thread::spawn(move || read_connection(stream, sender));
+                    }
+                });
+            }
+        }
+
+        for raw_message in messages {
+            let _ = recv_callback(raw_message);
+        }
+        Err(ClientError::IO("listener is closed".to_string()))
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        if self.write(msg).is_ok() {
+            return Ok(());
+        }
+        // the listener could be restarted - connect again
+        self.write(msg).map_err(to_client_error)
+    }
+}
+
+/// Connection with invalid frame is closed.
+fn read_connection(mut stream: impl Read, sender: mpsc::Sender<RawMessage>) {
+    while let Ok(Some(raw_message)) = read_frame(&mut stream) {
+        if sender.send(raw_message).is_err() {
+            break;
+        }
+    }
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
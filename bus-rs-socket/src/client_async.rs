@@ -0,0 +1,114 @@
+use std::{fs, io, sync::Arc};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+
+use crate::{
+    frame::{read_frame_async, write_frame_async},
+    to_client_error, SocketAddress,
+};
+
+type Stream = Box<dyn AsyncWrite + Unpin + Send + Sync>;
+
+/// Async version of `SocketClient`.
+pub struct SocketClientAsync {
+    address: SocketAddress,
+    stream: Option<Stream>,
+}
+
+impl SocketClientAsync {
+    pub fn new(addr: &str) -> SocketClientAsync {
+        SocketClientAsync {
+            address: SocketAddress::parse(addr).unwrap(),
+            stream: None,
+        }
+    }
+
+    async fn write(&mut self, msg: &RawMessage) -> io::Result<()> {
+        if self.stream.is_none() {
+            let stream: Stream = match &self.address {
+                SocketAddress::Tcp(addr) => Box::new(TcpStream::connect(addr).await?),
+                #[cfg(unix)]
+                SocketAddress::Unix(path) => Box::new(UnixStream::connect(path).await?),
+            };
+            self.stream = Some(stream);
+        }
+        let result = write_frame_async(self.stream.as_mut().unwrap(), msg).await;
+        if result.is_err() {
+            self.stream = None;
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for SocketClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        // connections are read by own tasks, messages are handled one by one here
+        let (sender, mut messages) = mpsc::unbounded_channel();
+        match &self.address {
+            SocketAddress::Tcp(addr) => {
+                let listener = TcpListener::bind(addr).await.map_err(to_client_error)?;
+                tokio::spawn(async move {
+                    // listener is closed together with the receiver (e.g. the listener task is aborted)
+                    while let Some(Ok((stream, _))) = tokio::select! {
+                        accepted = listener.accept() => Some(accepted),
+                        _ = sender.closed() => None,
+                    } {
+                        tokio::spawn(read_connection(stream, sender.clone()));
+                    }
+                });
+            }
+            #[cfg(unix)]
+            SocketAddress::Unix(path) => {
+                // socket file of the previous listener
+                let _ = fs::remove_file(path);
+                let listener = UnixListener::bind(path).map_err(to_client_error)?;
+                tokio::spawn(async move {
+                    // listener is closed together with the receiver (e.g. the listener task is aborted)
+                    while let Some(Ok((stream, _))) = tokio::select! {
+                        accepted = listener.accept() => Some(accepted),
+                        _ = sender.closed() => None,
+                    } {
+                        tokio::spawn(read_connection(stream, sender.clone()));
+                    }
+                });
+            }
+        }
+
+        while let Some(raw_message) = messages.recv().await {
+            let _ = recv_callback(raw_message).await;
+        }
+        Err(ClientError::IO("listener is closed".to_string()))
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        if self.write(msg).await.is_ok() {
+            return Ok(());
+        }
+        // the listener could be restarted - connect again
+        self.write(msg).await.map_err(to_client_error)
+    }
+}
+
+/// Connection with invalid frame is closed.
+async fn read_connection(
+    mut stream: impl AsyncRead + Unpin,
+    sender: mpsc::UnboundedSender<RawMessage>,
+) {
+    while let Ok(Some(raw_message)) = read_frame_async(&mut stream).await {
+        if sender.send(raw_message).is_err() {
+            break;
+        }
+    }
+}
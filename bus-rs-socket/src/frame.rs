@@ -0,0 +1,76 @@
+//! Length-prefixed JSON framing of the socket transport, `bus-rs-broker` uses it for its TCP protocol too.
+
+use std::io::{self, Read, Write};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Frames larger than this are rejected, so a broken peer can't exhaust the memory.
+pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Frame is the length of the body (4 bytes, big endian) followed by the body - JSON of the value
+/// (`RawMessage` for the socket transport).
+pub fn encode(value: &impl Serialize) -> io::Result<Vec<u8>> {
+    let body = serde_json::to_vec(value)?;
+    if body.len() > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "frame is too large",
+        ));
+    }
+    let mut frame = Vec::with_capacity(4 + body.len());
+    frame.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+pub fn write_frame(writer: &mut impl Write, value: &impl Serialize) -> io::Result<()> {
+    writer.write_all(&encode(value)?)?;
+    writer.flush()
+}
+
+/// Next value of the stream or `None` when the peer closed the connection.
+pub fn read_frame<T: DeserializeOwned>(reader: &mut impl Read) -> io::Result<Option<T>> {
+    let mut length = [0; 4];
+    match reader.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut body = vec![0; body_length(length)?];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+pub async fn write_frame_async(
+    writer: &mut (impl AsyncWrite + Unpin),
+    value: &(impl Serialize + Sync),
+) -> io::Result<()> {
+    writer.write_all(&encode(value)?).await?;
+    writer.flush().await
+}
+
+pub async fn read_frame_async<T: DeserializeOwned>(
+    reader: &mut (impl AsyncRead + Unpin),
+) -> io::Result<Option<T>> {
+    let mut length = [0; 4];
+    match reader.read_exact(&mut length).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut body = vec![0; body_length(length)?];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn body_length(length: [u8; 4]) -> io::Result<usize> {
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "frame is too large",
+        ));
+    }
+    Ok(length)
+}
@@ -0,0 +1,36 @@
+mod client;
+mod client_async;
+pub mod frame;
+
+pub use client::SocketClient;
+pub use client_async::SocketClientAsync;
+
+use std::path::PathBuf;
+
+/// Address of the listener - `tcp://host:port` or `unix:///path/to/socket`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SocketAddress {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl SocketAddress {
+    pub fn parse(addr: &str) -> Result<SocketAddress, bus_rs::ClientError> {
+        if let Some(host) = addr.strip_prefix("tcp://") {
+            return Ok(SocketAddress::Tcp(host.to_string()));
+        }
+        #[cfg(unix)]
+        if let Some(path) = addr.strip_prefix("unix://") {
+            return Ok(SocketAddress::Unix(PathBuf::from(path)));
+        }
+        Err(bus_rs::ClientError::General(format!(
+            "unsupported address {}",
+            addr
+        )))
+    }
+}
+
+pub(crate) fn to_client_error(e: std::io::Error) -> bus_rs::ClientError {
+    bus_rs::ClientError::IO(e.to_string())
+}
@@ -0,0 +1,22 @@
+mod outbox;
+mod queue;
+mod queue_async;
+mod saga;
+
+pub use outbox::{SqliteOutbox, SqliteOutboxStore};
+pub use queue::SqliteClient;
+pub use queue_async::SqliteClientAsync;
+pub use saga::SqliteSagaRepository;
+
+/// Position where the consumer without a cursor starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StartFrom {
+    /// The oldest message kept in the table.
+    Earliest,
+    /// Only messages appended after the consumer is started.
+    Latest,
+}
+
+pub(crate) fn to_client_error(e: rusqlite::Error) -> bus_rs::ClientError {
+    bus_rs::ClientError::General(e.to_string())
+}
@@ -0,0 +1,178 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bus_rs::{
+    before_layers,
+    outbox::{OutboxEntry, OutboxStore},
+    ClientError, MessageConstraints, PubSubLayer, RawMessage,
+};
+use rusqlite::{params, Connection};
+
+use crate::to_client_error;
+
+/// Writes messages into the outbox table using the caller's connection - pass the transaction
+/// (`rusqlite::Transaction` derefs to `Connection`), so the messages are committed together with the data.
+pub struct SqliteOutbox {
+    table: String,
+    layers: Vec<Box<dyn PubSubLayer>>,
+}
+
+impl SqliteOutbox {
+    pub fn new(table: &str) -> SqliteOutbox {
+        SqliteOutbox {
+            table: table.to_string(),
+            layers: vec![],
+        }
+    }
+
+    /// `before` actions are called when the message is written to the outbox - rejected message
+    /// fails `publish`, so the caller can roll back the transaction.
+    pub fn add_layer(mut self, layer: Box<dyn PubSubLayer>) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    pub fn create_table(&self, connection: &Connection) -> Result<(), ClientError> {
+        create_table(connection, &self.table)
+    }
+
+    pub fn publish<TMessage>(
+        &self,
+        connection: &Connection,
+        msg: &TMessage,
+        headers: Option<HashMap<String, String>>,
+    ) -> Result<(), ClientError>
+    where
+        TMessage: MessageConstraints,
+    {
+        let mut raw_msg = RawMessage::from_message(msg, headers);
+        if !before_layers(&self.layers, &mut raw_msg)? {
+            return Ok(());
+        }
+
+        connection
+            .execute(
+                &format!(
+                    "INSERT INTO {} (msg_type, headers, payload, created_at) VALUES (?1, ?2, ?3, ?4)",
+                    self.table
+                ),
+                params![
+                    raw_msg.msg_type,
+                    serde_json::to_string(&raw_msg.headers).unwrap(),
+                    raw_msg.payload,
+                    to_millis(SystemTime::now()),
+                ],
+            )
+            .map_err(to_client_error)?;
+
+        self.layers.iter().rev().for_each(|l| {
+            l.after(&raw_msg);
+        });
+        Ok(())
+    }
+}
+
+/// Outbox store read by `OutboxRelay`. It has to use own connection to the database file.
+pub struct SqliteOutboxStore {
+    connection: Connection,
+    table: String,
+}
+
+impl SqliteOutboxStore {
+    pub fn new(path: &str, table: &str) -> SqliteOutboxStore {
+        let connection = Connection::open(path).unwrap();
+        // relay waits for the transactions of the writers instead of failing
+        connection.busy_timeout(Duration::from_secs(5)).unwrap();
+        create_table(&connection, table).unwrap();
+        SqliteOutboxStore {
+            connection,
+            table: table.to_string(),
+        }
+    }
+
+    /// Remove messages dispatched before given time. Returns number of removed messages.
+    pub fn purge_dispatched(&mut self, before: SystemTime) -> Result<usize, ClientError> {
+        self.connection
+            .execute(
+                &format!(
+                    "DELETE FROM {} WHERE dispatched_at IS NOT NULL AND dispatched_at < ?1",
+                    self.table
+                ),
+                params![to_millis(before)],
+            )
+            .map_err(to_client_error)
+    }
+}
+
+impl OutboxStore for SqliteOutboxStore {
+    fn pending(&mut self, limit: usize) -> Result<Vec<OutboxEntry>, ClientError> {
+        let mut statement = self
+            .connection
+            .prepare_cached(&format!(
+                "SELECT id, msg_type, headers, payload FROM {} WHERE dispatched_at IS NULL ORDER BY id LIMIT ?1",
+                self.table
+            ))
+            .map_err(to_client_error)?;
+        let rows = statement
+            .query_map(params![limit as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .map_err(to_client_error)?
+            .collect::<Result<Vec<(i64, String, String, String)>, _>>()
+            .map_err(to_client_error)?;
+        // message with malformed headers fails the relay instead of being published without them -
+        // the relay stops on it, so the order of messages is kept until the row is fixed
+        rows.into_iter()
+            .map(|(id, msg_type, headers, payload)| {
+                let headers = serde_json::from_str(&headers).map_err(|e| {
+                    ClientError::General(format!(
+                        "headers of outbox message {} can't be decoded: {}",
+                        id, e
+                    ))
+                })?;
+                Ok(OutboxEntry {
+                    id,
+                    message: RawMessage {
+                        msg_type,
+                        headers,
+                        payload,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn mark_dispatched(&mut self, id: i64) -> Result<(), ClientError> {
+        self.connection
+            .execute(
+                &format!("UPDATE {} SET dispatched_at = ?1 WHERE id = ?2", self.table),
+                params![to_millis(SystemTime::now()), id],
+            )
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+}
+
+fn create_table(connection: &Connection, table: &str) -> Result<(), ClientError> {
+    connection
+        .execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {table} (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                msg_type TEXT NOT NULL,
+                headers TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                dispatched_at INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS {table}_pending ON {table} (dispatched_at, id);"
+        ))
+        .map_err(to_client_error)
+}
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
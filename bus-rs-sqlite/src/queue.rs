@@ -0,0 +1,280 @@
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bus_rs::{ClientError, RawMessage};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{to_client_error, StartFrom};
+
+pub(crate) const DEFAULT_CONSUMER: &str = "default";
+pub(crate) const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const READ_LIMIT: i64 = 100;
+
+/// Durable local queue in the SQLite file - messages of every channel are kept in `bus_messages`
+/// table and every consumer has own cursor (`bus_cursors`). The cursor is moved after the message
+/// is handled, so messages are received again after restart when they weren't handled (at-least-once delivery).
+/// Consumers with different names receive all messages of the channel. Consumer without a cursor starts according
+/// to `start_from`.
+pub struct SqliteClient {
+    log: Mutex<MessageLog>,
+    channel: String,
+    consumer: String,
+    start_from: StartFrom,
+    poll_interval: Duration,
+}
+
+impl SqliteClient {
+    pub fn new(path: &str, channel: String) -> SqliteClient {
+        SqliteClient {
+            log: Mutex::new(MessageLog::open(path)),
+            channel,
+            consumer: DEFAULT_CONSUMER.to_string(),
+            start_from: StartFrom::Earliest,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Name of the consumer whose cursor is used by the receiver.
+    pub fn consumer(mut self, consumer: &str) -> Self {
+        self.consumer = consumer.to_string();
+        self
+    }
+
+    /// Position where the consumer without a cursor starts, default is `StartFrom::Earliest`.
+    pub fn start_from(mut self, start_from: StartFrom) -> Self {
+        self.start_from = start_from;
+        self
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Remove messages of the channel which are already handled by all known consumers (which have a cursor).
+    /// Returns number of removed messages.
+    pub fn purge_consumed(&mut self) -> Result<usize, ClientError> {
+        self.log.get_mut().unwrap().purge_consumed(&self.channel)
+    }
+
+    /// Remove the cursor of the consumer, so it no longer holds back `purge_consumed`.
+    /// The consumer receives all messages of the channel again when it's started later.
+    pub fn remove_consumer(&mut self) -> Result<(), ClientError> {
+        self.log
+            .get_mut()
+            .unwrap()
+            .remove_cursor(&self.channel, &self.consumer)
+    }
+}
+
+impl bus_rs::Client for SqliteClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let log = self.log.get_mut().unwrap();
+        if self.start_from == StartFrom::Latest {
+            log.start_at_latest(&self.channel, &self.consumer)?;
+        }
+        loop {
+            let messages = log.read(&self.channel, &self.consumer)?;
+            if messages.is_empty() {
+                thread::sleep(self.poll_interval);
+                continue;
+            }
+
+            for (id, raw_message) in messages {
+                // on Err the offset is committed anyway, so the message doesn't block the next
+                // ones - it stays in the log and can be read again by a new consumer
+                let _ = recv_callback(raw_message);
+                log.commit(&self.channel, &self.consumer, id)?;
+            }
+        }
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        self.log.get_mut().unwrap().append(&self.channel, msg)
+    }
+
+    fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+        // batch is written in one transaction, so the messages share its result
+        let result = self.log.get_mut().unwrap().append_all(&self.channel, msgs);
+        msgs.iter()
+            .map(|_| match &result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(ClientError::General(e.to_string())),
+            })
+            .collect()
+    }
+}
+
+pub(crate) struct MessageLog {
+    connection: Connection,
+}
+
+impl MessageLog {
+    pub(crate) fn open(path: &str) -> MessageLog {
+        let connection = Connection::open(path).unwrap();
+        // publishers and consumers of other processes wait for each other instead of failing
+        connection.busy_timeout(Duration::from_secs(5)).unwrap();
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                CREATE TABLE IF NOT EXISTS bus_messages (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    channel TEXT NOT NULL,
+                    msg_type TEXT NOT NULL,
+                    headers TEXT NOT NULL,
+                    payload TEXT NOT NULL,
+                    created_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS bus_messages_channel_id ON bus_messages (channel, id);
+                CREATE TABLE IF NOT EXISTS bus_cursors (
+                    consumer TEXT NOT NULL,
+                    channel TEXT NOT NULL,
+                    position INTEGER NOT NULL,
+                    PRIMARY KEY (consumer, channel)
+                );",
+            )
+            .unwrap();
+        MessageLog { connection }
+    }
+
+    pub(crate) fn append(&mut self, channel: &str, msg: &RawMessage) -> Result<(), ClientError> {
+        insert(&self.connection, channel, msg).map_err(to_client_error)
+    }
+
+    pub(crate) fn append_all(
+        &mut self,
+        channel: &str,
+        msgs: &[RawMessage],
+    ) -> Result<(), rusqlite::Error> {
+        let transaction = self.connection.transaction()?;
+        for msg in msgs {
+            insert(&transaction, channel, msg)?;
+        }
+        transaction.commit()
+    }
+
+    /// Messages of the channel after the consumer's cursor, with their ids.
+    pub(crate) fn read(
+        &mut self,
+        channel: &str,
+        consumer: &str,
+    ) -> Result<Vec<(i64, RawMessage)>, ClientError> {
+        let position: i64 = self
+            .connection
+            .query_row(
+                "SELECT position FROM bus_cursors WHERE consumer = ?1 AND channel = ?2",
+                params![consumer, channel],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(to_client_error)?
+            .unwrap_or_default();
+
+        let mut statement = self
+            .connection
+            .prepare_cached(
+                "SELECT id, msg_type, headers, payload FROM bus_messages
+                WHERE channel = ?1 AND id > ?2 ORDER BY id LIMIT ?3",
+            )
+            .map_err(to_client_error)?;
+        let rows = statement
+            .query_map(params![channel, position, READ_LIMIT], |row| {
+                let headers: String = row.get(2)?;
+                Ok((
+                    row.get(0)?,
+                    RawMessage {
+                        msg_type: row.get(1)?,
+                        headers: serde_json::from_str(&headers).unwrap_or_default(),
+                        payload: row.get(3)?,
+                    },
+                ))
+            })
+            .map_err(to_client_error)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(to_client_error)
+    }
+
+    /// Create the cursor of the consumer at the last message of the channel, unless it already has one.
+    pub(crate) fn start_at_latest(
+        &mut self,
+        channel: &str,
+        consumer: &str,
+    ) -> Result<(), ClientError> {
+        self.connection
+            .execute(
+                "INSERT INTO bus_cursors (consumer, channel, position)
+                SELECT ?1, ?2, COALESCE(MAX(id), 0) FROM bus_messages WHERE channel = ?2
+                ON CONFLICT (consumer, channel) DO NOTHING",
+                params![consumer, channel],
+            )
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+
+    /// Move the consumer's cursor to the handled message.
+    pub(crate) fn commit(
+        &mut self,
+        channel: &str,
+        consumer: &str,
+        id: i64,
+    ) -> Result<(), ClientError> {
+        self.connection
+            .execute(
+                "INSERT INTO bus_cursors (consumer, channel, position) VALUES (?1, ?2, ?3)
+                ON CONFLICT (consumer, channel) DO UPDATE SET position = excluded.position",
+                params![consumer, channel, id],
+            )
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+
+    pub(crate) fn remove_cursor(
+        &mut self,
+        channel: &str,
+        consumer: &str,
+    ) -> Result<(), ClientError> {
+        self.connection
+            .execute(
+                "DELETE FROM bus_cursors WHERE consumer = ?1 AND channel = ?2",
+                params![consumer, channel],
+            )
+            .map(|_| ())
+            .map_err(to_client_error)
+    }
+
+    pub(crate) fn purge_consumed(&mut self, channel: &str) -> Result<usize, ClientError> {
+        self.connection
+            .execute(
+                "DELETE FROM bus_messages WHERE channel = ?1 AND id <= (
+                    SELECT COALESCE(MIN(position), 0) FROM bus_cursors WHERE channel = ?1
+                )",
+                params![channel],
+            )
+            .map_err(to_client_error)
+    }
+}
+
+fn insert(connection: &Connection, channel: &str, msg: &RawMessage) -> Result<(), rusqlite::Error> {
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+    connection
+        .execute(
+            "INSERT INTO bus_messages (channel, msg_type, headers, payload, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                channel,
+                msg.msg_type,
+                serde_json::to_string(&msg.headers).unwrap(),
+                msg.payload,
+                created_at,
+            ],
+        )
+        .map(|_| ())
+}
@@ -0,0 +1,116 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+
+use crate::{
+    queue::{MessageLog, DEFAULT_CONSUMER, DEFAULT_POLL_INTERVAL},
+    StartFrom,
+};
+
+/// Async version of `SqliteClient`. SQLite calls are blocking - they're short local file operations.
+pub struct SqliteClientAsync {
+    log: Mutex<MessageLog>,
+    channel: String,
+    consumer: String,
+    start_from: StartFrom,
+    poll_interval: Duration,
+}
+
+impl SqliteClientAsync {
+    pub fn new(path: &str, channel: String) -> SqliteClientAsync {
+        SqliteClientAsync {
+            log: Mutex::new(MessageLog::open(path)),
+            channel,
+            consumer: DEFAULT_CONSUMER.to_string(),
+            start_from: StartFrom::Earliest,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Name of the consumer whose cursor is used by the receiver.
+    pub fn consumer(mut self, consumer: &str) -> Self {
+        self.consumer = consumer.to_string();
+        self
+    }
+
+    /// Position where the consumer without a cursor starts, default is `StartFrom::Earliest`.
+    pub fn start_from(mut self, start_from: StartFrom) -> Self {
+        self.start_from = start_from;
+        self
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Remove messages of the channel which are already handled by all known consumers (which have a cursor).
+    /// Returns number of removed messages.
+    pub fn purge_consumed(&mut self) -> Result<usize, ClientError> {
+        self.log.get_mut().unwrap().purge_consumed(&self.channel)
+    }
+
+    /// Remove the cursor of the consumer, so it no longer holds back `purge_consumed`.
+    /// The consumer receives all messages of the channel again when it's started later.
+    pub fn remove_consumer(&mut self) -> Result<(), ClientError> {
+        self.log
+            .get_mut()
+            .unwrap()
+            .remove_cursor(&self.channel, &self.consumer)
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for SqliteClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        if self.start_from == StartFrom::Latest {
+            self.log
+                .get_mut()
+                .unwrap()
+                .start_at_latest(&self.channel, &self.consumer)?;
+        }
+        loop {
+            let messages = self
+                .log
+                .get_mut()
+                .unwrap()
+                .read(&self.channel, &self.consumer)?;
+            if messages.is_empty() {
+                tokio::time::sleep(self.poll_interval).await;
+                continue;
+            }
+
+            for (id, raw_message) in messages {
+                // on Err the offset is committed anyway, so the message doesn't block the next
+                // ones - it stays in the log and can be read again by a new consumer
+                let _ = recv_callback(raw_message).await;
+                self.log
+                    .get_mut()
+                    .unwrap()
+                    .commit(&self.channel, &self.consumer, id)?;
+            }
+        }
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        self.log.get_mut().unwrap().append(&self.channel, msg)
+    }
+
+    async fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+        // batch is written in one transaction, so the messages share its result
+        let result = self.log.get_mut().unwrap().append_all(&self.channel, msgs);
+        msgs.iter()
+            .map(|_| match &result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(ClientError::General(e.to_string())),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,136 @@
+use std::{sync::Mutex, time::Duration};
+
+use async_trait::async_trait;
+use bus_rs::{
+    saga::{conflict_error, SagaRecord, SagaRepository},
+    ClientError,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::to_client_error;
+
+/// Durable `SagaRepository` in the SQLite file - states of all sagas are kept in `bus_sagas` table.
+/// SQLite calls are blocking - they're short local file operations.
+pub struct SqliteSagaRepository {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteSagaRepository {
+    pub fn new(path: &str) -> SqliteSagaRepository {
+        let connection = Connection::open(path).unwrap();
+        // instances of the service sharing the file wait for each other instead of failing
+        connection.busy_timeout(Duration::from_secs(5)).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS bus_sagas (
+                    saga TEXT NOT NULL,
+                    key TEXT NOT NULL,
+                    state TEXT NOT NULL,
+                    commands TEXT NOT NULL,
+                    completed INTEGER NOT NULL,
+                    version INTEGER NOT NULL,
+                    PRIMARY KEY (saga, key)
+                );",
+            )
+            .unwrap();
+        SqliteSagaRepository {
+            connection: Mutex::new(connection),
+        }
+    }
+}
+
+#[async_trait]
+impl SagaRepository for SqliteSagaRepository {
+    async fn load(&self, saga: &str, key: &str) -> Result<Option<SagaRecord>, ClientError> {
+        let row: Option<(String, String, bool, i64)> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT state, commands, completed, version FROM bus_sagas WHERE saga = ?1 AND key = ?2",
+                params![saga, key],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()
+            .map_err(to_client_error)?;
+        let Some((state, commands, completed, version)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(SagaRecord {
+            state,
+            commands: serde_json::from_str(&commands)
+                .map_err(|e| ClientError::General(e.to_string()))?,
+            completed,
+            version: version as u64,
+        }))
+    }
+
+    async fn save(
+        &self,
+        saga: &str,
+        key: &str,
+        record: SagaRecord,
+        expected_version: Option<u64>,
+    ) -> Result<u64, ClientError> {
+        let commands = serde_json::to_string(&record.commands)
+            .map_err(|e| ClientError::General(e.to_string()))?;
+        let connection = self.connection.lock().unwrap();
+        let changed = match expected_version {
+            None => connection.execute(
+                "INSERT INTO bus_sagas (saga, key, state, commands, completed, version)
+                VALUES (?1, ?2, ?3, ?4, ?5, 1) ON CONFLICT (saga, key) DO NOTHING",
+                params![saga, key, record.state, commands, record.completed],
+            ),
+            Some(version) => connection.execute(
+                "UPDATE bus_sagas SET state = ?3, commands = ?4, completed = ?5, version = version + 1
+                WHERE saga = ?1 AND key = ?2 AND version = ?6",
+                params![
+                    saga,
+                    key,
+                    record.state,
+                    commands,
+                    record.completed,
+                    version as i64
+                ],
+            ),
+        }
+        .map_err(to_client_error)?;
+        if changed == 0 {
+            return Err(conflict_error(saga, key));
+        }
+        Ok(expected_version.unwrap_or_default() + 1)
+    }
+
+    async fn delete(
+        &self,
+        saga: &str,
+        key: &str,
+        expected_version: Option<u64>,
+    ) -> Result<(), ClientError> {
+        let connection = self.connection.lock().unwrap();
+        let Some(version) = expected_version else {
+            // there is nothing to delete, unless the state was saved in the meantime
+            let exists: bool = connection
+                .query_row(
+                    "SELECT EXISTS (SELECT 1 FROM bus_sagas WHERE saga = ?1 AND key = ?2)",
+                    params![saga, key],
+                    |row| row.get(0),
+                )
+                .map_err(to_client_error)?;
+            return match exists {
+                true => Err(conflict_error(saga, key)),
+                false => Ok(()),
+            };
+        };
+        let deleted = connection
+            .execute(
+                "DELETE FROM bus_sagas WHERE saga = ?1 AND key = ?2 AND version = ?3",
+                params![saga, key, version as i64],
+            )
+            .map_err(to_client_error)?;
+        if deleted == 0 {
+            return Err(conflict_error(saga, key));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use bus_rs::{Client, ClientError, MessageConstraints, RawMessage};
+
+use crate::{
+    state::{MockState, Next},
+    DEFAULT_DRAIN_TIMEOUT,
+};
+
+/// In-memory client for tests. Sent messages are captured for assertions and injected messages are delivered
+/// to the listener. Clones share the state - give one clone to the publisher or listener and keep the other.
+#[derive(Clone)]
+pub struct MockClient {
+    state: Arc<MockState>,
+    drain_timeout: Duration,
+}
+
+impl MockClient {
+    pub fn new() -> Self {
+        MockClient {
+            state: Arc::new(MockState::default()),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+        }
+    }
+
+    /// Default is `DEFAULT_DRAIN_TIMEOUT`.
+    pub fn drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = drain_timeout;
+        self
+    }
+
+    /// Messages sent through the client, in order.
+    pub fn sent(&self) -> Vec<RawMessage> {
+        self.state.sent()
+    }
+
+    /// Sent messages of the type. Panics when the payload of any of them can't be decoded.
+    #[track_caller]
+    pub fn published<TMessage>(&self) -> Vec<TMessage>
+    where
+        TMessage: MessageConstraints,
+    {
+        self.state.published::<TMessage>()
+    }
+
+    /// Panics when no sent message of the type matches the predicate.
+    #[track_caller]
+    pub fn assert_published<TMessage>(&self, predicate: impl Fn(&TMessage) -> bool)
+    where
+        TMessage: MessageConstraints,
+    {
+        self.state.assert_published(predicate);
+    }
+
+    #[track_caller]
+    pub fn assert_not_published<TMessage>(&self)
+    where
+        TMessage: MessageConstraints,
+    {
+        self.state.assert_not_published::<TMessage>();
+    }
+
+    /// Deliver the message to the listener, as if it came from the transport.
+    pub fn inject<TMessage>(&self, msg: &TMessage, headers: Option<HashMap<String, String>>)
+    where
+        TMessage: MessageConstraints,
+    {
+        self.state.inject(RawMessage::from_message(msg, headers));
+    }
+
+    pub fn inject_raw(&self, raw_msg: RawMessage) {
+        self.state.inject(raw_msg);
+    }
+
+    /// Stop the receiver once the injected messages are handled, so `listen` returns.
+    pub fn close(&self) {
+        self.state.close();
+    }
+
+    /// Wait until the listener has handled all injected messages. Panics after the drain timeout.
+    #[track_caller]
+    pub fn drain_until_idle(&self) {
+        if !self.state.wait_idle(self.drain_timeout) {
+            panic!(
+                "injected messages were not handled in {:?} - is the listener listening?",
+                self.drain_timeout
+            );
+        }
+    }
+}
+
+impl Default for MockClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Client for MockClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        loop {
+            match self.state.wait_next() {
+                Next::Message(raw_msg) => {
+                    // Err is dropped - the message counts as done,
+                    // so `drain_until_idle` doesn't wait for it
+                    let _ = recv_callback(raw_msg);
+                    self.state.done();
+                }
+                Next::Empty => {}
+                Next::Closed => return Ok(()),
+            }
+        }
+    }
+
+    fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        self.state.record_sent(msg);
+        Ok(())
+    }
+}
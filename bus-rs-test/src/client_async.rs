@@ -0,0 +1,129 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use bus_rs::{ClientAsync, ClientCallbackFnAsync, ClientError, MessageConstraints, RawMessage};
+
+use crate::{
+    state::{MockState, Next},
+    DEFAULT_DRAIN_TIMEOUT,
+};
+
+/// Async version of `MockClient`.
+#[derive(Clone)]
+pub struct MockClientAsync {
+    state: Arc<MockState>,
+    drain_timeout: Duration,
+}
+
+impl MockClientAsync {
+    pub fn new() -> Self {
+        MockClientAsync {
+            state: Arc::new(MockState::default()),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+        }
+    }
+
+    /// Default is `DEFAULT_DRAIN_TIMEOUT`.
+    pub fn drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = drain_timeout;
+        self
+    }
+
+    /// Messages sent through the client, in order.
+    pub fn sent(&self) -> Vec<RawMessage> {
+        self.state.sent()
+    }
+
+    /// Sent messages of the type. Panics when the payload of any of them can't be decoded.
+    #[track_caller]
+    pub fn published<TMessage>(&self) -> Vec<TMessage>
+    where
+        TMessage: MessageConstraints,
+    {
+        self.state.published::<TMessage>()
+    }
+
+    /// Panics when no sent message of the type matches the predicate.
+    #[track_caller]
+    pub fn assert_published<TMessage>(&self, predicate: impl Fn(&TMessage) -> bool)
+    where
+        TMessage: MessageConstraints,
+    {
+        self.state.assert_published(predicate);
+    }
+
+    #[track_caller]
+    pub fn assert_not_published<TMessage>(&self)
+    where
+        TMessage: MessageConstraints,
+    {
+        self.state.assert_not_published::<TMessage>();
+    }
+
+    /// Deliver the message to the listener, as if it came from the transport.
+    pub fn inject<TMessage>(&self, msg: &TMessage, headers: Option<HashMap<String, String>>)
+    where
+        TMessage: MessageConstraints,
+    {
+        self.state.inject(RawMessage::from_message(msg, headers));
+    }
+
+    pub fn inject_raw(&self, raw_msg: RawMessage) {
+        self.state.inject(raw_msg);
+    }
+
+    /// Stop the receiver once the injected messages are handled, so `listen` returns.
+    pub fn close(&self) {
+        self.state.close();
+    }
+
+    /// Wait until the listener has handled all injected messages. Panics after the drain timeout.
+    pub async fn drain_until_idle(&self) {
+        let started = Instant::now();
+        while !self.state.is_idle() {
+            if started.elapsed() > self.drain_timeout {
+                panic!(
+                    "injected messages were not handled in {:?} - is the listener listening?",
+                    self.drain_timeout
+                );
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+}
+
+impl Default for MockClientAsync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ClientAsync for MockClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        loop {
+            match self.state.next() {
+                Next::Message(raw_msg) => {
+                    // Err is dropped - the message counts as done,
+                    // so `drain_until_idle` doesn't wait for it
+                    let _ = recv_callback(raw_msg).await;
+                    self.state.done();
+                }
+                Next::Empty => self.state.wait_injected().await,
+                Next::Closed => return Ok(()),
+            }
+        }
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        self.state.record_sent(msg);
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+mod client;
+mod client_async;
+mod state;
+
+pub use client::MockClient;
+pub use client_async::MockClientAsync;
+
+use std::time::Duration;
+
+/// Time after which `drain_until_idle` gives up - the listener is probably not listening.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
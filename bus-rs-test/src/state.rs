@@ -0,0 +1,159 @@
+use std::{
+    collections::VecDeque,
+    sync::{Condvar, Mutex},
+    time::Duration,
+};
+
+use bus_rs::{MessageConstraints, RawMessage};
+use tokio::sync::Notify;
+
+/// State shared by the clones of the mock client - the test keeps one clone and the publisher or listener the other.
+#[derive(Default)]
+pub(crate) struct MockState {
+    inner: Mutex<Inner>,
+    changed: Condvar,
+    injected: Notify,
+}
+
+#[derive(Default)]
+struct Inner {
+    sent: Vec<RawMessage>,
+    inbox: VecDeque<RawMessage>,
+    in_flight: usize,
+    closed: bool,
+}
+
+pub(crate) enum Next {
+    Message(RawMessage),
+    Empty,
+    Closed,
+}
+
+impl MockState {
+    pub(crate) fn record_sent(&self, raw_msg: &RawMessage) {
+        self.inner.lock().unwrap().sent.push(raw_msg.clone());
+    }
+
+    pub(crate) fn sent(&self) -> Vec<RawMessage> {
+        self.inner.lock().unwrap().sent.clone()
+    }
+
+    pub(crate) fn inject(&self, raw_msg: RawMessage) {
+        self.inner.lock().unwrap().inbox.push_back(raw_msg);
+        self.changed.notify_all();
+        self.injected.notify_one();
+    }
+
+    pub(crate) fn close(&self) {
+        self.inner.lock().unwrap().closed = true;
+        self.changed.notify_all();
+        self.injected.notify_one();
+    }
+
+    /// Next injected message, which is in flight until `done` is called.
+    pub(crate) fn next(&self) -> Next {
+        Self::take(&mut self.inner.lock().unwrap())
+    }
+
+    /// Blocking version of `next` - it never returns `Next::Empty`.
+    pub(crate) fn wait_next(&self) -> Next {
+        let inner = self.inner.lock().unwrap();
+        let mut inner = self
+            .changed
+            .wait_while(inner, |inner| inner.inbox.is_empty() && !inner.closed)
+            .unwrap();
+        Self::take(&mut inner)
+    }
+
+    pub(crate) async fn wait_injected(&self) {
+        self.injected.notified().await;
+    }
+
+    pub(crate) fn done(&self) {
+        self.inner.lock().unwrap().in_flight -= 1;
+        self.changed.notify_all();
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.inbox.is_empty() && inner.in_flight == 0
+    }
+
+    /// Returns false when the messages were not handled in time.
+    pub(crate) fn wait_idle(&self, timeout: Duration) -> bool {
+        let inner = self.inner.lock().unwrap();
+        let (_inner, result) = self
+            .changed
+            .wait_timeout_while(inner, timeout, |inner| {
+                !inner.inbox.is_empty() || inner.in_flight > 0
+            })
+            .unwrap();
+        !result.timed_out()
+    }
+
+    /// Panics when the payload of the sent message doesn't match the type - the test sent a different message
+    /// under the same name.
+    #[track_caller]
+    pub(crate) fn published<TMessage>(&self) -> Vec<TMessage>
+    where
+        TMessage: MessageConstraints,
+    {
+        // the lock is not held while panicking, so the state isn't poisoned for other checks
+        let mut published = Vec::new();
+        for raw_msg in self.sent() {
+            if raw_msg.msg_type != TMessage::name() {
+                continue;
+            }
+            match serde_json::from_str(&raw_msg.payload) {
+                Ok(msg) => published.push(msg),
+                Err(e) => panic!(
+                    "sent message can't be decoded as {}: {}, raw message: {:?}",
+                    TMessage::name(),
+                    e,
+                    raw_msg
+                ),
+            }
+        }
+        published
+    }
+
+    #[track_caller]
+    pub(crate) fn assert_published<TMessage>(&self, predicate: impl Fn(&TMessage) -> bool)
+    where
+        TMessage: MessageConstraints,
+    {
+        if !self.published::<TMessage>().iter().any(predicate) {
+            panic!(
+                "no matching {} was published, sent messages: {:?}",
+                TMessage::name(),
+                self.sent()
+            );
+        }
+    }
+
+    #[track_caller]
+    pub(crate) fn assert_not_published<TMessage>(&self)
+    where
+        TMessage: MessageConstraints,
+    {
+        let published = self.published::<TMessage>();
+        if !published.is_empty() {
+            panic!(
+                "{} {} messages were published, expected none",
+                published.len(),
+                TMessage::name()
+            );
+        }
+    }
+
+    fn take(inner: &mut Inner) -> Next {
+        match inner.inbox.pop_front() {
+            Some(raw_msg) => {
+                inner.in_flight += 1;
+                Next::Message(raw_msg)
+            }
+            None if inner.closed => Next::Closed,
+            None => Next::Empty,
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use futures_util::future::join_all;
+use reqwest::{header::CONTENT_TYPE, StatusCode};
+
+use crate::{signature, HEADER_PREFIX, MSG_TYPE_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+const DEFAULT_RETRIES: u32 = 3;
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Endpoint receiving the messages. Requests are signed when the endpoint has a secret.
+#[derive(Clone, Debug)]
+pub struct Endpoint {
+    url: String,
+    secret: Option<String>,
+}
+
+impl Endpoint {
+    pub fn new(url: &str) -> Endpoint {
+        Endpoint {
+            url: url.to_string(),
+            secret: None,
+        }
+    }
+
+    pub fn secret(mut self, secret: &str) -> Self {
+        self.secret = Some(secret.to_string());
+        self
+    }
+}
+
+/// Delivery results of the endpoint.
+#[derive(Clone, Debug, Default)]
+pub struct EndpointStatus {
+    /// Failed deliveries since the last successful one - it's reset by the successful delivery.
+    pub consecutive_failures: u32,
+    pub total_failures: u64,
+    pub last_error: Option<String>,
+    pub last_success: Option<SystemTime>,
+}
+
+/// Delivery results of all endpoints of the client, keyed by URL. It's shared with the client, so it can be read
+/// after the client is moved to the publisher.
+#[derive(Clone, Default)]
+pub struct WebhookFailures {
+    statuses: Arc<Mutex<HashMap<String, EndpointStatus>>>,
+}
+
+impl WebhookFailures {
+    pub fn status(&self, url: &str) -> Option<EndpointStatus> {
+        self.statuses.lock().unwrap().get(url).cloned()
+    }
+
+    fn record(&self, url: &str, result: &Result<(), String>) {
+        let mut statuses = self.statuses.lock().unwrap();
+        let status = statuses.entry(url.to_string()).or_default();
+        match result {
+            Ok(()) => {
+                status.consecutive_failures = 0;
+                status.last_success = Some(SystemTime::now());
+            }
+            Err(e) => {
+                status.consecutive_failures += 1;
+                status.total_failures += 1;
+                status.last_error = Some(e.clone());
+            }
+        }
+    }
+}
+
+/// Egress transport - every message is delivered by HTTP POST to all endpoints. The body is the payload of the message,
+/// its type is in `bus-msg-type` header and its headers are in `bus-header-<name>` headers (names are lowercased).
+/// Failed requests (connection errors, 429 and 5xx responses) are retried with the exponential backoff.
+/// Sending fails when any endpoint didn't accept the message, endpoints which accepted it are not skipped when it's sent again.
+pub struct WebhookClientAsync {
+    http: reqwest::Client,
+    endpoints: Vec<Endpoint>,
+    retries: u32,
+    backoff: Duration,
+    failures: WebhookFailures,
+}
+
+impl WebhookClientAsync {
+    pub fn new(endpoints: Vec<Endpoint>) -> WebhookClientAsync {
+        WebhookClientAsync {
+            http: http_client(DEFAULT_TIMEOUT),
+            endpoints,
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            failures: WebhookFailures::default(),
+        }
+    }
+
+    /// Number of retries after the failed request. Default is 3.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Delay before the first retry, it's doubled with every next one. Default is 500 ms.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Timeout of one request. Default is 10 s.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.http = http_client(timeout);
+        self
+    }
+
+    pub fn failures(&self) -> WebhookFailures {
+        self.failures.clone()
+    }
+
+    async fn deliver(&self, endpoint: &Endpoint, msg: &RawMessage) -> Result<(), String> {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+        let result = loop {
+            match self.post(endpoint, msg).await {
+                Ok(()) => break Ok(()),
+                Err((e, retryable)) if !retryable || attempt >= self.retries => break Err(e),
+                Err(_) => {}
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        };
+        self.failures.record(&endpoint.url, &result);
+        result
+    }
+
+    /// Error contains the flag whether the request can be retried.
+    async fn post(&self, endpoint: &Endpoint, msg: &RawMessage) -> Result<(), (String, bool)> {
+        let mut request = self
+            .http
+            .post(&endpoint.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(MSG_TYPE_HEADER, &msg.msg_type);
+        for (name, value) in &msg.headers {
+            request = request.header(format!("{}{}", HEADER_PREFIX, name), value);
+        }
+        if let Some(secret) = &endpoint.secret {
+            let timestamp = signature::now();
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(
+                    SIGNATURE_HEADER,
+                    signature::sign(
+                        secret,
+                        timestamp,
+                        &msg.msg_type,
+                        &msg.headers,
+                        msg.payload.as_bytes(),
+                    ),
+                );
+        }
+
+        let response = request
+            .body(msg.payload.clone())
+            .send()
+            .await
+            .map_err(|e| {
+                // invalid URL or header can't be fixed by sending the request again
+                let retryable = !e.is_builder();
+                (e.to_string(), retryable)
+            })?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let retryable = status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS;
+        Err((format!("{} responded {}", endpoint.url, status), retryable))
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for WebhookClientAsync {
+    async fn receiver(
+        &mut self,
+        _recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        Err(ClientError::General(
+            "webhook client only delivers messages - use WebhookIngressAsync to receive them"
+                .to_string(),
+        ))
+    }
+
+    async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+        let results = join_all(
+            self.endpoints
+                .iter()
+                .map(|endpoint| self.deliver(endpoint, msg)),
+        )
+        .await;
+        let errors: Vec<String> = results.into_iter().filter_map(Result::err).collect();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(ClientError::General(errors.join("; ")))
+    }
+}
+
+fn http_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder().timeout(timeout).build().unwrap()
+}
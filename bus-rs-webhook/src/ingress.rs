@@ -0,0 +1,170 @@
+use std::{collections::HashMap, convert::Infallible, sync::Arc};
+
+use async_trait::async_trait;
+use bus_rs::{ClientCallbackFnAsync, ClientError, RawMessage};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::{
+    body::{Bytes, Incoming},
+    header::HeaderMap,
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+use crate::{signature, HEADER_PREFIX, MSG_TYPE_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+/// Larger bodies are rejected, so a broken sender can't exhaust the memory.
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// Ingress transport - exposes HTTP endpoint and feeds bodies of POST requests into the listener.
+/// The body is the payload, the type is taken from `bus-msg-type` header (or other configured header)
+/// and `bus-header-<name>` headers become headers of the message.
+/// Responds `202 Accepted` when the message is handled and `422 Unprocessable Entity` when it's rejected by a layer.
+#[derive(Clone)]
+pub struct WebhookIngressAsync {
+    addr: String,
+    path: String,
+    secret: Option<String>,
+    msg_type_header: String,
+    msg_type: Option<String>,
+}
+
+impl WebhookIngressAsync {
+    pub fn new(addr: &str, path: &str) -> WebhookIngressAsync {
+        WebhookIngressAsync {
+            addr: addr.to_string(),
+            path: path.to_string(),
+            secret: None,
+            msg_type_header: MSG_TYPE_HEADER.to_string(),
+            msg_type: None,
+        }
+    }
+
+    /// Secret of the signature - requests without the valid signature are rejected with `401 Unauthorized`.
+    /// The signature covers the type the message is dispatched with, from the configured header or the default type.
+    pub fn secret(mut self, secret: &str) -> Self {
+        self.secret = Some(secret.to_string());
+        self
+    }
+
+    /// Header with the type of the message, e.g. `x-github-event` of GitHub webhooks.
+    pub fn msg_type_header(mut self, header: &str) -> Self {
+        self.msg_type_header = header.to_lowercase();
+        self
+    }
+
+    /// Type of the messages whose request doesn't have the type header.
+    pub fn msg_type(mut self, msg_type: &str) -> Self {
+        self.msg_type = Some(msg_type.to_string());
+        self
+    }
+
+    async fn receive(
+        &self,
+        request: Request<Incoming>,
+        callback: &ClientCallbackFnAsync,
+    ) -> StatusCode {
+        if request.uri().path() != self.path {
+            return StatusCode::NOT_FOUND;
+        }
+        if request.method() != Method::POST {
+            return StatusCode::METHOD_NOT_ALLOWED;
+        }
+        let headers = request.headers().clone();
+        let Ok(body) = Limited::new(request.into_body(), MAX_BODY_SIZE)
+            .collect()
+            .await
+        else {
+            return StatusCode::BAD_REQUEST;
+        };
+        let body = body.to_bytes();
+
+        let forwarded: HashMap<String, String> = headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = name.as_str().strip_prefix(HEADER_PREFIX)?;
+                Some((name.to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+
+        let msg_type = header(&headers, &self.msg_type_header)
+            .map(str::to_string)
+            .or_else(|| self.msg_type.clone());
+        if let Some(secret) = &self.secret {
+            let timestamp = header(&headers, TIMESTAMP_HEADER).unwrap_or_default();
+            let signature = header(&headers, SIGNATURE_HEADER).unwrap_or_default();
+            // signature has to cover the type the message is dispatched with, not just the default type header
+            let signed_type = msg_type.as_deref().unwrap_or_default();
+            if !signature::verify(secret, timestamp, signature, signed_type, &forwarded, &body) {
+                return StatusCode::UNAUTHORIZED;
+            }
+        }
+        let Some(msg_type) = msg_type else {
+            return StatusCode::BAD_REQUEST;
+        };
+        let Ok(payload) = String::from_utf8(body.to_vec()) else {
+            return StatusCode::BAD_REQUEST;
+        };
+
+        match callback(RawMessage {
+            msg_type,
+            headers: forwarded,
+            payload,
+        })
+        .await
+        {
+            Ok(()) => StatusCode::ACCEPTED,
+            // sender shouldn't retry the message rejected by a layer (e.g. invalid schema)
+            Err(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+#[async_trait]
+impl bus_rs::ClientAsync for WebhookIngressAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let listener = TcpListener::bind(&self.addr)
+            .await
+            .map_err(|e| ClientError::IO(e.to_string()))?;
+        let ingress = Arc::new(self.clone());
+        loop {
+            let (stream, _) = listener
+                .accept()
+                .await
+                .map_err(|e| ClientError::IO(e.to_string()))?;
+            let ingress = ingress.clone();
+            let recv_callback = recv_callback.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let ingress = ingress.clone();
+                    let recv_callback = recv_callback.clone();
+                    async move {
+                        let status = ingress.receive(request, recv_callback.as_ref()).await;
+                        let mut response = Response::new(Full::new(Bytes::new()));
+                        *response.status_mut() = status;
+                        Ok::<_, Infallible>(response)
+                    }
+                });
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    }
+
+    async fn send(&mut self, _msg: &RawMessage) -> Result<(), ClientError> {
+        Err(ClientError::General(
+            "webhook ingress only receives messages - use WebhookClientAsync to deliver them"
+                .to_string(),
+        ))
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
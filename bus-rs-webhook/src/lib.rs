@@ -0,0 +1,15 @@
+mod egress;
+mod ingress;
+mod signature;
+
+pub use egress::{Endpoint, EndpointStatus, WebhookClientAsync, WebhookFailures};
+pub use ingress::WebhookIngressAsync;
+
+/// HTTP header with the type of the message.
+pub const MSG_TYPE_HEADER: &str = "bus-msg-type";
+/// Prefix of HTTP headers carrying headers of the message.
+pub const HEADER_PREFIX: &str = "bus-header-";
+/// HMAC-SHA256 of the timestamp, message type, forwarded headers and the body - `sha256=<hex>`.
+pub const SIGNATURE_HEADER: &str = "bus-signature";
+/// Seconds since UNIX epoch, when the request was signed.
+pub const TIMESTAMP_HEADER: &str = "bus-timestamp";
@@ -0,0 +1,89 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Requests signed longer ago (or with the clock skew) are rejected, so captured requests can't be replayed later.
+pub(crate) const TOLERANCE: Duration = Duration::from_secs(5 * 60);
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Signature of the request - `sha256=<hex>` of HMAC of the timestamp, message type, forwarded headers
+/// (names without the prefix, lowercase as in HTTP) and the body.
+pub(crate) fn sign(
+    secret: &str,
+    timestamp: u64,
+    msg_type: &str,
+    headers: &HashMap<String, String>,
+    body: &[u8],
+) -> String {
+    let signature = mac(secret, timestamp, msg_type, headers, body)
+        .finalize()
+        .into_bytes();
+    format!("sha256={}", hex::encode(signature))
+}
+
+pub(crate) fn verify(
+    secret: &str,
+    timestamp: &str,
+    signature: &str,
+    msg_type: &str,
+    headers: &HashMap<String, String>,
+    body: &[u8],
+) -> bool {
+    let Ok(timestamp) = timestamp.parse::<u64>() else {
+        return false;
+    };
+    if now().abs_diff(timestamp) > TOLERANCE.as_secs() {
+        return false;
+    }
+    let Some(signature) = signature
+        .strip_prefix("sha256=")
+        .and_then(|s| hex::decode(s).ok())
+    else {
+        return false;
+    };
+    // comparison in constant time
+    mac(secret, timestamp, msg_type, headers, body)
+        .verify_slice(&signature)
+        .is_ok()
+}
+
+fn mac(
+    secret: &str,
+    timestamp: u64,
+    msg_type: &str,
+    headers: &HashMap<String, String>,
+    body: &[u8],
+) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+    // every part is length prefixed, so moving bytes between fields changes the signature
+    let mut update = |part: &[u8]| {
+        mac.update(&(part.len() as u64).to_be_bytes());
+        mac.update(part);
+    };
+    update(timestamp.to_string().as_bytes());
+    update(msg_type.as_bytes());
+    let mut headers: Vec<(String, &String)> = headers
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), value))
+        .collect();
+    headers.sort();
+    update(&(headers.len() as u64).to_be_bytes());
+    for (name, value) in headers {
+        update(name.as_bytes());
+        update(value.as_bytes());
+    }
+    update(body);
+    mac
+}
@@ -0,0 +1,193 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use crate::{
+    before_layers, publisher_async::PublisherAsync, rejected_layers, ClientAsync,
+    ClientCallbackFnAsync, ClientError, PubSubLayer, RawMessage,
+};
+
+/// Rule of the bridge - matching messages are republished through the destination publisher,
+/// whose layers are applied on the outgoing leg. Route without conditions matches every message.
+pub struct Route {
+    destination: Arc<PublisherAsync>,
+    msg_types: Vec<String>,
+    headers: Vec<(String, String)>,
+    set_headers: Vec<(String, String)>,
+    rename_headers: Vec<(String, String)>,
+    remove_headers: Vec<String>,
+}
+
+impl Route {
+    /// Destination can be shared by several routes.
+    pub fn new(destination: Arc<PublisherAsync>) -> Self {
+        Route {
+            destination,
+            msg_types: vec![],
+            headers: vec![],
+            set_headers: vec![],
+            rename_headers: vec![],
+            remove_headers: vec![],
+        }
+    }
+
+    /// Match the message type - several types can be matched by the route.
+    pub fn msg_type(mut self, msg_type: &str) -> Self {
+        self.msg_types.push(msg_type.to_string());
+        self
+    }
+
+    /// Match the header value - all header conditions must be met.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn set_header(mut self, name: &str, value: &str) -> Self {
+        self.set_headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn rename_header(mut self, from: &str, to: &str) -> Self {
+        self.rename_headers.push((from.to_string(), to.to_string()));
+        self
+    }
+
+    pub fn remove_header(mut self, name: &str) -> Self {
+        self.remove_headers.push(name.to_string());
+        self
+    }
+
+    fn matches(&self, raw_msg: &RawMessage) -> bool {
+        (self.msg_types.is_empty() || self.msg_types.contains(&raw_msg.msg_type))
+            && self
+                .headers
+                .iter()
+                .all(|(name, value)| raw_msg.headers.get(name) == Some(value))
+    }
+
+    /// Headers are renamed first, then removed and set.
+    fn rewrite(&self, mut headers: HashMap<String, String>) -> HashMap<String, String> {
+        for (from, to) in &self.rename_headers {
+            if let Some(value) = headers.remove(from) {
+                headers.insert(to.clone(), value);
+            }
+        }
+        for name in &self.remove_headers {
+            headers.remove(name);
+        }
+        for (name, value) in &self.set_headers {
+            headers.insert(name.clone(), value.clone());
+        }
+        headers
+    }
+}
+
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+struct BridgeContext {
+    layers: Vec<Box<dyn PubSubLayer>>,
+    routes: Vec<Route>,
+    retry_backoff: Duration,
+}
+
+impl BridgeContext {
+    /// Transports don't redeliver the message whose callback failed (most of them commit or drop it), so the bridge
+    /// retries the failed destination until it's back - the source waits meanwhile. Only the message rejected by
+    /// a layer of the destination is returned as the error.
+    async fn publish(&self, route: &Route, msg: RawMessage) -> Result<(), ClientError> {
+        let mut backoff = self.retry_backoff;
+        loop {
+            match route.destination.publish_raw(msg.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(ClientError::Layer(e)) => return Err(ClientError::Layer(e)),
+                Err(e) => {
+                    log::warn!(
+                        "bridging of message {} failed, retrying in {:?}: {:?}",
+                        msg.msg_type,
+                        backoff,
+                        e
+                    );
+                }
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+        }
+    }
+}
+
+/// Consumes messages of the source client and republishes them through every matching route,
+/// e.g. to migrate from one transport to another. Source layers (e.g. decryption) are applied on the incoming leg.
+/// Messages matching no route are dropped. When a destination is down, publishing is retried with the backoff
+/// until it succeeds, so the message isn't lost and the next messages wait. When a layer of the destination rejects
+/// the message, the next routes are skipped and the rejection is returned to the source client.
+pub struct Bridge {
+    source: Box<dyn ClientAsync + Send + Sync>,
+    layers: Vec<Box<dyn PubSubLayer>>,
+    routes: Vec<Route>,
+    retry_backoff: Duration,
+}
+
+impl Bridge {
+    pub fn new(source: Box<dyn ClientAsync + Send + Sync>) -> Self {
+        Bridge {
+            source,
+            layers: vec![],
+            routes: vec![],
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+        }
+    }
+
+    /// Delay before the first retry of the failed destination, it's doubled with every next one up to 30 s.
+    /// Default is 100 ms.
+    pub fn retry_backoff(mut self, retry_backoff: Duration) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
+    /// Layer of the incoming leg.
+    pub fn add_layer(mut self, layer: Box<dyn PubSubLayer>) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    pub fn route(mut self, route: Route) -> Self {
+        self.routes.push(route);
+        self
+    }
+
+    /// Forward messages until the source client stops.
+    pub async fn run(mut self) -> Result<(), ClientError> {
+        let context = Arc::new(BridgeContext {
+            layers: self.layers,
+            routes: self.routes,
+            retry_backoff: self.retry_backoff,
+        });
+        let callback: Arc<ClientCallbackFnAsync> = Arc::new(move |mut msg: RawMessage| {
+            let context = context.clone();
+            Box::pin(async move {
+                if !before_layers(&context.layers, &mut msg)? {
+                    return Ok(());
+                }
+
+                for route in context.routes.iter().filter(|r| r.matches(&msg)) {
+                    let routed = RawMessage {
+                        msg_type: msg.msg_type.clone(),
+                        headers: route.rewrite(msg.headers.clone()),
+                        payload: msg.payload.clone(),
+                    };
+                    if let Err(e) = context.publish(route, routed).await {
+                        rejected_layers(&context.layers, &msg);
+                        return Err(e);
+                    }
+                }
+
+                context.layers.iter().rev().for_each(|l| {
+                    l.after(&msg);
+                });
+                Ok(())
+            })
+        });
+
+        self.source.receiver(callback).await
+    }
+}
@@ -1,9 +1,16 @@
 use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "schema")]
+use crate::schema::{SchemaRegistry, SchemaValidationLayer};
 use crate::{
-    listener::Listener, listener_async::ListenerAsync, publisher::Publisher,
-    publisher_async::PublisherAsync, Client, ClientAsync, PubSubLayer, PublisherContext,
-    PublisherContextAsync,
+    listener::Listener,
+    listener_async::ListenerAsync,
+    publisher::Publisher,
+    publisher_async::PublisherAsync,
+    rate_limit::RateLimiter,
+    scheduler::{Scheduler, SchedulerAsync},
+    Client, ClientAsync, ClientError, ErrorHookFn, PubSubLayer, PublisherContext,
+    PublisherContextAsync, RawMessage,
 };
 
 pub trait Builder<TPubSub> {
@@ -14,6 +21,12 @@ pub struct PubSubBuilder {
     client: Option<Box<dyn Client + Send + Sync>>,
     client_async: Option<Box<dyn ClientAsync + Send + Sync>>,
     layers: Vec<Box<dyn PubSubLayer>>,
+    rate_limiter: Option<RateLimiter>,
+    error_hook: Option<Arc<ErrorHookFn>>,
+    scheduler: Option<Box<dyn Scheduler + Send + Sync>>,
+    scheduler_async: Option<Box<dyn SchedulerAsync + Send + Sync>>,
+    #[cfg(feature = "schema")]
+    schema_registry: Option<SchemaRegistry>,
 }
 
 pub fn pubsub(client: Box<dyn Client + Send + Sync>) -> PubSubBuilder {
@@ -21,6 +34,12 @@ pub fn pubsub(client: Box<dyn Client + Send + Sync>) -> PubSubBuilder {
         client: Some(client),
         client_async: None,
         layers: vec![],
+        rate_limiter: None,
+        error_hook: None,
+        scheduler: None,
+        scheduler_async: None,
+        #[cfg(feature = "schema")]
+        schema_registry: None,
     }
 }
 
@@ -29,6 +48,12 @@ pub fn pubsub_async(client: Box<dyn ClientAsync + Send + Sync>) -> PubSubBuilder
         client: None,
         client_async: Some(client),
         layers: vec![],
+        rate_limiter: None,
+        error_hook: None,
+        scheduler: None,
+        scheduler_async: None,
+        #[cfg(feature = "schema")]
+        schema_registry: None,
     }
 }
 
@@ -37,11 +62,64 @@ impl PubSubBuilder {
         self.layers.push(layer);
         self
     }
+
+    /// Throttle handled (listener) or sent (publisher) messages.
+    pub fn rate_limit(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Called when a received message is rejected (e.g. it can't be decrypted) instead of logging it.
+    /// Used by the listener only.
+    pub fn on_error(
+        mut self,
+        error_hook: impl Fn(&RawMessage, &ClientError) + Send + Sync + 'static,
+    ) -> Self {
+        self.error_hook = Some(Arc::new(error_hook));
+        self
+    }
+
+    /// Storage of messages published by `publish_at` and `publish_after`.
+    pub fn scheduler(mut self, scheduler: Box<dyn Scheduler + Send + Sync>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    pub fn scheduler_async(mut self, scheduler: Box<dyn SchedulerAsync + Send + Sync>) -> Self {
+        self.scheduler_async = Some(scheduler);
+        self
+    }
+
+    /// Validate received messages against the schemas of message types registered by `register_handler`
+    /// of the listener. Validation layer is added at this position, so e.g. decryption layer should be added before.
+    #[cfg(feature = "schema")]
+    pub fn validate_schemas(mut self) -> Self {
+        let registry = SchemaRegistry::new();
+        self.layers
+            .push(Box::new(SchemaValidationLayer::new(registry.clone())));
+        self.schema_registry = Some(registry);
+        self
+    }
 }
 
 impl Builder<Listener> for PubSubBuilder {
     fn build(self) -> Listener {
-        Listener::new(self.client.unwrap(), self.layers)
+        let mut client = self.client.unwrap();
+        if let Some(error_hook) = &self.error_hook {
+            client.set_error_hook(error_hook.clone());
+        }
+        let mut listener = Listener::new(client, self.layers);
+        if let Some(rate_limiter) = self.rate_limiter {
+            listener.set_rate_limiter(rate_limiter);
+        }
+        if let Some(error_hook) = self.error_hook {
+            listener.set_error_hook(error_hook);
+        }
+        #[cfg(feature = "schema")]
+        if let Some(registry) = self.schema_registry {
+            listener.set_schema_registry(registry);
+        }
+        listener
     }
 }
 
@@ -50,6 +128,8 @@ impl Builder<Publisher> for PubSubBuilder {
         let context = PublisherContext {
             client: self.client.unwrap(),
             layers: self.layers,
+            rate_limiter: self.rate_limiter,
+            scheduler: self.scheduler,
         };
         Publisher::new(Arc::new(Mutex::new(context)))
     }
@@ -57,7 +137,22 @@ impl Builder<Publisher> for PubSubBuilder {
 
 impl Builder<ListenerAsync> for PubSubBuilder {
     fn build(self) -> ListenerAsync {
-        ListenerAsync::new(self.client_async.unwrap(), self.layers)
+        let mut client = self.client_async.unwrap();
+        if let Some(error_hook) = &self.error_hook {
+            client.set_error_hook(error_hook.clone());
+        }
+        let mut listener = ListenerAsync::new(client, self.layers);
+        if let Some(rate_limiter) = self.rate_limiter {
+            listener.set_rate_limiter(rate_limiter);
+        }
+        if let Some(error_hook) = self.error_hook {
+            listener.set_error_hook(error_hook);
+        }
+        #[cfg(feature = "schema")]
+        if let Some(registry) = self.schema_registry {
+            listener.set_schema_registry(registry);
+        }
+        listener
     }
 }
 
@@ -66,6 +161,8 @@ impl Builder<PublisherAsync> for PubSubBuilder {
         let context = PublisherContextAsync {
             client: self.client_async.unwrap(),
             layers: self.layers,
+            rate_limiter: self.rate_limiter,
+            scheduler: self.scheduler_async,
         };
         PublisherAsync::new(Arc::new(tokio::sync::Mutex::new(context)))
     }
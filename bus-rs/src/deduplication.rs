@@ -0,0 +1,152 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{ClientError, LayerError, PubSubLayer, RawMessage};
+
+pub const MESSAGE_ID_HEADER: &str = "bus-message-id";
+
+/// Storage of already processed message ids.
+pub trait DeduplicationStore: Send + Sync {
+    /// Record the id unless it's already recorded - check and insert must be one atomic operation,
+    /// so listeners sharing the store can't claim the same id. Returns false for the duplicate.
+    fn claim(&self, id: &str) -> Result<bool, ClientError>;
+
+    /// Forget the claimed id, so the message is handled when it's delivered again.
+    fn release(&self, id: &str) -> Result<(), ClientError>;
+}
+
+/// Keeps up to `capacity` ids, each one for `ttl` since it was last seen. The least recently seen id is evicted
+/// when the store is full - a duplicate makes the id recent again.
+pub struct InMemoryDeduplicationStore {
+    capacity: usize,
+    ttl: Duration,
+    ids: Mutex<(HashMap<String, Instant>, VecDeque<String>)>,
+}
+
+impl InMemoryDeduplicationStore {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        InMemoryDeduplicationStore {
+            capacity,
+            ttl,
+            ids: Mutex::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+}
+
+impl DeduplicationStore for InMemoryDeduplicationStore {
+    fn claim(&self, id: &str) -> Result<bool, ClientError> {
+        let mut ids = self.ids.lock().unwrap();
+        let (seen, order) = &mut *ids;
+        let duplicate = seen
+            .get(id)
+            .is_some_and(|last_seen| last_seen.elapsed() < self.ttl);
+
+        // ttl is the same for every id, so the order of last sightings is the expiration order too
+        while let Some(oldest) = order.front() {
+            let expired = seen[oldest].elapsed() >= self.ttl;
+            if !expired && (duplicate || seen.len() < self.capacity) {
+                break;
+            }
+            seen.remove(oldest);
+            order.pop_front();
+        }
+
+        if seen.insert(id.to_string(), Instant::now()).is_some() {
+            order.retain(|i| i != id);
+        }
+        order.push_back(id.to_string());
+        Ok(!duplicate)
+    }
+
+    fn release(&self, id: &str) -> Result<(), ClientError> {
+        let mut ids = self.ids.lock().unwrap();
+        let (seen, order) = &mut *ids;
+        if seen.remove(id).is_some() {
+            order.retain(|i| i != id);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct DeduplicationStats {
+    suppressed: Arc<AtomicUsize>,
+}
+
+impl DeduplicationStats {
+    pub fn suppressed(&self) -> usize {
+        self.suppressed.load(Ordering::Relaxed)
+    }
+}
+
+/// Listener side layer - skips messages whose id was already processed. The id is claimed before the message
+/// is handled, so listeners sharing the store don't handle it twice. When a later layer rejects the message,
+/// the claim is released, so its redelivery is handled.
+pub struct DeduplicationLayer {
+    store: Box<dyn DeduplicationStore>,
+    stats: DeduplicationStats,
+}
+
+impl DeduplicationLayer {
+    pub fn new(store: Box<dyn DeduplicationStore>) -> Self {
+        DeduplicationLayer {
+            store,
+            stats: DeduplicationStats {
+                suppressed: Arc::new(AtomicUsize::new(0)),
+            },
+        }
+    }
+
+    pub fn stats(&self) -> DeduplicationStats {
+        self.stats.clone()
+    }
+}
+
+impl PubSubLayer for DeduplicationLayer {
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        let Some(id) = raw_msg.headers.get(MESSAGE_ID_HEADER) else {
+            return Ok(());
+        };
+        let claimed = self
+            .store
+            .claim(id)
+            .map_err(|e| LayerError::Rejected(format!("deduplication store: {:?}", e)))?;
+        if !claimed {
+            self.stats.suppressed.fetch_add(1, Ordering::Relaxed);
+            return Err(LayerError::Skip);
+        }
+        Ok(())
+    }
+
+    fn after(&self, _raw_msg: &RawMessage) {}
+
+    fn rejected(&self, raw_msg: &RawMessage) {
+        let Some(id) = raw_msg.headers.get(MESSAGE_ID_HEADER) else {
+            return;
+        };
+        if let Err(e) = self.store.release(id) {
+            log::warn!("claim of message id {} can't be released: {:?}", id, e);
+        }
+    }
+}
+
+/// Publisher side layer - assigns unique id to every message which doesn't have one yet.
+pub struct MessageIdLayer;
+
+impl PubSubLayer for MessageIdLayer {
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        raw_msg
+            .headers
+            .entry(MESSAGE_ID_HEADER.to_string())
+            .or_insert_with(|| uuid::Uuid::new_v4().to_string());
+        Ok(())
+    }
+
+    fn after(&self, _raw_msg: &RawMessage) {}
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::ChaCha20Poly1305;
+
+use crate::{LayerError, PubSubLayer, RawMessage};
+
+pub const KEY_ID_HEADER: &str = "bus-key-id";
+
+const NONCE_LEN: usize = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cipher {
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+#[derive(Clone)]
+pub struct EncryptionKey {
+    cipher: Cipher,
+    bytes: [u8; 32],
+}
+
+impl EncryptionKey {
+    pub fn new(cipher: Cipher, bytes: [u8; 32]) -> Self {
+        EncryptionKey { cipher, bytes }
+    }
+
+    pub fn generate(cipher: Cipher) -> Self {
+        let key = Aes256Gcm::generate_key(&mut OsRng);
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&key);
+        EncryptionKey { cipher, bytes }
+    }
+
+    fn encrypt(&self, nonce: &[u8], payload: Payload) -> Result<Vec<u8>, aes_gcm::Error> {
+        let nonce = Nonce::from_slice(nonce);
+        match self.cipher {
+            Cipher::Aes256Gcm => Aes256Gcm::new(&self.bytes.into()).encrypt(nonce, payload),
+            Cipher::ChaCha20Poly1305 => {
+                ChaCha20Poly1305::new(&self.bytes.into()).encrypt(nonce, payload)
+            }
+        }
+    }
+
+    fn decrypt(&self, nonce: &[u8], payload: Payload) -> Result<Vec<u8>, aes_gcm::Error> {
+        let nonce = Nonce::from_slice(nonce);
+        match self.cipher {
+            Cipher::Aes256Gcm => Aes256Gcm::new(&self.bytes.into()).decrypt(nonce, payload),
+            Cipher::ChaCha20Poly1305 => {
+                ChaCha20Poly1305::new(&self.bytes.into()).decrypt(nonce, payload)
+            }
+        }
+    }
+}
+
+/// Set of encryption keys addressed by id. New messages are always encrypted with the
+/// current key, while the older ones are kept to decrypt messages which are still in flight.
+pub struct Keyring {
+    current: String,
+    keys: HashMap<String, EncryptionKey>,
+}
+
+impl Keyring {
+    pub fn new(key_id: &str, key: EncryptionKey) -> Self {
+        Keyring {
+            current: key_id.to_string(),
+            keys: HashMap::from([(key_id.to_string(), key)]),
+        }
+    }
+
+    pub fn add_key(&mut self, key_id: &str, key: EncryptionKey) {
+        self.keys.insert(key_id.to_string(), key);
+    }
+
+    /// Switch the key used for encryption. The previous key stays available for decryption
+    /// until it's removed.
+    pub fn rotate(&mut self, key_id: &str, key: EncryptionKey) {
+        self.add_key(key_id, key);
+        self.current = key_id.to_string();
+    }
+
+    pub fn remove_key(&mut self, key_id: &str) -> Option<EncryptionKey> {
+        if key_id == self.current {
+            return None;
+        }
+        self.keys.remove(key_id)
+    }
+
+    pub fn current_key_id(&self) -> &str {
+        &self.current
+    }
+}
+
+/// Publisher side layer - encrypts the message payload with the current keyring key.
+pub struct EncryptionLayer {
+    keyring: Arc<RwLock<Keyring>>,
+}
+
+impl EncryptionLayer {
+    pub fn new(keyring: Arc<RwLock<Keyring>>) -> Self {
+        EncryptionLayer { keyring }
+    }
+}
+
+impl PubSubLayer for EncryptionLayer {
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        let keyring = self.keyring.read().unwrap();
+        let key = keyring.keys.get(&keyring.current).unwrap();
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: raw_msg.payload.as_bytes(),
+            aad: raw_msg.msg_type.as_bytes(),
+        };
+        let ciphertext = key
+            .encrypt(&nonce, payload)
+            .map_err(|e| LayerError::Rejected(format!("encryption failed: {}", e)))?;
+
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        raw_msg.payload = STANDARD.encode(data);
+        raw_msg
+            .headers
+            .insert(KEY_ID_HEADER.to_string(), keyring.current.clone());
+        Ok(())
+    }
+
+    fn after(&self, _raw_msg: &RawMessage) {}
+}
+
+/// Listener side layer - decrypts the message payload with the key pointed by the key id header.
+pub struct DecryptionLayer {
+    keyring: Arc<RwLock<Keyring>>,
+}
+
+impl DecryptionLayer {
+    pub fn new(keyring: Arc<RwLock<Keyring>>) -> Self {
+        DecryptionLayer { keyring }
+    }
+}
+
+impl PubSubLayer for DecryptionLayer {
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        let key_id = raw_msg
+            .headers
+            .get(KEY_ID_HEADER)
+            .ok_or_else(|| LayerError::Rejected("missing encryption key id".to_string()))?;
+        let keyring = self.keyring.read().unwrap();
+        let key = keyring
+            .keys
+            .get(key_id)
+            .ok_or_else(|| LayerError::Rejected(format!("unknown encryption key: {}", key_id)))?;
+
+        let data = STANDARD
+            .decode(&raw_msg.payload)
+            .map_err(|e| LayerError::Rejected(format!("invalid encrypted payload: {}", e)))?;
+        if data.len() < NONCE_LEN {
+            return Err(LayerError::Rejected(
+                "invalid encrypted payload: too short".to_string(),
+            ));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: raw_msg.msg_type.as_bytes(),
+        };
+        let plaintext = key
+            .decrypt(nonce, payload)
+            .map_err(|e| LayerError::Rejected(format!("decryption failed: {}", e)))?;
+
+        raw_msg.payload = String::from_utf8(plaintext)
+            .map_err(|e| LayerError::Rejected(format!("decryption failed: {}", e)))?;
+        raw_msg.headers.remove(KEY_ID_HEADER);
+        Ok(())
+    }
+
+    fn after(&self, _raw_msg: &RawMessage) {}
+}
@@ -1,20 +1,48 @@
 use async_trait::async_trait;
 use futures::future::BoxFuture;
+use rate_limit::RateLimiter;
+use scheduler::{Scheduler, SchedulerAsync};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 
+pub mod bridge;
 pub mod builder;
+#[cfg(feature = "deduplication")]
+pub mod deduplication;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod listener;
 pub mod listener_async;
 pub mod message_handler;
 pub mod message_handler_async;
 pub mod message_store;
+pub mod outbox;
 pub mod publisher;
 pub mod publisher_async;
+pub mod rate_limit;
+pub mod recording;
+pub mod saga;
+pub mod scheduler;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "signing")]
+pub mod signing;
 
 pub trait Client {
-    fn receiver(&mut self, recv_callback: &dyn Fn(RawMessage)) -> Result<(), ClientError>;
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError>;
     fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError>;
+
+    /// Send many messages at once. Clients which are able to do it in one round trip should override it.
+    fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+        msgs.iter().map(|msg| self.send(msg)).collect()
+    }
+
+    /// Error hook of the listener. Clients which can receive messages that can't be decoded (so they never
+    /// reach the callback) should keep it and report them with `report_error`.
+    fn set_error_hook(&mut self, _error_hook: Arc<ErrorHookFn>) {}
 }
 
 pub type ClientCallbackFnAsync =
@@ -27,6 +55,18 @@ pub trait ClientAsync {
         recv_callback: Arc<ClientCallbackFnAsync>,
     ) -> Result<(), ClientError>;
     async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError>;
+
+    /// Send many messages at once. Clients which are able to do it in one round trip should override it.
+    async fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+        let mut results = Vec::with_capacity(msgs.len());
+        for msg in msgs {
+            results.push(self.send(msg).await);
+        }
+        results
+    }
+
+    /// Error hook of the listener, like `Client::set_error_hook`.
+    fn set_error_hook(&mut self, _error_hook: Arc<ErrorHookFn>) {}
 }
 
 #[derive(Debug)]
@@ -34,6 +74,21 @@ pub enum ClientError {
     NotAssignedConnection,
     IO(String),
     General(String),
+    Layer(LayerError),
+}
+
+impl From<LayerError> for ClientError {
+    fn from(value: LayerError) -> Self {
+        ClientError::Layer(value)
+    }
+}
+
+#[derive(Debug)]
+pub enum LayerError {
+    Rejected(String),
+    Invalid(Vec<String>),
+    /// Drop the message silently - it's not reported as an error.
+    Skip,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -61,24 +116,144 @@ impl Into<String> for &RawMessage {
     }
 }
 
+impl RawMessage {
+    pub fn from_message<TMessage>(msg: &TMessage, headers: Option<HashMap<String, String>>) -> Self
+    where
+        TMessage: MessageConstraints,
+    {
+        RawMessage {
+            msg_type: TMessage::name().to_string(),
+            headers: headers.unwrap_or_default(),
+            payload: serde_json::to_string(msg).unwrap(),
+        }
+    }
+
+    /// Decode the message encoded by `Into<String>` - unlike `From<String>` it fails instead of panicking,
+    /// so a malformed message received by the transport doesn't stop the receiver.
+    pub fn decode(value: &str) -> Result<RawMessage, ClientError> {
+        serde_json::from_str(value)
+            .map_err(|e| ClientError::General(format!("message can't be decoded: {}", e)))
+    }
+
+    /// Received content which can't be decoded, in the form reported to the error hook - the type is empty.
+    pub fn undecodable(content: &str) -> RawMessage {
+        RawMessage {
+            msg_type: String::new(),
+            headers: HashMap::new(),
+            payload: content.to_string(),
+        }
+    }
+}
+
 pub trait MessageTypeName {
     fn name() -> &'static str;
+
+    /// JSON Schema of the message, generated by `#[message(schema)]`.
+    fn schema() -> Option<serde_json::Value> {
+        None
+    }
 }
 
 pub trait MessageConstraints: DeserializeOwned + Serialize + MessageTypeName + 'static {}
 impl<T: DeserializeOwned + Serialize + MessageTypeName + 'static> MessageConstraints for T {}
 
+/// Called by the listener when a received message is rejected (e.g. by a layer) and isn't handled,
+/// and by the transport when the received message can't be decoded (see `RawMessage::undecodable`).
+/// Without the hook the error is logged.
+pub type ErrorHookFn = dyn Fn(&RawMessage, &ClientError) + Send + Sync;
+
+pub fn report_error(hook: &Option<Arc<ErrorHookFn>>, raw_msg: &RawMessage, e: &ClientError) {
+    match hook {
+        Some(hook) => hook(raw_msg, e),
+        None => log::warn!("message {} is rejected: {:?}", raw_msg.msg_type, e),
+    }
+}
+
 pub trait PubSubLayer: Send + Sync {
-    fn before(&self, raw_msg: &mut RawMessage);
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError>;
     fn after(&self, raw_msg: &RawMessage);
+
+    /// Called on the layers which passed the message when a later layer rejected it, so they can undo
+    /// what `before` did (e.g. release the claimed message id).
+    fn rejected(&self, _raw_msg: &RawMessage) {}
+}
+
+/// Run `before` of every layer. Returns false when one of them decided to skip the message.
+pub fn before_layers(
+    layers: &[Box<dyn PubSubLayer>],
+    raw_msg: &mut RawMessage,
+) -> Result<bool, LayerError> {
+    for (i, l) in layers.iter().enumerate() {
+        match l.before(raw_msg) {
+            Ok(()) => {}
+            Err(LayerError::Skip) => return Ok(false),
+            Err(e) => {
+                rejected_layers(&layers[..i], raw_msg);
+                return Err(e);
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Run `rejected` of the layers whose `before` passed the message, in the reverse order like `after`.
+pub fn rejected_layers(layers: &[Box<dyn PubSubLayer>], raw_msg: &RawMessage) {
+    layers.iter().rev().for_each(|l| l.rejected(raw_msg));
+}
+
+/// Run `before` layers on every message of the batch. Returns messages which should be sent and
+/// results of the whole batch - `None` is a placeholder for the result of sending.
+pub(crate) fn before_layers_batch(
+    layers: &[Box<dyn PubSubLayer>],
+    raw_msgs: Vec<RawMessage>,
+) -> (Vec<RawMessage>, Vec<Option<Result<(), ClientError>>>) {
+    let mut to_send = vec![];
+    let mut results = vec![];
+    for mut raw_msg in raw_msgs {
+        match before_layers(layers, &mut raw_msg) {
+            Ok(true) => {
+                to_send.push(raw_msg);
+                results.push(None);
+            }
+            Ok(false) => results.push(Some(Ok(()))),
+            Err(e) => results.push(Some(Err(e.into()))),
+        }
+    }
+    (to_send, results)
+}
+
+/// Fill the batch results with the results of sending and run `after` layers on the sent messages.
+pub(crate) fn after_layers_batch(
+    layers: &[Box<dyn PubSubLayer>],
+    results: Vec<Option<Result<(), ClientError>>>,
+    sent: &[RawMessage],
+    sent_results: Vec<Result<(), ClientError>>,
+) -> Vec<Result<(), ClientError>> {
+    let mut sent = sent.iter().zip(sent_results);
+    results
+        .into_iter()
+        .map(|result| {
+            result.unwrap_or_else(|| {
+                let (raw_msg, result) = sent.next().unwrap();
+                if result.is_ok() {
+                    layers.iter().rev().for_each(|l| l.after(raw_msg));
+                }
+                result
+            })
+        })
+        .collect()
 }
 
 pub struct PublisherContext {
     pub(crate) client: Box<dyn Client + Send + Sync>,
     pub(crate) layers: Vec<Box<dyn PubSubLayer>>,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) scheduler: Option<Box<dyn Scheduler + Send + Sync>>,
 }
 
 pub struct PublisherContextAsync {
     pub(crate) client: Box<dyn ClientAsync + Send + Sync>,
     pub(crate) layers: Vec<Box<dyn PubSubLayer>>,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) scheduler: Option<Box<dyn SchedulerAsync + Send + Sync>>,
 }
@@ -1,8 +1,15 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+};
 
+#[cfg(feature = "schema")]
+use crate::schema::{InvalidSchema, SchemaRegistry};
 use crate::{
-    message_handler::MessageHandler, message_store::MessageStore, Client, ClientError,
-    MessageConstraints, PubSubLayer, RawMessage,
+    before_layers, message_handler::MessageHandler, message_store::MessageStore,
+    rate_limit::RateLimiter, report_error, Client, ClientError, ErrorHookFn, MessageConstraints,
+    PubSubLayer, RawMessage,
 };
 
 pub struct Listener {
@@ -10,6 +17,13 @@ pub struct Listener {
     client: Box<dyn Client + Send + Sync>,
     handlers: Box<HashMap<String, Box<dyn Fn(&MessageStore, RawMessage) + Send + Sync>>>,
     layers: Box<Vec<Box<dyn PubSubLayer>>>,
+    schemas: HashMap<String, serde_json::Value>,
+    rate_limiter: Option<RateLimiter>,
+    error_hook: Option<Arc<ErrorHookFn>>,
+    #[cfg(feature = "schema")]
+    schema_registry: Option<SchemaRegistry>,
+    #[cfg(feature = "schema")]
+    invalid_schemas: Vec<InvalidSchema>,
 }
 
 impl Listener {
@@ -19,12 +33,31 @@ impl Listener {
             client,
             handlers: Box::new(HashMap::new()),
             layers: Box::new(layers),
+            schemas: HashMap::new(),
+            rate_limiter: None,
+            error_hook: None,
+            #[cfg(feature = "schema")]
+            schema_registry: None,
+            #[cfg(feature = "schema")]
+            invalid_schemas: vec![],
         }
     }
 
+    /// Fails without receiving when schema of a registered message type can't be used for validation.
     pub fn listen(&mut self) -> Result<(), ClientError> {
+        #[cfg(feature = "schema")]
+        if let Some(invalid_schema) = self.invalid_schemas.first() {
+            return Err(ClientError::General(invalid_schema.to_string()));
+        }
         let callback = |msg: RawMessage| {
-            Self::handle(msg, &self.layers, &self.handlers, &self.message_store);
+            Self::handle(
+                msg.clone(),
+                &self.layers,
+                &self.handlers,
+                &self.message_store,
+                &self.rate_limiter,
+            )
+            .inspect_err(|e| report_error(&self.error_hook, &msg, e))
         };
         self.client.receiver(&callback)
     }
@@ -46,6 +79,15 @@ impl Listener {
         };
 
         self.message_store.register::<TMessage>(TMessage::name());
+        if let Some(schema) = TMessage::schema() {
+            self.schemas.insert(TMessage::name().to_string(), schema);
+        }
+        #[cfg(feature = "schema")]
+        if let Some(registry) = &self.schema_registry {
+            if let Err(e) = registry.register::<TMessage>() {
+                self.invalid_schemas.push(e);
+            }
+        }
         self.register_handler_callback::<TMessage, _>(handler_fn);
     }
 
@@ -53,23 +95,47 @@ impl Listener {
         self.handlers.len()
     }
 
+    /// JSON Schemas of the registered message types, keyed by message type name.
+    pub fn schemas(&self) -> HashMap<String, serde_json::Value> {
+        self.schemas.clone()
+    }
+
+    pub(crate) fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        self.rate_limiter = Some(rate_limiter);
+    }
+
+    pub(crate) fn set_error_hook(&mut self, error_hook: Arc<ErrorHookFn>) {
+        self.error_hook = Some(error_hook);
+    }
+
+    /// Schemas of the registered message types are added to the registry of the validation layer.
+    #[cfg(feature = "schema")]
+    pub(crate) fn set_schema_registry(&mut self, registry: SchemaRegistry) {
+        self.schema_registry = Some(registry);
+    }
+
     fn handle(
         mut msg: RawMessage,
         layers: &Box<Vec<Box<dyn PubSubLayer>>>,
         handlers: &Box<HashMap<String, Box<dyn Fn(&MessageStore, RawMessage) + Send + Sync>>>,
         message_store: &Box<MessageStore>,
-    ) {
-        layers.iter().for_each(|l| {
-            l.before(&mut msg);
-        });
+        rate_limiter: &Option<RateLimiter>,
+    ) -> Result<(), ClientError> {
+        if !before_layers(layers, &mut msg)? {
+            return Ok(());
+        }
 
         if let Some(handler) = handlers.get(msg.msg_type.as_str()) {
+            if let Some(rate_limiter) = rate_limiter {
+                thread::sleep(rate_limiter.delay(&msg.msg_type));
+            }
             handler(&message_store, msg.clone());
         }
 
         layers.iter().rev().for_each(|l| {
             l.after(&msg);
         });
+        Ok(())
     }
 
     fn register_handler_callback<TMessage, TCallback>(&mut self, callback: TCallback)
@@ -1,11 +1,14 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use futures::future::BoxFuture;
 use tokio::sync::Mutex;
 
+#[cfg(feature = "schema")]
+use crate::schema::{InvalidSchema, SchemaRegistry};
 use crate::{
-    message_handler_async::MessageHandlerAsync, message_store::MessageStore, ClientAsync,
-    ClientCallbackFnAsync, ClientError, MessageConstraints, PubSubLayer, RawMessage,
+    before_layers, message_handler_async::MessageHandlerAsync, message_store::MessageStore,
+    rate_limit::RateLimiter, report_error, ClientAsync, ClientCallbackFnAsync, ClientError,
+    ErrorHookFn, MessageConstraints, PubSubLayer, RawMessage,
 };
 
 type MessageHandlerCallbackFnAsync =
@@ -15,6 +18,13 @@ pub(super) struct ContextContainer {
     message_store: Box<MessageStore>,
     handlers: Box<HashMap<String, Arc<MessageHandlerCallbackFnAsync>>>,
     layers: Box<Vec<Box<dyn PubSubLayer>>>,
+    schemas: HashMap<String, serde_json::Value>,
+    rate_limiter: Option<RateLimiter>,
+    error_hook: Option<Arc<ErrorHookFn>>,
+    #[cfg(feature = "schema")]
+    schema_registry: Option<SchemaRegistry>,
+    #[cfg(feature = "schema")]
+    invalid_schemas: Vec<InvalidSchema>,
 }
 
 pub struct ListenerAsync {
@@ -31,6 +41,13 @@ impl ListenerAsync {
             message_store: Box::new(MessageStore::new()),
             handlers: Box::new(HashMap::new()),
             layers: Box::new(layers),
+            schemas: HashMap::new(),
+            rate_limiter: None,
+            error_hook: None,
+            #[cfg(feature = "schema")]
+            schema_registry: None,
+            #[cfg(feature = "schema")]
+            invalid_schemas: vec![],
         };
         ListenerAsync {
             context: Arc::new(Mutex::new(context_container)),
@@ -38,17 +55,43 @@ impl ListenerAsync {
         }
     }
 
+    /// Fails without receiving when schema of a registered message type can't be used for validation.
     pub async fn listen(&mut self) -> Result<(), ClientError> {
+        #[cfg(feature = "schema")]
+        if let Some(invalid_schema) = self.context.lock().await.invalid_schemas.first() {
+            return Err(ClientError::General(invalid_schema.to_string()));
+        }
         let context = self.context.clone();
-        let callback: Arc<ClientCallbackFnAsync> = Arc::new(move |msg: RawMessage| {
-            let mut msg = msg.clone();
+        let callback: Arc<ClientCallbackFnAsync> = Arc::new(move |raw_msg: RawMessage| {
+            let mut msg = raw_msg.clone();
             let context = context.clone();
             Box::pin(async move {
-                let context = context.lock().await;
-                context.layers.iter().for_each(|l| {
-                    l.before(&mut msg);
-                });
+                let delay = {
+                    let context = context.lock().await;
+                    match before_layers(&context.layers, &mut msg) {
+                        Ok(true) => {}
+                        Ok(false) => return Ok(()),
+                        Err(e) => {
+                            let e = e.into();
+                            report_error(&context.error_hook, &raw_msg, &e);
+                            return Err(e);
+                        }
+                    }
+                    match &context.rate_limiter {
+                        Some(rate_limiter)
+                            if context.handlers.contains_key(msg.msg_type.as_str()) =>
+                        {
+                            rate_limiter.delay(&msg.msg_type)
+                        }
+                        _ => Duration::ZERO,
+                    }
+                };
+                // the context is not locked while waiting, so registering handlers is not blocked
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
 
+                let context = context.lock().await;
                 if let Some(handler) = context.handlers.get(msg.msg_type.as_str()) {
                     handler(&context.message_store, msg.clone()).await;
                 }
@@ -88,6 +131,15 @@ impl ListenerAsync {
 
         let mut context = self.context.lock().await;
         context.message_store.register::<TMessage>(TMessage::name());
+        if let Some(schema) = TMessage::schema() {
+            context.schemas.insert(TMessage::name().to_string(), schema);
+        }
+        #[cfg(feature = "schema")]
+        if let Some(registry) = &context.schema_registry {
+            if let Err(e) = registry.register::<TMessage>() {
+                context.invalid_schemas.push(e);
+            }
+        }
     }
 
     pub async fn registered_handlers_count(&self) -> usize {
@@ -95,6 +147,35 @@ impl ListenerAsync {
         context.handlers.len()
     }
 
+    /// JSON Schemas of the registered message types, keyed by message type name.
+    pub async fn schemas(&self) -> HashMap<String, serde_json::Value> {
+        let context = self.context.lock().await;
+        context.schemas.clone()
+    }
+
+    pub(crate) fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        Arc::get_mut(&mut self.context)
+            .unwrap()
+            .get_mut()
+            .rate_limiter = Some(rate_limiter);
+    }
+
+    pub(crate) fn set_error_hook(&mut self, error_hook: Arc<ErrorHookFn>) {
+        Arc::get_mut(&mut self.context)
+            .unwrap()
+            .get_mut()
+            .error_hook = Some(error_hook);
+    }
+
+    /// Schemas of the registered message types are added to the registry of the validation layer.
+    #[cfg(feature = "schema")]
+    pub(crate) fn set_schema_registry(&mut self, registry: SchemaRegistry) {
+        Arc::get_mut(&mut self.context)
+            .unwrap()
+            .get_mut()
+            .schema_registry = Some(registry);
+    }
+
     async fn register_handler_callback<TMessage>(
         &mut self,
         callback: Box<MessageHandlerCallbackFnAsync>,
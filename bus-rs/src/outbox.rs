@@ -0,0 +1,95 @@
+use std::{thread, time::Duration};
+
+use crate::{Client, ClientAsync, ClientError, RawMessage};
+
+/// Message stored in the outbox, waiting to be sent.
+pub struct OutboxEntry {
+    pub id: i64,
+    pub message: RawMessage,
+}
+
+/// Local store of messages written together with the application data (in the same transaction).
+pub trait OutboxStore {
+    /// Oldest messages which are not dispatched yet, in order of writing.
+    fn pending(&mut self, limit: usize) -> Result<Vec<OutboxEntry>, ClientError>;
+    fn mark_dispatched(&mut self, id: i64) -> Result<(), ClientError>;
+}
+
+/// Forwards pending outbox messages to the client and marks them dispatched.
+/// Message is marked only when it's sent, so it can be sent again after a crash (at-least-once delivery).
+pub struct OutboxRelay {
+    store: Box<dyn OutboxStore + Send>,
+    batch_size: usize,
+}
+
+impl OutboxRelay {
+    pub fn new(store: Box<dyn OutboxStore + Send>) -> Self {
+        OutboxRelay {
+            store,
+            batch_size: 100,
+        }
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Send all pending messages. Stops on the first failure to keep the order of messages.
+    /// Returns number of dispatched messages.
+    pub fn relay(&mut self, client: &mut dyn Client) -> Result<usize, ClientError> {
+        let mut dispatched = 0;
+        loop {
+            let entries = self.store.pending(self.batch_size)?;
+            for entry in entries.iter() {
+                client.send(&entry.message)?;
+                self.store.mark_dispatched(entry.id)?;
+                dispatched += 1;
+            }
+            if entries.len() < self.batch_size {
+                return Ok(dispatched);
+            }
+        }
+    }
+
+    pub async fn relay_async(
+        &mut self,
+        client: &mut (dyn ClientAsync + Send),
+    ) -> Result<usize, ClientError> {
+        let mut dispatched = 0;
+        loop {
+            let entries = self.store.pending(self.batch_size)?;
+            for entry in entries.iter() {
+                client.send(&entry.message).await?;
+                self.store.mark_dispatched(entry.id)?;
+                dispatched += 1;
+            }
+            if entries.len() < self.batch_size {
+                return Ok(dispatched);
+            }
+        }
+    }
+
+    /// Relay pending messages every `poll_interval`. Returns only on error.
+    pub fn run(
+        &mut self,
+        client: &mut dyn Client,
+        poll_interval: Duration,
+    ) -> Result<(), ClientError> {
+        loop {
+            self.relay(client)?;
+            thread::sleep(poll_interval);
+        }
+    }
+
+    pub async fn run_async(
+        &mut self,
+        client: &mut (dyn ClientAsync + Send),
+        poll_interval: Duration,
+    ) -> Result<(), ClientError> {
+        loop {
+            self.relay_async(client).await?;
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
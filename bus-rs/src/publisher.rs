@@ -2,9 +2,14 @@ use std::{
     collections::HashMap,
     iter::Iterator,
     sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime},
 };
 
-use crate::{Client, MessageConstraints, PubSubLayer, PublisherContext, RawMessage};
+use crate::{
+    after_layers_batch, before_layers, before_layers_batch, ClientError, MessageConstraints,
+    PublisherContext, RawMessage,
+};
 
 pub struct Publisher {
     context: Arc<Mutex<PublisherContext>>,
@@ -15,28 +20,105 @@ impl Publisher {
         Self { context }
     }
 
-    // @@@ todo return publisher error
-    pub fn publish<TMessage>(&self, msg: &TMessage, headers: Option<HashMap<String, String>>)
+    pub fn publish<TMessage>(
+        &self,
+        msg: &TMessage,
+        headers: Option<HashMap<String, String>>,
+    ) -> Result<(), ClientError>
     where
         TMessage: MessageConstraints,
     {
-        let mut raw_msg = RawMessage {
-            msg_type: TMessage::name().to_string(),
-            headers: match headers {
-                Some(h) => h,
-                None => HashMap::new(),
-            },
-            payload: serde_json::to_string(msg).unwrap(),
-        };
+        let mut raw_msg = RawMessage::from_message(msg, headers);
         let mut context = self.context.lock().unwrap();
-        context.layers.iter().for_each(|l| {
-            l.before(&mut raw_msg);
+        if !before_layers(&context.layers, &mut raw_msg)? {
+            return Ok(());
+        }
+
+        if let Some(rate_limiter) = &context.rate_limiter {
+            thread::sleep(rate_limiter.delay(&raw_msg.msg_type));
+        }
+        context.client.send(&raw_msg)?;
+
+        context.layers.iter().rev().for_each(|l| {
+            l.after(&raw_msg);
         });
+        Ok(())
+    }
 
-        let _ = context.client.send(&raw_msg);
+    /// Publish the message when `when` comes. The message goes through `before` layers immediately
+    /// and is handed over to the scheduler, which sends it to the channel when it's due.
+    /// Layers which stamp the time (e.g. `SigningLayer`) stamp the scheduling time, so `max_age` of the
+    /// `VerificationLayer` has to cover the delay.
+    pub fn publish_at<TMessage>(
+        &self,
+        msg: &TMessage,
+        headers: Option<HashMap<String, String>>,
+        when: SystemTime,
+    ) -> Result<(), ClientError>
+    where
+        TMessage: MessageConstraints,
+    {
+        let mut raw_msg = RawMessage::from_message(msg, headers);
+
+        let mut context = self.context.lock().unwrap();
+        let context = &mut *context;
+        let Some(scheduler) = context.scheduler.as_mut() else {
+            return Err(ClientError::General(
+                "scheduler is not configured".to_string(),
+            ));
+        };
+        if !before_layers(&context.layers, &mut raw_msg)? {
+            return Ok(());
+        }
+        scheduler.schedule(&raw_msg, when)?;
 
         context.layers.iter().rev().for_each(|l| {
             l.after(&raw_msg);
         });
+        Ok(())
+    }
+
+    pub fn publish_after<TMessage>(
+        &self,
+        msg: &TMessage,
+        headers: Option<HashMap<String, String>>,
+        delay: Duration,
+    ) -> Result<(), ClientError>
+    where
+        TMessage: MessageConstraints,
+    {
+        self.publish_at(msg, headers, SystemTime::now() + delay)
+    }
+
+    /// Publish many messages with one lock acquisition and (if the client supports it) in one round trip.
+    /// Returns result for every message, in the same order.
+    pub fn publish_batch<TMessage>(
+        &self,
+        msgs: &[TMessage],
+        headers: Option<HashMap<String, String>>,
+    ) -> Vec<Result<(), ClientError>>
+    where
+        TMessage: MessageConstraints,
+    {
+        let raw_msgs = msgs
+            .iter()
+            .map(|msg| RawMessage::from_message(msg, headers.clone()))
+            .collect();
+
+        let mut context = self.context.lock().unwrap();
+        let (to_send, results) = before_layers_batch(&context.layers, raw_msgs);
+
+        if let Some(rate_limiter) = &context.rate_limiter {
+            // tokens are reserved one by one, so the last message waits the longest
+            let delay = to_send
+                .iter()
+                .map(|raw_msg| rate_limiter.delay(&raw_msg.msg_type))
+                .max()
+                .unwrap_or_default();
+            thread::sleep(delay);
+        }
+        let sent_results = context.client.send_batch(&to_send);
+
+        after_layers_batch(&context.layers, results, &to_send, sent_results)
     }
 }
@@ -1,8 +1,15 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use tokio::sync::Mutex;
 
-use crate::{MessageConstraints, PublisherContextAsync, RawMessage};
+use crate::{
+    after_layers_batch, before_layers, before_layers_batch, ClientError, MessageConstraints,
+    PublisherContextAsync, RawMessage,
+};
 
 pub struct PublisherAsync {
     context: Arc<Mutex<PublisherContextAsync>>,
@@ -13,29 +20,119 @@ impl PublisherAsync {
         Self { context }
     }
 
-    // @@@ todo return publisher error
-    pub async fn publish<TMessage>(&self, msg: &TMessage, headers: Option<HashMap<String, String>>)
+    pub async fn publish<TMessage>(
+        &self,
+        msg: &TMessage,
+        headers: Option<HashMap<String, String>>,
+    ) -> Result<(), ClientError>
     where
         TMessage: MessageConstraints,
     {
-        let mut raw_msg = RawMessage {
-            msg_type: TMessage::name().to_string(),
-            headers: match headers {
-                Some(h) => h,
-                None => HashMap::new(),
-            },
-            payload: serde_json::to_string(msg).unwrap(),
-        };
+        self.publish_raw(RawMessage::from_message(msg, headers))
+            .await
+    }
 
+    /// Publish the message which is already serialized, e.g. received from outside of the bus.
+    /// It goes through the layers the same way as the typed message.
+    pub async fn publish_raw(&self, mut raw_msg: RawMessage) -> Result<(), ClientError> {
         let mut context = self.context.lock().await;
-        context.layers.iter().for_each(|l| {
-            l.before(&mut raw_msg);
+        if !before_layers(&context.layers, &mut raw_msg)? {
+            return Ok(());
+        }
+
+        if let Some(rate_limiter) = &context.rate_limiter {
+            tokio::time::sleep(rate_limiter.delay(&raw_msg.msg_type)).await;
+        }
+        context.client.send(&raw_msg).await?;
+
+        context.layers.iter().rev().for_each(|l| {
+            l.after(&raw_msg);
         });
+        Ok(())
+    }
 
-        let _ = context.client.send(&raw_msg).await;
+    /// Publish the message when `when` comes. The message goes through `before` layers immediately
+    /// and is handed over to the scheduler, which sends it to the channel when it's due.
+    /// Layers which stamp the time (e.g. `SigningLayer`) stamp the scheduling time, so `max_age` of the
+    /// `VerificationLayer` has to cover the delay.
+    pub async fn publish_at<TMessage>(
+        &self,
+        msg: &TMessage,
+        headers: Option<HashMap<String, String>>,
+        when: SystemTime,
+    ) -> Result<(), ClientError>
+    where
+        TMessage: MessageConstraints,
+    {
+        self.publish_raw_at(RawMessage::from_message(msg, headers), when)
+            .await
+    }
+
+    pub(crate) async fn publish_raw_at(
+        &self,
+        mut raw_msg: RawMessage,
+        when: SystemTime,
+    ) -> Result<(), ClientError> {
+        let mut context = self.context.lock().await;
+        let context = &mut *context;
+        let Some(scheduler) = context.scheduler.as_mut() else {
+            return Err(ClientError::General(
+                "scheduler is not configured".to_string(),
+            ));
+        };
+        if !before_layers(&context.layers, &mut raw_msg)? {
+            return Ok(());
+        }
+        scheduler.schedule(&raw_msg, when).await?;
 
         context.layers.iter().rev().for_each(|l| {
             l.after(&raw_msg);
         });
+        Ok(())
+    }
+
+    pub async fn publish_after<TMessage>(
+        &self,
+        msg: &TMessage,
+        headers: Option<HashMap<String, String>>,
+        delay: Duration,
+    ) -> Result<(), ClientError>
+    where
+        TMessage: MessageConstraints,
+    {
+        self.publish_at(msg, headers, SystemTime::now() + delay)
+            .await
+    }
+
+    /// Publish many messages with one lock acquisition and (if the client supports it) in one round trip.
+    /// Returns result for every message, in the same order.
+    pub async fn publish_batch<TMessage>(
+        &self,
+        msgs: &[TMessage],
+        headers: Option<HashMap<String, String>>,
+    ) -> Vec<Result<(), ClientError>>
+    where
+        TMessage: MessageConstraints,
+    {
+        let raw_msgs = msgs
+            .iter()
+            .map(|msg| RawMessage::from_message(msg, headers.clone()))
+            .collect();
+
+        let mut context = self.context.lock().await;
+        let (to_send, results) = before_layers_batch(&context.layers, raw_msgs);
+
+        if let Some(rate_limiter) = &context.rate_limiter {
+            // tokens are reserved one by one, so the last message waits the longest
+            let delay = to_send
+                .iter()
+                .map(|raw_msg| rate_limiter.delay(&raw_msg.msg_type))
+                .max()
+                .unwrap_or_default();
+            tokio::time::sleep(delay).await;
+        }
+        let sent_results = context.client.send_batch(&to_send).await;
+
+        after_layers_batch(&context.layers, results, &to_send, sent_results)
     }
 }
@@ -0,0 +1,95 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::MessageTypeName;
+
+/// Token bucket which refills `per_second` tokens every second up to `burst` tokens.
+pub struct TokenBucket {
+    per_second: f64,
+    burst: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(per_second: NonZeroU32, burst: u32) -> Self {
+        let burst = burst.max(1) as f64;
+        TokenBucket {
+            per_second: per_second.get() as f64,
+            burst,
+            tokens: burst,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Take a token and return how long the caller has to wait until the token is available.
+    /// Tokens are reserved upfront, so the next callers are queued behind the waiting one.
+    pub fn acquire(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.burst);
+        self.refilled_at = now;
+
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(-self.tokens / self.per_second)
+    }
+}
+
+/// Limits of message throughput - global (all message types) and per message type.
+pub struct RateLimiter {
+    global: Option<Mutex<TokenBucket>>,
+    message_types: HashMap<String, Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        RateLimiter {
+            global: None,
+            message_types: HashMap::new(),
+        }
+    }
+
+    pub fn limit(mut self, per_second: NonZeroU32, burst: u32) -> Self {
+        self.global = Some(Mutex::new(TokenBucket::new(per_second, burst)));
+        self
+    }
+
+    pub fn limit_message<TMessage>(mut self, per_second: NonZeroU32, burst: u32) -> Self
+    where
+        TMessage: MessageTypeName,
+    {
+        self.message_types.insert(
+            TMessage::name().to_string(),
+            Mutex::new(TokenBucket::new(per_second, burst)),
+        );
+        self
+    }
+
+    /// How long the message of given type has to wait before it can be processed.
+    pub fn delay(&self, msg_type: &str) -> Duration {
+        let global = self
+            .global
+            .as_ref()
+            .map(|b| b.lock().unwrap().acquire())
+            .unwrap_or_default();
+        let message_type = self
+            .message_types
+            .get(msg_type)
+            .map(|b| b.lock().unwrap().acquire())
+            .unwrap_or_default();
+        global.max(message_type)
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
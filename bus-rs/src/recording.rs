@@ -0,0 +1,184 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Client, ClientAsync, ClientCallbackFnAsync, ClientError, LayerError, PubSubLayer, RawMessage,
+};
+
+/// One line of the recording file - JSON object with the time in milliseconds since the Unix epoch and the message,
+/// e.g. `{"timestamp":1700000000000,"message":{"msg_type":"OrderPlaced","headers":{},"payload":"{}"}}`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Record {
+    pub timestamp: u64,
+    pub message: RawMessage,
+}
+
+/// Layer which appends every message to the recording file. It can be added to publishers and listeners -
+/// the message is recorded in the form seen at the layer position. Layers run in the order they were added,
+/// so to record what goes through the transport add it last to the publisher (after e.g. encryption and signing)
+/// and first to the listener. Failing writes don't stop the traffic, the recording is diagnostic only.
+pub struct RecordingLayer {
+    file: Arc<Mutex<File>>,
+}
+
+impl RecordingLayer {
+    /// Records are appended to the existing file. Fails when the file can't be opened.
+    pub fn new(path: &str) -> Result<Self, ClientError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| ClientError::IO(e.to_string()))?;
+        Ok(RecordingLayer {
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+}
+
+impl PubSubLayer for RecordingLayer {
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let record = Record {
+            timestamp,
+            message: raw_msg.clone(),
+        };
+        let mut line = serde_json::to_string(&record).unwrap();
+        line.push('\n');
+        // one write per record, so records of the layers sharing the file are not interleaved
+        let _ = self.file.lock().unwrap().write_all(line.as_bytes());
+        Ok(())
+    }
+
+    fn after(&self, _raw_msg: &RawMessage) {}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayTiming {
+    /// Messages are delivered one after another without waiting.
+    Immediate,
+    /// Gaps between the messages are the same as when they were recorded.
+    Original,
+}
+
+/// Read records of the file. The last line which was not written completely is ignored.
+pub fn read_recording(path: &str) -> Result<Vec<Record>, ClientError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ClientError::IO(e.to_string()))?;
+    content
+        .split_inclusive('\n')
+        .filter(|line| line.ends_with('\n') && !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|e| ClientError::General(format!("invalid record: {}", e)))
+        })
+        .collect()
+}
+
+/// Client which feeds the recording into the listener. The receiver returns when all records are delivered,
+/// so `listen` finishes as well. Sending is not supported.
+pub struct ReplayClient {
+    path: PathBuf,
+    timing: ReplayTiming,
+}
+
+impl ReplayClient {
+    pub fn new(path: &str) -> Self {
+        ReplayClient {
+            path: PathBuf::from(path),
+            timing: ReplayTiming::Immediate,
+        }
+    }
+
+    /// Default is `ReplayTiming::Immediate`.
+    pub fn timing(mut self, timing: ReplayTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+}
+
+impl Client for ReplayClient {
+    fn receiver(
+        &mut self,
+        recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+    ) -> Result<(), ClientError> {
+        let records = read_recording(&self.path.to_string_lossy())?;
+        let mut previous = None;
+        for record in records {
+            std::thread::sleep(delay(self.timing, previous, record.timestamp));
+            previous = Some(record.timestamp);
+            // Err is dropped and the replay goes on with the next record - the file isn't changed
+            let _ = recv_callback(record.message);
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, _msg: &RawMessage) -> Result<(), ClientError> {
+        Err(ClientError::General(
+            "replay client doesn't send messages".to_string(),
+        ))
+    }
+}
+
+/// Async version of `ReplayClient`.
+pub struct ReplayClientAsync {
+    path: PathBuf,
+    timing: ReplayTiming,
+}
+
+impl ReplayClientAsync {
+    pub fn new(path: &str) -> Self {
+        ReplayClientAsync {
+            path: PathBuf::from(path),
+            timing: ReplayTiming::Immediate,
+        }
+    }
+
+    /// Default is `ReplayTiming::Immediate`.
+    pub fn timing(mut self, timing: ReplayTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+}
+
+#[async_trait]
+impl ClientAsync for ReplayClientAsync {
+    async fn receiver(
+        &mut self,
+        recv_callback: Arc<ClientCallbackFnAsync>,
+    ) -> Result<(), ClientError> {
+        let records = read_recording(&self.path.to_string_lossy())?;
+        let mut previous = None;
+        for record in records {
+            tokio::time::sleep(delay(self.timing, previous, record.timestamp)).await;
+            previous = Some(record.timestamp);
+            // Err is dropped and the replay goes on with the next record - the file isn't changed
+            let _ = recv_callback(record.message).await;
+        }
+        Ok(())
+    }
+
+    async fn send(&mut self, _msg: &RawMessage) -> Result<(), ClientError> {
+        Err(ClientError::General(
+            "replay client doesn't send messages".to_string(),
+        ))
+    }
+}
+
+fn delay(timing: ReplayTiming, previous: Option<u64>, timestamp: u64) -> Duration {
+    match (timing, previous) {
+        (ReplayTiming::Original, Some(previous)) => {
+            Duration::from_millis(timestamp.saturating_sub(previous))
+        }
+        _ => Duration::ZERO,
+    }
+}
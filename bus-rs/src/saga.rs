@@ -0,0 +1,348 @@
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    message_handler_async::MessageHandlerAsync, publisher_async::PublisherAsync, ClientError,
+    MessageConstraints, RawMessage,
+};
+
+/// Long-running process which correlates several message types by the correlation key.
+/// Its state is loaded from `SagaRepository` before a message is handled and persisted afterwards
+/// together with the follow-up messages, which are published from the stored record.
+pub trait Saga: Send + 'static {
+    type State: Serialize + DeserializeOwned + Default + Send;
+
+    /// Name of the saga - state of every saga is stored separately.
+    fn name() -> &'static str;
+
+    /// Called when the state can't be loaded or persisted, or follow-up messages can't be published -
+    /// they stay stored with the state then.
+    fn on_error(&mut self, _correlation_key: &str, _error: ClientError) {}
+}
+
+#[async_trait]
+pub trait SagaHandler<TMessage>: Saga
+where
+    TMessage: MessageConstraints,
+{
+    fn correlation_key(&self, msg: &TMessage, headers: &Option<HashMap<String, String>>) -> String;
+
+    async fn handle(
+        &mut self,
+        msg: TMessage,
+        headers: Option<HashMap<String, String>>,
+        context: &mut SagaContext<Self::State>,
+    );
+}
+
+/// State of the saga instance and actions requested by the handler.
+pub struct SagaContext<TState> {
+    pub state: TState,
+    is_new: bool,
+    completed: bool,
+    commands: Vec<SagaCommand>,
+}
+
+impl<TState> SagaContext<TState> {
+    /// True when there is no stored state for the correlation key - `state` is the default one.
+    pub fn is_new(&self) -> bool {
+        self.is_new
+    }
+
+    /// Publish follow-up message after the state is persisted.
+    pub fn send<TMessage>(&mut self, msg: &TMessage, headers: Option<HashMap<String, String>>)
+    where
+        TMessage: MessageConstraints,
+    {
+        self.commands.push(SagaCommand {
+            message: RawMessage::from_message(msg, headers),
+            at: None,
+        });
+    }
+
+    /// Publish the message after `delay` - publisher has to have a scheduler.
+    pub fn schedule_timeout<TMessage>(
+        &mut self,
+        msg: &TMessage,
+        headers: Option<HashMap<String, String>>,
+        delay: Duration,
+    ) where
+        TMessage: MessageConstraints,
+    {
+        self.commands.push(SagaCommand {
+            message: RawMessage::from_message(msg, headers),
+            at: Some(SystemTime::now() + delay),
+        });
+    }
+
+    /// Finish the saga - its state is removed from the repository.
+    pub fn complete(&mut self) {
+        self.completed = true;
+    }
+}
+
+/// Follow-up message of the saga, stored with its state until it's published.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SagaCommand {
+    pub message: RawMessage,
+    /// Time of the timeout - the message is scheduled by the publisher.
+    pub at: Option<SystemTime>,
+}
+
+/// Stored state of the saga instance. `version` is increased by every save.
+#[derive(Clone, Debug, Default)]
+pub struct SagaRecord {
+    pub state: String,
+    /// Follow-up messages which aren't published yet.
+    pub commands: Vec<SagaCommand>,
+    /// Completed saga is kept until its follow-up messages are published.
+    pub completed: bool,
+    pub version: u64,
+}
+
+#[async_trait]
+pub trait SagaRepository: Send + Sync {
+    async fn load(&self, saga: &str, key: &str) -> Result<Option<SagaRecord>, ClientError>;
+
+    /// Save the record only when the stored version is still `expected_version` (`None` - there is
+    /// no stored state), so concurrent changes of the same instance are not overwritten.
+    /// `version` of the record is assigned by the repository - the new version is returned.
+    async fn save(
+        &self,
+        saga: &str,
+        key: &str,
+        record: SagaRecord,
+        expected_version: Option<u64>,
+    ) -> Result<u64, ClientError>;
+
+    /// Delete the state only when the stored version is still `expected_version`, like `save`.
+    async fn delete(
+        &self,
+        saga: &str,
+        key: &str,
+        expected_version: Option<u64>,
+    ) -> Result<(), ClientError>;
+}
+
+pub struct InMemorySagaRepository {
+    records: Mutex<HashMap<(String, String), SagaRecord>>,
+}
+
+impl InMemorySagaRepository {
+    pub fn new() -> Self {
+        InMemorySagaRepository {
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for InMemorySagaRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SagaRepository for InMemorySagaRepository {
+    async fn load(&self, saga: &str, key: &str) -> Result<Option<SagaRecord>, ClientError> {
+        let records = self.records.lock().unwrap();
+        Ok(records.get(&(saga.to_string(), key.to_string())).cloned())
+    }
+
+    async fn save(
+        &self,
+        saga: &str,
+        key: &str,
+        record: SagaRecord,
+        expected_version: Option<u64>,
+    ) -> Result<u64, ClientError> {
+        let mut records = self.records.lock().unwrap();
+        let id = (saga.to_string(), key.to_string());
+        let version = records.get(&id).map(|r| r.version);
+        if version != expected_version {
+            return Err(conflict_error(saga, key));
+        }
+        let version = version.unwrap_or_default() + 1;
+        records.insert(id, SagaRecord { version, ..record });
+        Ok(version)
+    }
+
+    async fn delete(
+        &self,
+        saga: &str,
+        key: &str,
+        expected_version: Option<u64>,
+    ) -> Result<(), ClientError> {
+        let mut records = self.records.lock().unwrap();
+        let id = (saga.to_string(), key.to_string());
+        if records.get(&id).map(|r| r.version) != expected_version {
+            return Err(conflict_error(saga, key));
+        }
+        records.remove(&id);
+        Ok(())
+    }
+}
+
+/// Error of `SagaRepository::save` and `SagaRepository::delete` when the stored version is not the expected one.
+pub fn conflict_error(saga: &str, key: &str) -> ClientError {
+    ClientError::General(format!(
+        "state of saga {} ({}) was changed concurrently",
+        saga, key
+    ))
+}
+
+/// Creates message handlers of the saga, which can be registered in `ListenerAsync`. Handlers of one manager
+/// share the saga and run one at a time, so the version check of the repository only detects changes made
+/// by other processes sharing the repository (e.g. instances of the service consuming the same queue).
+pub struct SagaManager<TSaga> {
+    saga: Arc<tokio::sync::Mutex<TSaga>>,
+    repository: Arc<dyn SagaRepository>,
+    publisher: Arc<PublisherAsync>,
+}
+
+impl<TSaga> SagaManager<TSaga>
+where
+    TSaga: Saga,
+{
+    pub fn new(
+        saga: TSaga,
+        repository: Arc<dyn SagaRepository>,
+        publisher: Arc<PublisherAsync>,
+    ) -> Self {
+        SagaManager {
+            saga: Arc::new(tokio::sync::Mutex::new(saga)),
+            repository,
+            publisher,
+        }
+    }
+
+    pub fn handler<TMessage>(&self) -> SagaMessageHandler<TSaga, TMessage>
+    where
+        TSaga: SagaHandler<TMessage>,
+        TMessage: MessageConstraints,
+    {
+        SagaMessageHandler {
+            saga: self.saga.clone(),
+            repository: self.repository.clone(),
+            publisher: self.publisher.clone(),
+            _message: PhantomData,
+        }
+    }
+}
+
+pub struct SagaMessageHandler<TSaga, TMessage> {
+    saga: Arc<tokio::sync::Mutex<TSaga>>,
+    repository: Arc<dyn SagaRepository>,
+    publisher: Arc<PublisherAsync>,
+    _message: PhantomData<fn() -> TMessage>,
+}
+
+impl<TSaga, TMessage> SagaMessageHandler<TSaga, TMessage>
+where
+    TSaga: SagaHandler<TMessage>,
+    TMessage: MessageConstraints + Send,
+{
+    async fn process(
+        &self,
+        saga: &mut TSaga,
+        key: &str,
+        msg: TMessage,
+        headers: Option<HashMap<String, String>>,
+    ) -> Result<(), ClientError> {
+        let record = self.repository.load(TSaga::name(), key).await?;
+        let expected_version = record.as_ref().map(|r| r.version);
+        // follow-up messages which failed to be published before are kept and published with the new ones
+        let (state, commands) = match record {
+            Some(r) if !r.completed => {
+                let state: TSaga::State = serde_json::from_str(&r.state)
+                    .map_err(|e| ClientError::General(e.to_string()))?;
+                (Some(state), r.commands)
+            }
+            Some(r) => (None, r.commands),
+            None => (None, vec![]),
+        };
+        let mut context = SagaContext {
+            is_new: state.is_none(),
+            state: state.unwrap_or_default(),
+            completed: false,
+            commands,
+        };
+
+        saga.handle(msg, headers, &mut context).await;
+
+        if context.completed && context.commands.is_empty() {
+            return self
+                .repository
+                .delete(TSaga::name(), key, expected_version)
+                .await;
+        }
+        let record = SagaRecord {
+            state: serde_json::to_string(&context.state)
+                .map_err(|e| ClientError::General(e.to_string()))?,
+            commands: context.commands,
+            completed: context.completed,
+            version: 0,
+        };
+        let version = self
+            .repository
+            .save(TSaga::name(), key, record.clone(), expected_version)
+            .await?;
+        self.dispatch(key, SagaRecord { version, ..record }).await
+    }
+
+    /// Publish follow-up messages of the stored record and remove them from it. When publishing fails, they stay
+    /// in the record and they're published again with the next message of the saga instance (at-least-once).
+    async fn dispatch(&self, key: &str, record: SagaRecord) -> Result<(), ClientError> {
+        if record.commands.is_empty() {
+            return Ok(());
+        }
+        for command in &record.commands {
+            match command.at {
+                Some(at) => {
+                    self.publisher
+                        .publish_raw_at(command.message.clone(), at)
+                        .await?
+                }
+                None => self.publisher.publish_raw(command.message.clone()).await?,
+            }
+        }
+
+        if record.completed {
+            self.repository
+                .delete(TSaga::name(), key, Some(record.version))
+                .await
+        } else {
+            let record = SagaRecord {
+                commands: vec![],
+                ..record
+            };
+            let expected_version = Some(record.version);
+            self.repository
+                .save(TSaga::name(), key, record, expected_version)
+                .await
+                .map(|_| ())
+        }
+    }
+}
+
+#[async_trait]
+impl<TSaga, TMessage> MessageHandlerAsync<TMessage> for SagaMessageHandler<TSaga, TMessage>
+where
+    TSaga: SagaHandler<TMessage>,
+    TMessage: MessageConstraints + Send,
+{
+    async fn handle(&mut self, msg: TMessage, headers: Option<HashMap<String, String>>) {
+        let mut saga = self.saga.lock().await;
+        let key = saga.correlation_key(&msg, &headers);
+        if let Err(e) = self.process(&mut saga, &key, msg, headers).await {
+            saga.on_error(&key, e);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+
+use crate::{Client, ClientAsync, ClientError, RawMessage};
+
+/// Storage of messages which should be sent later. Scheduler implementation is responsible
+/// for moving the message onto the channel when it's due.
+pub trait Scheduler {
+    fn schedule(&mut self, msg: &RawMessage, due: SystemTime) -> Result<(), ClientError>;
+}
+
+#[async_trait]
+pub trait SchedulerAsync {
+    async fn schedule(&mut self, msg: &RawMessage, due: SystemTime) -> Result<(), ClientError>;
+}
+
+/// Scheduler which keeps messages in memory - due messages are sent by `dispatch_due`.
+#[derive(Clone)]
+pub struct InMemoryScheduler {
+    messages: Arc<Mutex<Vec<(SystemTime, RawMessage)>>>,
+}
+
+impl InMemoryScheduler {
+    pub fn new() -> Self {
+        InMemoryScheduler {
+            messages: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.messages.lock().unwrap().len()
+    }
+
+    /// Send due messages (in order of their due time) by the client. Returns number of sent messages.
+    /// Messages are taken one by one - the message which failed to be sent stays scheduled with the rest.
+    pub fn dispatch_due(&self, client: &mut dyn Client) -> Result<usize, ClientError> {
+        let now = SystemTime::now();
+        let mut sent = 0;
+        while let Some((when, msg)) = self.take_next_due(now) {
+            if let Err(e) = client.send(&msg) {
+                self.messages.lock().unwrap().push((when, msg));
+                return Err(e);
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    pub async fn dispatch_due_async(
+        &self,
+        client: &mut (dyn ClientAsync + Send),
+    ) -> Result<usize, ClientError> {
+        let now = SystemTime::now();
+        let mut sent = 0;
+        while let Some((when, msg)) = self.take_next_due(now) {
+            if let Err(e) = client.send(&msg).await {
+                self.messages.lock().unwrap().push((when, msg));
+                return Err(e);
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    fn take_next_due(&self, now: SystemTime) -> Option<(SystemTime, RawMessage)> {
+        let mut messages = self.messages.lock().unwrap();
+        let index = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, (when, _))| *when <= now)
+            .min_by_key(|(_, (when, _))| *when)
+            .map(|(index, _)| index)?;
+        Some(messages.remove(index))
+    }
+}
+
+impl Default for InMemoryScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler for InMemoryScheduler {
+    fn schedule(&mut self, msg: &RawMessage, due: SystemTime) -> Result<(), ClientError> {
+        self.messages.lock().unwrap().push((due, msg.clone()));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SchedulerAsync for InMemoryScheduler {
+    async fn schedule(&mut self, msg: &RawMessage, due: SystemTime) -> Result<(), ClientError> {
+        Scheduler::schedule(self, msg, due)
+    }
+}
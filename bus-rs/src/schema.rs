@@ -0,0 +1,123 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use jsonschema::JSONSchema;
+pub use schemars;
+
+use crate::{LayerError, MessageTypeName, PubSubLayer, RawMessage};
+
+/// Schema which can't be compiled.
+#[derive(Clone, Debug)]
+pub struct InvalidSchema {
+    pub msg_type: String,
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid schema of {}: {}", self.msg_type, self.reason)
+    }
+}
+
+struct RegisteredSchema {
+    schema: serde_json::Value,
+    validator: JSONSchema,
+}
+
+/// Schemas of message types, shared between the validation layer and the code which registers them.
+#[derive(Clone)]
+pub struct SchemaRegistry {
+    schemas: Arc<RwLock<HashMap<String, RegisteredSchema>>>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        SchemaRegistry {
+            schemas: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Register schema of the message type (`#[message(schema)]`). Types without schema are skipped.
+    pub fn register<TMessage>(&self) -> Result<(), InvalidSchema>
+    where
+        TMessage: MessageTypeName,
+    {
+        match TMessage::schema() {
+            Some(schema) => self.register_schema(TMessage::name(), schema),
+            None => Ok(()),
+        }
+    }
+
+    pub fn register_schema(
+        &self,
+        msg_type: &str,
+        schema: serde_json::Value,
+    ) -> Result<(), InvalidSchema> {
+        let validator = JSONSchema::compile(&schema).map_err(|e| InvalidSchema {
+            msg_type: msg_type.to_string(),
+            reason: e.to_string(),
+        })?;
+        self.schemas
+            .write()
+            .unwrap()
+            .insert(msg_type.to_string(), RegisteredSchema { schema, validator });
+        Ok(())
+    }
+
+    pub fn schemas(&self) -> HashMap<String, serde_json::Value> {
+        self.schemas
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.schema.clone()))
+            .collect()
+    }
+
+    /// Validate payload against the schema of its message type. Every violation is reported
+    /// as `<json pointer>: <reason>`.
+    pub fn validate(&self, raw_msg: &RawMessage) -> Result<(), LayerError> {
+        let schemas = self.schemas.read().unwrap();
+        let Some(registered) = schemas.get(&raw_msg.msg_type) else {
+            return Ok(());
+        };
+
+        let payload: serde_json::Value = serde_json::from_str(&raw_msg.payload)
+            .map_err(|e| LayerError::Invalid(vec![format!(": {}", e)]))?;
+        registered.validator.validate(&payload).map_err(|errors| {
+            LayerError::Invalid(
+                errors
+                    .map(|e| format!("{}: {}", e.instance_path, e))
+                    .collect(),
+            )
+        })
+    }
+}
+
+impl Default for SchemaRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Listener side layer - rejects messages which don't match the registered schema. `validate_schemas` of the builder
+/// adds it with the registry filled by `register_handler` of the listener.
+pub struct SchemaValidationLayer {
+    registry: SchemaRegistry,
+}
+
+impl SchemaValidationLayer {
+    pub fn new(registry: SchemaRegistry) -> Self {
+        SchemaValidationLayer { registry }
+    }
+}
+
+impl PubSubLayer for SchemaValidationLayer {
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        self.registry.validate(raw_msg)
+    }
+
+    fn after(&self, _raw_msg: &RawMessage) {}
+}
@@ -0,0 +1,145 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{LayerError, PubSubLayer, RawMessage};
+
+pub const SIGNATURE_HEADER: &str = "bus-signature";
+pub const TIMESTAMP_HEADER: &str = "bus-timestamp";
+pub const SIGNED_HEADERS_HEADER: &str = "bus-signed-headers";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Publisher side layer - signs msg_type, payload, timestamp and selected headers with HMAC-SHA256.
+pub struct SigningLayer {
+    key: Vec<u8>,
+    signed_headers: Vec<String>,
+}
+
+impl SigningLayer {
+    pub fn new(key: &[u8]) -> Self {
+        SigningLayer {
+            key: key.to_vec(),
+            signed_headers: vec![],
+        }
+    }
+
+    /// Headers which should be covered by the signature (if they are present in the message).
+    pub fn with_signed_headers(mut self, headers: &[&str]) -> Self {
+        self.signed_headers = headers.iter().map(|h| h.to_string()).collect();
+        self.signed_headers.sort();
+        self.signed_headers.dedup();
+        self
+    }
+}
+
+impl PubSubLayer for SigningLayer {
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let signed_headers = self
+            .signed_headers
+            .iter()
+            .filter(|h| raw_msg.headers.contains_key(h.as_str()))
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(",");
+
+        raw_msg
+            .headers
+            .insert(TIMESTAMP_HEADER.to_string(), timestamp.to_string());
+        raw_msg
+            .headers
+            .insert(SIGNED_HEADERS_HEADER.to_string(), signed_headers);
+
+        let signature = sign(&self.key, raw_msg)?.finalize().into_bytes();
+        raw_msg
+            .headers
+            .insert(SIGNATURE_HEADER.to_string(), hex::encode(signature));
+        Ok(())
+    }
+
+    fn after(&self, _raw_msg: &RawMessage) {}
+}
+
+/// Listener side layer - rejects unsigned, tampered and stale messages.
+pub struct VerificationLayer {
+    key: Vec<u8>,
+    max_age: Duration,
+}
+
+impl VerificationLayer {
+    pub fn new(key: &[u8], max_age: Duration) -> Self {
+        VerificationLayer {
+            key: key.to_vec(),
+            max_age,
+        }
+    }
+}
+
+impl PubSubLayer for VerificationLayer {
+    fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+        let signature = raw_msg
+            .headers
+            .get(SIGNATURE_HEADER)
+            .ok_or_else(|| LayerError::Rejected("missing signature".to_string()))?;
+        let signature = hex::decode(signature)
+            .map_err(|e| LayerError::Rejected(format!("malformed signature: {}", e)))?;
+
+        sign(&self.key, raw_msg)?
+            .verify_slice(&signature)
+            .map_err(|_| LayerError::Rejected("invalid signature".to_string()))?;
+
+        // signature is valid so the timestamp is trusted at this point
+        let timestamp: u64 = raw_msg.headers[TIMESTAMP_HEADER].parse().unwrap();
+        let sent_at = UNIX_EPOCH + Duration::from_millis(timestamp);
+        let age = SystemTime::now()
+            .duration_since(sent_at)
+            .unwrap_or_else(|e| e.duration());
+        if age > self.max_age {
+            return Err(LayerError::Rejected(format!(
+                "stale message: signed {}ms ago",
+                age.as_millis()
+            )));
+        }
+        Ok(())
+    }
+
+    fn after(&self, _raw_msg: &RawMessage) {}
+}
+
+fn sign(key: &[u8], raw_msg: &RawMessage) -> Result<HmacSha256, LayerError> {
+    let header = |name: &str| {
+        raw_msg
+            .headers
+            .get(name)
+            .ok_or_else(|| LayerError::Rejected(format!("missing {} header", name)))
+    };
+    let timestamp = header(TIMESTAMP_HEADER)?;
+    if timestamp.parse::<u64>().is_err() {
+        return Err(LayerError::Rejected(format!(
+            "malformed timestamp: {}",
+            timestamp
+        )));
+    }
+    let signed_headers = header(SIGNED_HEADERS_HEADER)?;
+
+    let mut mac = HmacSha256::new_from_slice(key).unwrap();
+    // every part is length prefixed, so moving bytes between fields changes the signature
+    let mut update = |part: &str| {
+        mac.update(&(part.len() as u64).to_be_bytes());
+        mac.update(part.as_bytes());
+    };
+    update(&raw_msg.msg_type);
+    update(&raw_msg.payload);
+    update(timestamp);
+    update(signed_headers);
+    for name in signed_headers.split(',').filter(|h| !h.is_empty()) {
+        update(name);
+        update(header(name)?);
+    }
+    Ok(mac)
+}
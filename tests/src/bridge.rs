@@ -0,0 +1,251 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bus_rs::{
+        bridge::{Bridge, Route},
+        builder::{self, Builder},
+        publisher_async::PublisherAsync,
+        ClientAsync, ClientCallbackFnAsync, ClientError, LayerError, PubSubLayer, RawMessage,
+    };
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn should_route_messages_by_type_and_headers() {
+        // given
+        let source = MockClient::new(vec![
+            message("OrderPlaced", &[("tenant", "acme")]),
+            message("OrderPlaced", &[("tenant", "other")]),
+            message("PaymentReceived", &[]),
+            message("UserRegistered", &[]),
+        ]);
+        let (orders, orders_sent) = destination(vec![]);
+        let (audit, audit_sent) = destination(vec![]);
+        let bridge = Bridge::new(Box::new(source))
+            .route(
+                Route::new(orders)
+                    .msg_type("OrderPlaced")
+                    .header("tenant", "acme"),
+            )
+            .route(
+                Route::new(audit)
+                    .msg_type("OrderPlaced")
+                    .msg_type("PaymentReceived"),
+            );
+
+        // when
+        bridge.run().await.unwrap();
+
+        // then
+        let orders_sent = orders_sent.lock().unwrap().clone();
+        assert_eq!(1, orders_sent.len());
+        assert_eq!("acme", orders_sent[0].headers["tenant"]);
+        let audit_types: Vec<String> = audit_sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|msg| msg.msg_type.clone())
+            .collect();
+        assert_eq!(
+            vec!["OrderPlaced", "OrderPlaced", "PaymentReceived"],
+            audit_types
+        );
+    }
+
+    #[tokio::test]
+    async fn should_rewrite_headers_per_route() {
+        // given
+        let source = MockClient::new(vec![message(
+            "OrderPlaced",
+            &[("mqtt-topic", "orders/1"), ("internal", "yes")],
+        )]);
+        let (destination, sent) = destination(vec![]);
+        let bridge = Bridge::new(Box::new(source)).route(
+            Route::new(destination)
+                .rename_header("mqtt-topic", "source-topic")
+                .remove_header("internal")
+                .set_header("bridged-from", "mqtt"),
+        );
+
+        // when
+        bridge.run().await.unwrap();
+
+        // then
+        let sent = sent.lock().unwrap().clone();
+        assert_eq!(
+            HashMap::from([
+                ("source-topic".to_string(), "orders/1".to_string()),
+                ("bridged-from".to_string(), "mqtt".to_string()),
+            ]),
+            sent[0].headers
+        );
+    }
+
+    #[tokio::test]
+    async fn should_apply_layers_on_both_legs_and_return_error_of_destination() {
+        // given
+        let source = MockClient::new(vec![
+            message("OrderPlaced", &[]),
+            message("Skipped", &[]),
+            message("Rejected", &[]),
+            message("OrderPlaced", &[]),
+        ]);
+        let (destination, sent) = destination(vec![Box::new(RejectingLayer)]);
+        let bridge = Bridge::new(Box::new(source))
+            .add_layer(Box::new(SkippingLayer))
+            .route(Route::new(destination));
+
+        // when
+        let result = bridge.run().await;
+
+        // then the message is rejected by the layer of the destination
+        assert!(matches!(
+            result,
+            Err(ClientError::Layer(LayerError::Rejected(_)))
+        ));
+        let msg_types: Vec<String> = sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|msg| msg.msg_type.clone())
+            .collect();
+        assert_eq!(vec!["OrderPlaced"], msg_types);
+    }
+
+    #[tokio::test]
+    async fn should_retry_destination_until_it_accepts_message() {
+        // given destination which is down for the first two attempts
+        let source = MockClient::new(vec![message("OrderPlaced", &[]), message("OrderPaid", &[])]);
+        let destination = FailingClient {
+            failures: 2,
+            messages: Arc::new(Mutex::new(vec![])),
+        };
+        let sent = destination.messages.clone();
+        let destination: PublisherAsync = builder::pubsub_async(Box::new(destination)).build();
+        let bridge = Bridge::new(Box::new(source))
+            .retry_backoff(Duration::from_millis(10))
+            .route(Route::new(Arc::new(destination)));
+
+        // when
+        bridge.run().await.unwrap();
+
+        // then the message is delivered again and in order
+        let msg_types: Vec<String> = sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|msg| msg.msg_type.clone())
+            .collect();
+        assert_eq!(vec!["OrderPlaced", "OrderPaid"], msg_types);
+    }
+
+    // Helpers
+
+    fn message(msg_type: &str, headers: &[(&str, &str)]) -> RawMessage {
+        RawMessage {
+            msg_type: msg_type.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            payload: "{}".to_string(),
+        }
+    }
+
+    fn destination(
+        layers: Vec<Box<dyn PubSubLayer>>,
+    ) -> (Arc<PublisherAsync>, Arc<Mutex<Vec<RawMessage>>>) {
+        let client = MockClient::new(vec![]);
+        let sent = client.messages.clone();
+        let mut builder = builder::pubsub_async(Box::new(client));
+        for layer in layers {
+            builder = builder.add_layer(layer);
+        }
+        (Arc::new(builder.build()), sent)
+    }
+
+    struct SkippingLayer;
+
+    impl PubSubLayer for SkippingLayer {
+        fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+            if raw_msg.msg_type == "Skipped" {
+                return Err(LayerError::Skip);
+            }
+            Ok(())
+        }
+
+        fn after(&self, _raw_msg: &RawMessage) {}
+    }
+
+    struct RejectingLayer;
+
+    impl PubSubLayer for RejectingLayer {
+        fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+            if raw_msg.msg_type == "Rejected" {
+                return Err(LayerError::Rejected("rejected".to_string()));
+            }
+            Ok(())
+        }
+
+        fn after(&self, _raw_msg: &RawMessage) {}
+    }
+
+    struct MockClient {
+        messages: Arc<Mutex<Vec<RawMessage>>>,
+    }
+
+    impl MockClient {
+        fn new(messages: Vec<RawMessage>) -> Self {
+            MockClient {
+                messages: Arc::new(Mutex::new(messages)),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ClientAsync for MockClient {
+        async fn receiver(
+            &mut self,
+            recv_callback: Arc<ClientCallbackFnAsync>,
+        ) -> Result<(), ClientError> {
+            let messages = self.messages.lock().unwrap().clone();
+            for msg in messages {
+                recv_callback(msg).await?;
+            }
+            Ok(())
+        }
+
+        async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+
+    struct FailingClient {
+        failures: u32,
+        messages: Arc<Mutex<Vec<RawMessage>>>,
+    }
+
+    #[async_trait]
+    impl ClientAsync for FailingClient {
+        async fn receiver(
+            &mut self,
+            _recv_callback: Arc<ClientCallbackFnAsync>,
+        ) -> Result<(), ClientError> {
+            Ok(())
+        }
+
+        async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(ClientError::IO("connection refused".to_string()));
+            }
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,263 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::TcpListener,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        thread::{sleep, spawn},
+        time::Duration,
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+        ClientError,
+    };
+    use bus_rs_broker::{BrokerClient, BrokerClientAsync, BrokerServer, ChannelKind};
+    use bus_rs_socket::frame::{read_frame, write_frame};
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_topic_deliver_message_to_all_subscribers() {
+        // given
+        spawn(|| {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(BrokerServer::new().tcp("127.0.0.1:17044").run())
+        });
+        sleep(Duration::from_millis(100));
+        let first_logger = Arc::new(Mutex::new(TestLogger::new()));
+        let second_logger = Arc::new(Mutex::new(TestLogger::new()));
+        for logger in [first_logger.clone(), second_logger.clone()] {
+            let client = BrokerClient::new("127.0.0.1:17044", "orders".to_string());
+            let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+            listener.register_handler(TestMessageHandler { logger });
+            spawn(move || listener.listen());
+        }
+        sleep(Duration::from_millis(100));
+
+        // when
+        let client = BrokerClient::new("127.0.0.1:17044", "orders".to_string());
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test".to_string(),
+        };
+        publisher.publish(&test_msg, None).unwrap();
+
+        // then
+        sleep(Duration::from_millis(200));
+        assert_eq!(
+            vec!["msg: test headers: "],
+            *first_logger.lock().unwrap().get()
+        );
+        assert_eq!(
+            vec!["msg: test headers: "],
+            *second_logger.lock().unwrap().get()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_queue_deliver_every_message_to_one_consumer() {
+        // given
+        tokio::spawn(BrokerServer::new().tcp("127.0.0.1:17045").run());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let first_logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let second_logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        start_queue_listener("127.0.0.1:17045", first_logger.clone()).await;
+        start_queue_listener("127.0.0.1:17045", second_logger.clone()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // when
+        let client = BrokerClientAsync::new("127.0.0.1:17045", "orders".to_string())
+            .kind(ChannelKind::Queue);
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        for i in 0..4 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+
+        // then
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let first = first_logger.lock().await.get().clone();
+        let second = second_logger.lock().await.get().clone();
+        assert!(!first.is_empty());
+        assert!(!second.is_empty());
+        let mut messages = [first, second].concat();
+        messages.sort();
+        assert_eq!(
+            vec![
+                "msg: 0 headers: ",
+                "msg: 1 headers: ",
+                "msg: 2 headers: ",
+                "msg: 3 headers: "
+            ],
+            messages
+        );
+    }
+
+    #[tokio::test]
+    async fn should_restarted_broker_restore_persisted_queue() {
+        // given messages published to the queue without consumers
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        let broker_task = tokio::spawn(
+            BrokerServer::new()
+                .tcp("127.0.0.1:17046")
+                .data_dir(&path)
+                .run(),
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let client = BrokerClientAsync::new("127.0.0.1:17046", "orders".to_string())
+            .kind(ChannelKind::Queue);
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        for i in 0..2 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+
+        // when
+        broker_task.abort();
+        tokio::spawn(
+            BrokerServer::new()
+                .tcp("127.0.0.1:17047")
+                .data_dir(&path)
+                .run(),
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        start_queue_listener("127.0.0.1:17047", logger.clone()).await;
+
+        // then
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            vec!["msg: 0 headers: ", "msg: 1 headers: "],
+            *logger.lock().await.get()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_websocket_subscriber_receive_published_message() {
+        // given
+        tokio::spawn(
+            BrokerServer::new()
+                .tcp("127.0.0.1:17048")
+                .ws("127.0.0.1:17049")
+                .run(),
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let (mut ws, _) = tokio_tungstenite::connect_async("ws://127.0.0.1:17049")
+            .await
+            .unwrap();
+        let subscribe = r#"{"op":"subscribe","kind":"topic","channel":"orders"}"#;
+        ws.send(Message::Text(subscribe.to_string())).await.unwrap();
+        assert_eq!(r#"{"op":"ok"}"#, next_text(&mut ws).await);
+
+        // when
+        let client = BrokerClientAsync::new("127.0.0.1:17048", "orders".to_string());
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test".to_string(),
+        };
+        publisher.publish(&test_msg, None).await.unwrap();
+
+        // then
+        let deliver: serde_json::Value = serde_json::from_str(&next_text(&mut ws).await).unwrap();
+        assert_eq!("deliver", deliver["op"]);
+        assert_eq!("orders", deliver["channel"]);
+        assert_eq!("TestMessage", deliver["message"]["msg_type"]);
+        assert_eq!(r#"{"data":"test"}"#, deliver["message"]["payload"]);
+    }
+
+    #[test]
+    fn should_not_publish_again_when_response_of_written_frame_is_lost() {
+        // given broker which closes the connection without the response
+        let listener = TcpListener::bind("127.0.0.1:17050").unwrap();
+        let received = Arc::new(AtomicUsize::new(0));
+        let received_ref = received.clone();
+        spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                if let Ok(Some(_)) = read_frame::<serde_json::Value>(&mut stream) {
+                    received_ref.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        });
+        let client = BrokerClient::new("127.0.0.1:17050", "orders".to_string());
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let result = publisher.publish(&test_msg, None);
+
+        // then
+        sleep(Duration::from_millis(100));
+        assert!(matches!(result, Err(ClientError::IO(_))));
+        assert_eq!(1, received.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn should_publisher_reconnect_when_broker_closed_connection() {
+        // given broker which closes the connection after every response
+        let listener = TcpListener::bind("127.0.0.1:17051").unwrap();
+        spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                if let Ok(Some(_)) = read_frame::<serde_json::Value>(&mut stream) {
+                    write_frame(&mut stream, &serde_json::json!({"op": "ok"})).unwrap();
+                }
+            }
+        });
+        let client = BrokerClient::new("127.0.0.1:17051", "orders".to_string());
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+        publisher.publish(&test_msg, None).unwrap();
+        sleep(Duration::from_millis(100));
+
+        // when
+        let result = publisher.publish(&test_msg, None);
+
+        // then
+        assert!(result.is_ok());
+    }
+
+    // Helpers
+
+    async fn start_queue_listener(addr: &str, logger: Arc<tokio::sync::Mutex<TestLogger>>) {
+        let client = BrokerClientAsync::new(addr, "orders".to_string()).kind(ChannelKind::Queue);
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync { logger })
+            .await;
+        tokio::spawn(async move {
+            let _ = listener.listen().await;
+        });
+    }
+
+    async fn next_text(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    ) -> String {
+        let message = tokio::time::timeout(Duration::from_secs(1), ws.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        message.into_text().unwrap()
+    }
+}
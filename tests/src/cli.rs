@@ -0,0 +1,292 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        publisher_async::PublisherAsync,
+        ClientCallbackFnAsync, RawMessage,
+    };
+    use bus_rs_broker::BrokerServer;
+    use bus_rs_cli::{parse_headers, pretty, Filter, Mode, Stats, Transport, TransportArgs};
+    use bus_rs_sqlite::SqliteClientAsync;
+
+    #[test]
+    fn should_filter_match_messages_by_type_and_headers() {
+        // given
+        let filter = Filter {
+            msg_types: vec!["OrderCreated".to_string(), "OrderPaid".to_string()],
+            headers: parse_headers(&["tenant=acme".to_string()]).unwrap(),
+        };
+
+        // when
+        let matching = filter.matches(&raw_msg("OrderPaid", &[("tenant", "acme")]));
+        let wrong_type = filter.matches(&raw_msg("OrderShipped", &[("tenant", "acme")]));
+        let wrong_header = filter.matches(&raw_msg("OrderPaid", &[("tenant", "other")]));
+        let missing_header = filter.matches(&raw_msg("OrderPaid", &[]));
+
+        // then
+        assert!(matching);
+        assert!(!wrong_type);
+        assert!(!wrong_header);
+        assert!(!missing_header);
+        assert!(Filter::default().matches(&raw_msg("Anything", &[])));
+    }
+
+    #[test]
+    fn should_reject_header_without_name_or_value_separator() {
+        // when
+        let valid = parse_headers(&["trace=a=b".to_string(), "empty=".to_string()]);
+        let no_separator = parse_headers(&["trace".to_string()]);
+        let no_name = parse_headers(&["=value".to_string()]);
+
+        // then
+        assert_eq!(
+            HashMap::from([
+                ("trace".to_string(), "a=b".to_string()),
+                ("empty".to_string(), "".to_string())
+            ]),
+            valid.unwrap()
+        );
+        assert!(no_separator.is_err());
+        assert!(no_name.is_err());
+    }
+
+    #[test]
+    fn should_pretty_print_json_payload_as_value() {
+        // given
+        let mut json_msg = raw_msg("OrderCreated", &[("b", "2"), ("a", "1")]);
+        json_msg.payload = r#"{"id":7}"#.to_string();
+        let mut text_msg = raw_msg("Note", &[]);
+        text_msg.payload = "plain text".to_string();
+
+        // when
+        let json_output = pretty(&json_msg);
+        let text_output = pretty(&text_msg);
+
+        // then
+        assert_eq!(
+            r#"{
+  "headers": {
+    "a": "1",
+    "b": "2"
+  },
+  "msg_type": "OrderCreated",
+  "payload": {
+    "id": 7
+  }
+}"#,
+            json_output
+        );
+        assert!(text_output.contains(r#""payload": "plain text""#));
+    }
+
+    #[test]
+    fn should_report_rates_per_type_and_reset_counts() {
+        // given
+        let mut stats = Stats::new();
+        for _ in 0..4 {
+            stats.record(&raw_msg("OrderCreated", &[]));
+        }
+        stats.record(&raw_msg("OrderPaid", &[]));
+
+        // when
+        let report = stats.report(Duration::from_secs(2));
+        let next_report = stats.report(Duration::from_secs(2));
+
+        // then
+        assert_eq!(3, report.len());
+        assert!(report[0].starts_with("OrderCreated"));
+        assert!(report[0].ends_with("4 msgs        2.0 msg/s"));
+        assert!(report[1].starts_with("OrderPaid"));
+        assert!(report[1].ends_with("1 msgs        0.5 msg/s"));
+        assert!(report[2].starts_with("total"));
+        assert!(report[2].ends_with("5 msgs        2.5 msg/s"));
+        assert_eq!(1, next_report.len());
+        assert!(next_report[0].ends_with("0 msgs        0.0 msg/s"));
+    }
+
+    #[tokio::test]
+    async fn should_tail_messages_published_by_cli_transport() {
+        // given the receiving client created the same way as by `tail`
+        let dir = tempfile::tempdir().unwrap();
+        let args = TransportArgs {
+            transport: Transport::File,
+            url: dir.path().to_str().unwrap().to_string(),
+            channel: Some("orders".to_string()),
+            table: "bus_queue".to_string(),
+            stream: "BUS".to_string(),
+        };
+        let received = Arc::new(Mutex::new(vec![]));
+        let callback: Arc<ClientCallbackFnAsync> = Arc::new({
+            let received = received.clone();
+            move |raw_msg: RawMessage| {
+                received.lock().unwrap().push(raw_msg.msg_type);
+                Box::pin(async { Ok(()) })
+            }
+        });
+        let mut receiver = args.client(Mode::Receive).await.unwrap();
+        tokio::spawn(async move {
+            let _ = receiver.receiver(callback).await;
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // when
+        let publisher: PublisherAsync =
+            builder::pubsub_async(args.client(Mode::Publish).await.unwrap()).build();
+        publisher
+            .publish_raw(raw_msg("OrderCreated", &[("tenant", "acme")]))
+            .await
+            .unwrap();
+
+        // then
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(vec!["OrderCreated"], *received.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_deliver_message_published_by_cli_over_network_transport() {
+        // given broker and the receiving client created the same way as by `tail`
+        tokio::spawn(BrokerServer::new().tcp("127.0.0.1:17061").run());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let args = transport_args(Transport::BrokerTopic, "127.0.0.1:17061");
+        let received = Arc::new(Mutex::new(vec![]));
+        let callback: Arc<ClientCallbackFnAsync> = Arc::new({
+            let received = received.clone();
+            move |raw_msg: RawMessage| {
+                received.lock().unwrap().push(raw_msg.msg_type);
+                Box::pin(async { Ok(()) })
+            }
+        });
+        let mut receiver = args.client(Mode::Receive).await.unwrap();
+        tokio::spawn(async move {
+            let _ = receiver.receiver(callback).await;
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // when the publisher is dropped right after publishing, like when the `publish` process exits
+        {
+            let publisher: PublisherAsync =
+                builder::pubsub_async(args.client(Mode::Publish).await.unwrap()).build();
+            publisher
+                .publish_raw(raw_msg("OrderCreated", &[]))
+                .await
+                .unwrap();
+        }
+
+        // then
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(vec!["OrderCreated"], *received.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_tail_only_new_sqlite_messages() {
+        // given message published before `tail` is started
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bus.db").to_str().unwrap().to_string();
+        let args = transport_args(Transport::Sqlite, &path);
+        let mut publisher = args.client(Mode::Publish).await.unwrap();
+        publisher.send(&raw_msg("OrderCreated", &[])).await.unwrap();
+        let received = Arc::new(Mutex::new(vec![]));
+        let callback: Arc<ClientCallbackFnAsync> = Arc::new({
+            let received = received.clone();
+            move |raw_msg: RawMessage| {
+                received.lock().unwrap().push(raw_msg.msg_type);
+                Box::pin(async { Ok(()) })
+            }
+        });
+        let mut receiver = args.client(Mode::Receive).await.unwrap();
+        tokio::spawn(async move {
+            let _ = receiver.receiver(callback).await;
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // when
+        publisher.send(&raw_msg("OrderPaid", &[])).await.unwrap();
+
+        // then
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(vec!["OrderPaid"], *received.lock().unwrap());
+        args.remove_consumer().unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_remove_sqlite_cursor_of_tail() {
+        // given messages received by `tail` and by the other consumer
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bus.db").to_str().unwrap().to_string();
+        let args = transport_args(Transport::Sqlite, &path);
+        let mut publisher = args.client(Mode::Publish).await.unwrap();
+        publisher.send(&raw_msg("OrderCreated", &[])).await.unwrap();
+        for mut receiver in [
+            args.client(Mode::Receive).await.unwrap(),
+            Box::new(SqliteClientAsync::new(&path, "orders".to_string())),
+        ] {
+            let task = tokio::spawn(async move {
+                let callback: Arc<ClientCallbackFnAsync> = Arc::new(|_| Box::pin(async { Ok(()) }));
+                let _ = receiver.receiver(callback).await;
+            });
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            task.abort();
+        }
+
+        // when `tail` exits
+        args.remove_consumer().unwrap();
+
+        // then the message can be purged
+        let purged = SqliteClientAsync::new(&path, "orders".to_string())
+            .purge_consumed()
+            .unwrap();
+        assert_eq!(1, purged);
+        let cursors: i64 = rusqlite::Connection::open(&path)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM bus_cursors", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(1, cursors);
+    }
+
+    #[tokio::test]
+    async fn should_require_channel_for_transports_which_use_it() {
+        // given
+        let args = TransportArgs {
+            transport: Transport::BrokerTopic,
+            url: "127.0.0.1:7070".to_string(),
+            channel: None,
+            table: "bus_queue".to_string(),
+            stream: "BUS".to_string(),
+        };
+
+        // when
+        let result = args.client(Mode::Publish).await;
+
+        // then
+        assert!(result.is_err());
+    }
+
+    // Helpers
+
+    fn transport_args(transport: Transport, url: &str) -> TransportArgs {
+        TransportArgs {
+            transport,
+            url: url.to_string(),
+            channel: Some("orders".to_string()),
+            table: "bus_queue".to_string(),
+            stream: "BUS".to_string(),
+        }
+    }
+
+    fn raw_msg(msg_type: &str, headers: &[(&str, &str)]) -> RawMessage {
+        RawMessage {
+            msg_type: msg_type.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            payload: "{}".to_string(),
+        }
+    }
+}
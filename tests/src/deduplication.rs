@@ -0,0 +1,278 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bus_rs::{
+        builder::{self, Builder},
+        deduplication::{
+            DeduplicationLayer, DeduplicationStore, InMemoryDeduplicationStore, MessageIdLayer,
+            MESSAGE_ID_HEADER,
+        },
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        Client, ClientAsync, ClientCallbackFnAsync, ClientError, LayerError, PubSubLayer,
+        RawMessage,
+    };
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_listener_skip_already_processed_messages() {
+        // given
+        let client = MockClient::new(messages());
+        let results = client.results.clone();
+        let layer = DeduplicationLayer::new(Box::new(InMemoryDeduplicationStore::new(
+            100,
+            Duration::from_secs(60),
+        )));
+        let stats = layer.stats();
+        let mut listener: Listener = builder::pubsub(Box::new(client))
+            .add_layer(Box::new(layer))
+            .build();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+
+        // when
+        listener.listen().unwrap();
+
+        // then
+        assert_eq!(3, logger.lock().unwrap().get().len());
+        assert_eq!(2, stats.suppressed());
+        assert!(results.lock().unwrap().iter().all(|r| r.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn should_listener_async_skip_already_processed_messages() {
+        // given
+        let client = MockClient::new(messages());
+        let layer = DeduplicationLayer::new(Box::new(InMemoryDeduplicationStore::new(
+            100,
+            Duration::from_secs(60),
+        )));
+        let stats = layer.stats();
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client))
+            .add_layer(Box::new(layer))
+            .build();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+
+        // when
+        listener.listen().await.unwrap();
+
+        // then
+        assert_eq!(3, logger.lock().await.get().len());
+        assert_eq!(2, stats.suppressed());
+    }
+
+    #[test]
+    fn should_in_memory_store_forget_expired_and_evicted_ids() {
+        // given
+        let store = InMemoryDeduplicationStore::new(2, Duration::from_millis(50));
+
+        // when
+        assert!(store.claim("a").unwrap());
+        assert!(store.claim("b").unwrap());
+        assert!(!store.claim("a").unwrap());
+        assert!(store.claim("c").unwrap());
+
+        // then least recently seen id is evicted - the duplicate made the older id recent
+        assert!(!store.claim("a").unwrap());
+        assert!(!store.claim("c").unwrap());
+        assert!(store.claim("b").unwrap());
+
+        // and all ids expire
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(store.claim("a").unwrap());
+        assert!(store.claim("c").unwrap());
+    }
+
+    #[test]
+    fn should_release_id_of_message_rejected_by_later_layer() {
+        // given first delivery is rejected by the layer after deduplication
+        let client = MockClient::new(messages());
+        let results = client.results.clone();
+        let mut listener: Listener = builder::pubsub(Box::new(client))
+            .add_layer(Box::new(DeduplicationLayer::new(Box::new(
+                InMemoryDeduplicationStore::new(100, Duration::from_secs(60)),
+            ))))
+            .add_layer(Box::new(RejectOnceLayer {
+                rejected: Mutex::new(false),
+            }))
+            .build();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+
+        // when
+        listener.listen().unwrap();
+
+        // then redelivery of the rejected message is handled
+        assert!(results.lock().unwrap()[0].is_err());
+        assert_eq!(
+            vec![
+                "msg: data id1 headers: bus-message-id=id1",
+                "msg: data id2 headers: bus-message-id=id2",
+                "msg: data  headers: "
+            ],
+            *logger.lock().unwrap().get()
+        );
+    }
+
+    #[test]
+    fn should_only_one_concurrent_listener_claim_the_message_id() {
+        // given
+        let store = Arc::new(InMemoryDeduplicationStore::new(
+            100,
+            Duration::from_secs(60),
+        ));
+
+        // when
+        let claims: Vec<bool> = (0..8)
+            .map(|_| {
+                let store = store.clone();
+                std::thread::spawn(move || store.claim("id1").unwrap())
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|claim| claim.join().unwrap())
+            .collect();
+
+        // then
+        assert_eq!(1, claims.iter().filter(|claimed| **claimed).count());
+    }
+
+    #[test]
+    fn should_message_id_layer_assign_unique_id_to_published_message() {
+        // given
+        let client = MockClient::new(vec![]);
+        let sent = client.messages.clone();
+        let publisher: Publisher = builder::pubsub(Box::new(client))
+            .add_layer(Box::new(MessageIdLayer))
+            .build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        publisher.publish(&test_msg, None).unwrap();
+        publisher.publish(&test_msg, None).unwrap();
+        publisher
+            .publish(
+                &test_msg,
+                Some(HashMap::from([(
+                    MESSAGE_ID_HEADER.to_string(),
+                    "order-1".to_string(),
+                )])),
+            )
+            .unwrap();
+
+        // then
+        let sent = sent.lock().unwrap();
+        assert_ne!(
+            sent[0].headers[MESSAGE_ID_HEADER],
+            sent[1].headers[MESSAGE_ID_HEADER]
+        );
+        assert_eq!("order-1", sent[2].headers[MESSAGE_ID_HEADER]);
+    }
+
+    fn messages() -> Vec<RawMessage> {
+        ["id1", "id1", "id2", "", "id2"]
+            .iter()
+            .map(|id| {
+                let mut raw_msg: RawMessage = TestMessage {
+                    data: format!("data {}", id),
+                }
+                .into();
+                if !id.is_empty() {
+                    raw_msg
+                        .headers
+                        .insert(MESSAGE_ID_HEADER.to_string(), id.to_string());
+                }
+                raw_msg
+            })
+            .collect()
+    }
+
+    // Helpers
+    struct RejectOnceLayer {
+        rejected: Mutex<bool>,
+    }
+
+    impl PubSubLayer for RejectOnceLayer {
+        fn before(&self, _raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+            let mut rejected = self.rejected.lock().unwrap();
+            if !*rejected {
+                *rejected = true;
+                return Err(LayerError::Rejected("not yet".to_string()));
+            }
+            Ok(())
+        }
+
+        fn after(&self, _raw_msg: &RawMessage) {}
+    }
+
+    struct MockClient {
+        messages: Arc<Mutex<Vec<RawMessage>>>,
+        results: Arc<Mutex<Vec<Result<(), ClientError>>>>,
+    }
+
+    impl MockClient {
+        fn new(messages: Vec<RawMessage>) -> Self {
+            MockClient {
+                messages: Arc::new(Mutex::new(messages)),
+                results: Arc::new(Mutex::new(vec![])),
+            }
+        }
+    }
+
+    impl Client for MockClient {
+        fn receiver(
+            &mut self,
+            recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+        ) -> Result<(), ClientError> {
+            let messages = self.messages.lock().unwrap().clone();
+            for msg in messages {
+                let result = recv_callback(msg);
+                self.results.lock().unwrap().push(result);
+            }
+            Ok(())
+        }
+
+        fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ClientAsync for MockClient {
+        async fn receiver(
+            &mut self,
+            recv_callback: Arc<ClientCallbackFnAsync>,
+        ) -> Result<(), ClientError> {
+            let messages = self.messages.lock().unwrap().clone();
+            for msg in messages {
+                recv_callback(msg).await?;
+            }
+            Ok(())
+        }
+
+        async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,229 @@
+#[cfg(test)]
+mod tests {
+    use bus_rs::{
+        builder::{self, Builder},
+        encryption::{
+            Cipher, DecryptionLayer, EncryptionKey, EncryptionLayer, Keyring, KEY_ID_HEADER,
+        },
+        listener::Listener,
+        publisher::Publisher,
+        Client, ClientError, LayerError, RawMessage,
+    };
+
+    use std::sync::{Arc, Mutex, RwLock};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler};
+
+    #[test]
+    fn should_encrypt_payload_on_publish_and_decrypt_on_listen() {
+        // given
+        let keyring = Arc::new(RwLock::new(Keyring::new(
+            "k1",
+            EncryptionKey::generate(Cipher::Aes256Gcm),
+        )));
+        let transport = Arc::new(Mutex::new(vec![]));
+        let publisher: Publisher = builder::pubsub(Box::new(MockClient::new(transport.clone())))
+            .add_layer(Box::new(EncryptionLayer::new(keyring.clone())))
+            .build();
+
+        // when
+        publisher
+            .publish(
+                &TestMessage {
+                    data: "secret_data".to_string(),
+                },
+                None,
+            )
+            .unwrap();
+
+        // then
+        let sent = transport.lock().unwrap()[0].clone();
+        assert!(!sent.payload.contains("secret_data"));
+        assert_eq!("k1", sent.headers[KEY_ID_HEADER]);
+
+        // and
+        let (logger, results) = listen(transport, keyring);
+        assert!(results[0].is_ok());
+        assert_eq!(
+            "msg: secret_data headers: ",
+            logger.lock().unwrap().get()[0]
+        );
+    }
+
+    #[test]
+    fn should_decrypt_messages_encrypted_with_previous_key_after_rotation() {
+        // given
+        let keyring = Arc::new(RwLock::new(Keyring::new(
+            "k1",
+            EncryptionKey::generate(Cipher::Aes256Gcm),
+        )));
+        let transport = Arc::new(Mutex::new(vec![]));
+        let publisher: Publisher = builder::pubsub(Box::new(MockClient::new(transport.clone())))
+            .add_layer(Box::new(EncryptionLayer::new(keyring.clone())))
+            .build();
+
+        // when
+        publisher
+            .publish(
+                &TestMessage {
+                    data: "first".to_string(),
+                },
+                None,
+            )
+            .unwrap();
+        keyring
+            .write()
+            .unwrap()
+            .rotate("k2", EncryptionKey::generate(Cipher::ChaCha20Poly1305));
+        publisher
+            .publish(
+                &TestMessage {
+                    data: "second".to_string(),
+                },
+                None,
+            )
+            .unwrap();
+
+        // then
+        assert_eq!("k1", transport.lock().unwrap()[0].headers[KEY_ID_HEADER]);
+        assert_eq!("k2", transport.lock().unwrap()[1].headers[KEY_ID_HEADER]);
+
+        let (logger, results) = listen(transport, keyring);
+        let logger = logger.lock().unwrap();
+        assert!(results.iter().all(|r| r.is_ok()));
+        assert_eq!("msg: first headers: ", logger.get()[0]);
+        assert_eq!("msg: second headers: ", logger.get()[1]);
+    }
+
+    #[test]
+    fn should_reject_message_which_cannot_be_decrypted() {
+        // given
+        let key = EncryptionKey::generate(Cipher::Aes256Gcm);
+        let keyring = Arc::new(RwLock::new(Keyring::new("k1", key.clone())));
+        let transport = Arc::new(Mutex::new(vec![]));
+        let publisher: Publisher = builder::pubsub(Box::new(MockClient::new(transport.clone())))
+            .add_layer(Box::new(EncryptionLayer::new(keyring.clone())))
+            .build();
+        publisher
+            .publish(
+                &TestMessage {
+                    data: "secret_data".to_string(),
+                },
+                None,
+            )
+            .unwrap();
+
+        // when tampered and plain messages arrive
+        let mut tampered = transport.lock().unwrap()[0].clone();
+        tampered.msg_type = "WrongTestMessage".to_string();
+        let mut unknown_key = transport.lock().unwrap()[0].clone();
+        unknown_key
+            .headers
+            .insert(KEY_ID_HEADER.to_string(), "k0".to_string());
+        let plain: RawMessage = TestMessage {
+            data: "plain".to_string(),
+        }
+        .into();
+        *transport.lock().unwrap() = vec![tampered, unknown_key, plain];
+
+        let (logger, results) = listen(transport, keyring);
+
+        // then
+        assert_eq!(0, logger.lock().unwrap().get().len());
+        assert_eq!(3, results.len());
+        assert!(results
+            .iter()
+            .all(|r| matches!(r, Err(ClientError::Layer(LayerError::Rejected(_))))));
+    }
+
+    #[test]
+    fn should_report_message_which_cannot_be_decrypted_to_error_hook() {
+        // given
+        let keyring = Arc::new(RwLock::new(Keyring::new(
+            "k1",
+            EncryptionKey::generate(Cipher::Aes256Gcm),
+        )));
+        let plain: RawMessage = TestMessage {
+            data: "plain".to_string(),
+        }
+        .into();
+        let transport = Arc::new(Mutex::new(vec![plain]));
+        let reported = Arc::new(Mutex::new(vec![]));
+        let reported_ref = reported.clone();
+        let mut listener: Listener = builder::pubsub(Box::new(MockClient::new(transport)))
+            .add_layer(Box::new(DecryptionLayer::new(keyring)))
+            .on_error(move |raw_msg, e| {
+                reported_ref
+                    .lock()
+                    .unwrap()
+                    .push((raw_msg.msg_type.clone(), format!("{:?}", e)));
+            })
+            .build();
+        listener.register_handler(TestMessageHandler {
+            logger: Arc::new(Mutex::new(TestLogger::new())),
+        });
+
+        // when
+        listener.listen().unwrap();
+
+        // then
+        let reported = reported.lock().unwrap();
+        assert_eq!(1, reported.len());
+        assert_eq!("TestMessage", reported[0].0);
+        assert!(reported[0].1.contains("Rejected"));
+    }
+
+    fn listen(
+        transport: Arc<Mutex<Vec<RawMessage>>>,
+        keyring: Arc<RwLock<Keyring>>,
+    ) -> (Arc<Mutex<TestLogger>>, Vec<Result<(), ClientError>>) {
+        let client = MockClient::new(transport);
+        let results = client.results.clone();
+        let mut listener: Listener = builder::pubsub(Box::new(client))
+            .add_layer(Box::new(DecryptionLayer::new(keyring)))
+            .build();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+
+        listener.listen().unwrap();
+
+        let results = results.lock().unwrap().drain(..).collect();
+        (logger, results)
+    }
+
+    // Helpers
+    struct MockClient {
+        messages: Arc<Mutex<Vec<RawMessage>>>,
+        results: Arc<Mutex<Vec<Result<(), ClientError>>>>,
+    }
+
+    impl MockClient {
+        fn new(messages: Arc<Mutex<Vec<RawMessage>>>) -> Self {
+            MockClient {
+                messages,
+                results: Arc::new(Mutex::new(vec![])),
+            }
+        }
+    }
+
+    impl Client for MockClient {
+        fn receiver(
+            &mut self,
+            recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+        ) -> Result<(), ClientError> {
+            let messages = self.messages.lock().unwrap().clone();
+            for msg in messages {
+                let result = recv_callback(msg);
+                self.results.lock().unwrap().push(result);
+            }
+            Ok(())
+        }
+
+        fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::OpenOptions,
+        io::Write,
+        sync::{Arc, Mutex},
+        thread::{sleep, spawn},
+        time::Duration,
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+    };
+    use bus_rs_file::{FileLogClient, FileLogClientAsync, StartFrom};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_new_consumer_replay_log_from_given_offset_across_segments() {
+        // given messages in several segments
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        let client = FileLogClient::new(&path, "orders".to_string()).segment_size(100);
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+        for i in 0..5 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).unwrap();
+        }
+        assert!(
+            std::fs::read_dir(dir.path().join("orders"))
+                .unwrap()
+                .count()
+                > 2
+        );
+
+        // when
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let client = FileLogClient::new(&path, "orders".to_string())
+            .consumer("backfill")
+            .start_from(StartFrom::Offset(2))
+            .poll_interval(Duration::from_millis(10));
+        let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+
+        // then
+        sleep(Duration::from_millis(200));
+        assert_eq!(
+            vec!["msg: 2 headers: ", "msg: 3 headers: ", "msg: 4 headers: "],
+            *logger.lock().unwrap().get()
+        );
+    }
+
+    #[test]
+    fn should_consumer_starting_from_latest_receive_only_new_messages() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        let publisher: Publisher =
+            builder::pubsub(Box::new(FileLogClient::new(&path, "orders".to_string()))).build();
+        let old_msg = TestMessage {
+            data: "old".to_string(),
+        };
+        publisher.publish(&old_msg, None).unwrap();
+
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let client = FileLogClient::new(&path, "orders".to_string())
+            .start_from(StartFrom::Latest)
+            .poll_interval(Duration::from_millis(10));
+        let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+        sleep(Duration::from_millis(100));
+
+        // when
+        let new_msg = TestMessage {
+            data: "new".to_string(),
+        };
+        publisher.publish(&new_msg, None).unwrap();
+
+        // then
+        sleep(Duration::from_millis(200));
+        assert_eq!(vec!["msg: new headers: "], *logger.lock().unwrap().get());
+    }
+
+    #[tokio::test]
+    async fn should_restarted_consumer_continue_from_committed_offset() {
+        // given messages handled by the first listener
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(FileLogClientAsync::new(
+            &path,
+            "orders".to_string(),
+        )))
+        .build();
+        for i in 0..2 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let listener_task = start_listener(&path, logger.clone()).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        listener_task.abort();
+        assert_eq!(2, logger.lock().await.get().len());
+
+        // when message is published while consumer is stopped
+        let test_msg = TestMessage {
+            data: "2".to_string(),
+        };
+        publisher.publish(&test_msg, None).await.unwrap();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let _listener_task = start_listener(&path, logger.clone()).await;
+
+        // then
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(vec!["msg: 2 headers: "], *logger.lock().await.get());
+    }
+
+    #[test]
+    fn should_drop_partially_written_record_when_log_is_reopened() {
+        // given the record which was not written completely
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        let publisher: Publisher =
+            builder::pubsub(Box::new(FileLogClient::new(&path, "orders".to_string()))).build();
+        let first_msg = TestMessage {
+            data: "first".to_string(),
+        };
+        publisher.publish(&first_msg, None).unwrap();
+        let segment_path = dir.path().join("orders").join("00000000000000000000.log");
+        let mut segment = OpenOptions::new().append(true).open(segment_path).unwrap();
+        segment.write_all(br#"{"offset":1,"timest"#).unwrap();
+
+        // when
+        let publisher: Publisher =
+            builder::pubsub(Box::new(FileLogClient::new(&path, "orders".to_string()))).build();
+        let second_msg = TestMessage {
+            data: "second".to_string(),
+        };
+        publisher.publish(&second_msg, None).unwrap();
+
+        // then
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let client = FileLogClient::new(&path, "orders".to_string())
+            .poll_interval(Duration::from_millis(10));
+        let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+        sleep(Duration::from_millis(200));
+        assert_eq!(
+            vec!["msg: first headers: ", "msg: second headers: "],
+            *logger.lock().unwrap().get()
+        );
+    }
+
+    // Helpers
+
+    async fn start_listener(
+        path: &str,
+        logger: Arc<tokio::sync::Mutex<TestLogger>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let client = FileLogClientAsync::new(path, "orders".to_string())
+            .poll_interval(Duration::from_millis(10));
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync { logger })
+            .await;
+        tokio::spawn(async move {
+            let _ = listener.listen().await;
+        })
+    }
+}
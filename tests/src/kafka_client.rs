@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        thread::{sleep, spawn},
+        time::Duration,
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+    };
+    use bus_rs_kafka::{KafkaClient, KafkaClientAsync, PartitionKey};
+    use rdkafka::{
+        consumer::{Consumer, StreamConsumer},
+        ClientConfig, Message,
+    };
+    use testcontainers::{core::WaitFor, *};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_listener_with_kafka_client_receive_message_with_headers() {
+        // given
+        let docker_client = clients::Cli::default();
+        let _node = docker_client.run(Kafka::runnable(19092));
+        let brokers = "127.0.0.1:19092";
+        let publisher: Publisher =
+            builder::pubsub(Box::new(KafkaClient::new(brokers, "orders".to_string()))).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let headers = HashMap::from([("trace-id".to_owned(), "123".to_owned())]);
+        publisher.publish(&test_msg, Some(headers)).unwrap();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let client = KafkaClient::new(brokers, "orders".to_string());
+        let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+
+        // then
+        sleep(Duration::from_secs(10));
+        assert_eq!(
+            vec!["msg: test_data headers: trace-id=123"],
+            *logger.lock().unwrap().get()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_restarted_listener_continue_after_committed_offset() {
+        // given message handled by the first listener
+        let docker_client = clients::Cli::default();
+        let _node = docker_client.run(Kafka::runnable(19093));
+        let brokers = "127.0.0.1:19093";
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(KafkaClientAsync::new(
+            brokers,
+            "orders".to_string(),
+        )))
+        .build();
+        let test_msg = TestMessage {
+            data: "first".to_string(),
+        };
+        publisher.publish(&test_msg, None).await.unwrap();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let listener_task = start_listener(brokers, logger.clone()).await;
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        listener_task.abort();
+        assert_eq!(1, logger.lock().await.get().len());
+
+        // when
+        let test_msg = TestMessage {
+            data: "second".to_string(),
+        };
+        publisher.publish(&test_msg, None).await.unwrap();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let _listener_task = start_listener(brokers, logger.clone()).await;
+
+        // then
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert_eq!(vec!["msg: second headers: "], *logger.lock().await.get());
+    }
+
+    #[tokio::test]
+    async fn should_publish_records_with_key_from_message_field() {
+        // given
+        let docker_client = clients::Cli::default();
+        let _node = docker_client.run(Kafka::runnable(19094));
+        let brokers = "127.0.0.1:19094";
+        let client = KafkaClientAsync::new(brokers, "orders".to_string())
+            .partition_key(PartitionKey::Field("data".to_string()));
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+
+        // when
+        for _ in 0..3 {
+            let test_msg = TestMessage {
+                data: "customer-42".to_string(),
+            };
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+
+        // then
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("group.id", "test")
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .unwrap();
+        consumer.subscribe(&["orders"]).unwrap();
+        let mut partitions = Vec::new();
+        for _ in 0..3 {
+            let record = consumer.recv().await.unwrap();
+            assert_eq!(Some("customer-42".as_bytes()), record.key());
+            partitions.push(record.partition());
+        }
+        partitions.dedup();
+        assert_eq!(1, partitions.len());
+    }
+
+    // Helpers
+
+    async fn start_listener(
+        brokers: &str,
+        logger: Arc<tokio::sync::Mutex<TestLogger>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let client = KafkaClientAsync::new(brokers, "orders".to_string()).group_id("billing");
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync { logger })
+            .await;
+        tokio::spawn(async move {
+            let _ = listener.listen().await;
+        })
+    }
+
+    const NAME: &str = "apache/kafka";
+    const TAG: &str = "3.7.0";
+
+    #[derive(Debug)]
+    pub struct Kafka {
+        env_vars: HashMap<String, String>,
+    }
+
+    impl Kafka {
+        /// Advertised listener has to be reachable by the client, so the broker is mapped to the fixed port.
+        fn runnable(host_port: u16) -> RunnableImage<Kafka> {
+            let env_vars = [
+                ("KAFKA_NODE_ID", "1".to_string()),
+                ("KAFKA_PROCESS_ROLES", "broker,controller".to_string()),
+                (
+                    "KAFKA_LISTENERS",
+                    "PLAINTEXT://:9092,CONTROLLER://:9093".to_string(),
+                ),
+                (
+                    "KAFKA_ADVERTISED_LISTENERS",
+                    format!("PLAINTEXT://127.0.0.1:{}", host_port),
+                ),
+                ("KAFKA_CONTROLLER_LISTENER_NAMES", "CONTROLLER".to_string()),
+                (
+                    "KAFKA_LISTENER_SECURITY_PROTOCOL_MAP",
+                    "CONTROLLER:PLAINTEXT,PLAINTEXT:PLAINTEXT".to_string(),
+                ),
+                (
+                    "KAFKA_CONTROLLER_QUORUM_VOTERS",
+                    "1@localhost:9093".to_string(),
+                ),
+                ("KAFKA_OFFSETS_TOPIC_REPLICATION_FACTOR", "1".to_string()),
+                (
+                    "KAFKA_TRANSACTION_STATE_LOG_REPLICATION_FACTOR",
+                    "1".to_string(),
+                ),
+                ("KAFKA_TRANSACTION_STATE_LOG_MIN_ISR", "1".to_string()),
+                ("KAFKA_GROUP_INITIAL_REBALANCE_DELAY_MS", "0".to_string()),
+                ("KAFKA_NUM_PARTITIONS", "3".to_string()),
+            ];
+            let kafka = Kafka {
+                env_vars: env_vars
+                    .into_iter()
+                    .map(|(key, value)| (key.to_owned(), value))
+                    .collect(),
+            };
+            RunnableImage::from(kafka).with_mapped_port((host_port, 9092))
+        }
+    }
+
+    impl Image for Kafka {
+        type Args = ();
+
+        fn name(&self) -> String {
+            NAME.to_owned()
+        }
+
+        fn tag(&self) -> String {
+            TAG.to_owned()
+        }
+
+        fn ready_conditions(&self) -> Vec<WaitFor> {
+            vec![WaitFor::message_on_stdout("Kafka Server started")]
+        }
+
+        fn env_vars(&self) -> Box<dyn Iterator<Item = (&String, &String)> + '_> {
+            Box::new(self.env_vars.iter())
+        }
+    }
+}
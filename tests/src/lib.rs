@@ -5,17 +5,42 @@ use std::{
 
 use async_trait::async_trait;
 use bus_rs::{
-    message_handler::MessageHandler, message_handler_async::MessageHandlerAsync, PubSubLayer,
+    message_handler::MessageHandler, message_handler_async::MessageHandlerAsync, LayerError,
+    PubSubLayer,
 };
 use bus_rs_macros::message;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+mod amqp_client;
+mod bridge;
+mod broker_client;
+mod cli;
+mod deduplication;
+mod encryption;
+mod file_log_client;
+mod kafka_client;
 mod message_handler;
 mod message_handler_async;
 mod message_store;
+mod mock_client;
+mod mqtt_client;
+mod nats_client;
+mod postgres_client;
+mod publisher_batch;
+mod rate_limit;
+mod recording;
 mod redis_client;
 mod redis_client_async;
+mod saga;
+mod scheduler;
+mod schema;
+mod signing;
+mod socket_client;
+mod sqlite_client;
+mod sqlite_outbox;
+mod webhook_client;
+mod ws_gateway;
 
 struct TestLogger {
     messages: Vec<String>,
@@ -45,11 +70,12 @@ struct TestLayer {
 }
 
 impl PubSubLayer for TestLayer {
-    fn before(&self, raw_msg: &mut bus_rs::RawMessage) {
+    fn before(&self, raw_msg: &mut bus_rs::RawMessage) -> Result<(), LayerError> {
         self.logger
             .lock()
             .unwrap()
             .info(format!("TestLayer before | msg: {:?}", raw_msg));
+        Ok(())
     }
 
     fn after(&self, raw_msg: &bus_rs::RawMessage) {
@@ -65,11 +91,12 @@ struct SecondTestLayer {
 }
 
 impl PubSubLayer for SecondTestLayer {
-    fn before(&self, raw_msg: &mut bus_rs::RawMessage) {
+    fn before(&self, raw_msg: &mut bus_rs::RawMessage) -> Result<(), LayerError> {
         self.logger
             .lock()
             .unwrap()
             .info(format!("SecondTestLayer before | msg: {:?}", raw_msg));
+        Ok(())
     }
 
     fn after(&self, raw_msg: &bus_rs::RawMessage) {
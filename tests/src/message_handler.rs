@@ -75,10 +75,10 @@ mod tests {
     impl Client for MockClient {
         fn receiver(
             &mut self,
-            recv_callback: &dyn Fn(bus_rs::RawMessage),
+            recv_callback: &dyn Fn(bus_rs::RawMessage) -> Result<(), bus_rs::ClientError>,
         ) -> Result<(), bus_rs::ClientError> {
             for msg in self.messages.iter() {
-                recv_callback(msg.clone());
+                let _ = recv_callback(msg.clone());
             }
             Ok(())
         }
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        thread::spawn,
+        time::Duration,
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+        Client, RawMessage,
+    };
+    use bus_rs_test::{MockClient, MockClientAsync};
+
+    use crate::{
+        EmptyTestMessage, TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync,
+    };
+
+    #[test]
+    fn should_handle_injected_messages_before_drain_returns() {
+        // given
+        let mock = MockClient::new();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let mut listener: Listener = builder::pubsub(Box::new(mock.clone())).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        let listener_thread = spawn(move || listener.listen());
+
+        // when
+        for i in 0..3 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            mock.inject(&test_msg, None);
+        }
+        mock.drain_until_idle();
+
+        // then
+        assert_eq!(
+            vec!["msg: 0 headers: ", "msg: 1 headers: ", "msg: 2 headers: "],
+            *logger.lock().unwrap().get()
+        );
+        mock.close();
+        assert!(listener_thread.join().unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_capture_published_messages() {
+        // given
+        let mock = MockClientAsync::new();
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(mock.clone())).build();
+
+        // when
+        let test_msg = TestMessage {
+            data: "order".to_string(),
+        };
+        let headers = HashMap::from([("tenant".to_string(), "acme".to_string())]);
+        publisher.publish(&test_msg, Some(headers)).await.unwrap();
+
+        // then
+        mock.assert_published::<TestMessage>(|msg| msg.data == "order");
+        mock.assert_not_published::<EmptyTestMessage>();
+        assert_eq!(1, mock.published::<TestMessage>().len());
+        assert_eq!("acme", mock.sent()[0].headers["tenant"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "no matching TestMessage was published")]
+    fn should_assert_published_fail_when_no_message_matches() {
+        // given
+        let mock = MockClient::new();
+        let publisher: Publisher = builder::pubsub(Box::new(mock.clone())).build();
+        let test_msg = TestMessage {
+            data: "order".to_string(),
+        };
+        publisher.publish(&test_msg, None).unwrap();
+
+        // when
+        mock.assert_published::<TestMessage>(|msg| msg.data == "other");
+    }
+
+    #[test]
+    #[should_panic(expected = "sent message can't be decoded as TestMessage")]
+    fn should_published_fail_with_raw_message_when_payload_does_not_match_type() {
+        // given
+        let mut mock = MockClient::new();
+        let raw_msg = RawMessage {
+            msg_type: "TestMessage".to_string(),
+            headers: HashMap::new(),
+            payload: "not json".to_string(),
+        };
+        mock.send(&raw_msg).unwrap();
+
+        // when
+        mock.published::<TestMessage>();
+    }
+
+    #[tokio::test]
+    async fn should_async_listener_return_when_closed_mock_is_drained() {
+        // given
+        let mock = MockClientAsync::new();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(mock.clone())).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        let listener_task = tokio::spawn(async move { listener.listen().await });
+
+        // when
+        let test_msg = TestMessage {
+            data: "first".to_string(),
+        };
+        mock.inject(&test_msg, None);
+        mock.drain_until_idle().await;
+        let handled = logger.lock().await.get().clone();
+        mock.close();
+
+        // then
+        assert_eq!(vec!["msg: first headers: "], handled);
+        assert!(listener_task.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "is the listener listening?")]
+    async fn should_drain_fail_when_nobody_listens() {
+        // given
+        let mock = MockClientAsync::new().drain_timeout(Duration::from_millis(50));
+        let test_msg = TestMessage {
+            data: "lost".to_string(),
+        };
+        mock.inject(&test_msg, None);
+
+        // when
+        mock.drain_until_idle().await;
+    }
+}
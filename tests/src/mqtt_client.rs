@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        thread::{sleep, spawn},
+        time::Duration,
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+        ClientError,
+    };
+    use bus_rs_mqtt::{MqttClient, MqttClientAsync, MqttVersion, QoS};
+    use testcontainers::{core::WaitFor, *};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_listener_subscribed_to_topic_filter_receive_messages_of_matching_topics() {
+        // given
+        let docker_client = clients::Cli::default();
+        let node = docker_client.run(Mosquitto);
+        let port = node.get_host_port_ipv4(1883);
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let client = MqttClient::new("127.0.0.1", port, "sensors/+/temperature".to_string());
+        let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+        sleep(Duration::from_millis(500));
+
+        // when
+        for topic in ["sensors/1/temperature", "sensors/1/humidity"] {
+            let client = MqttClient::new("127.0.0.1", port, topic.to_string());
+            let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+            let test_msg = TestMessage {
+                data: topic.to_string(),
+            };
+            publisher.publish(&test_msg, None).unwrap();
+            sleep(Duration::from_millis(200));
+        }
+
+        // then
+        sleep(Duration::from_millis(200));
+        assert_eq!(
+            vec!["msg: sensors/1/temperature headers: "],
+            *logger.lock().unwrap().get()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_mqtt5_client_send_headers_as_user_properties() {
+        // given
+        let docker_client = clients::Cli::default();
+        let node = docker_client.run(Mosquitto);
+        let port = node.get_host_port_ipv4(1883);
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let client = MqttClientAsync::new("127.0.0.1", port, "orders".to_string())
+            .version(MqttVersion::V5)
+            .qos(QoS::ExactlyOnce);
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        tokio::spawn(async move { listener.listen().await });
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let client = MqttClientAsync::new("127.0.0.1", port, "orders".to_string())
+            .version(MqttVersion::V5)
+            .qos(QoS::ExactlyOnce);
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let headers = HashMap::from([("trace-id".to_owned(), "123".to_owned())]);
+        publisher.publish(&test_msg, Some(headers)).await.unwrap();
+
+        // then
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(
+            vec!["msg: test_data headers: trace-id=123"],
+            *logger.lock().await.get()
+        );
+    }
+
+    #[test]
+    fn should_reject_publishing_to_topic_filter() {
+        // given
+        let client = MqttClient::new("127.0.0.1", 1883, "sensors/#".to_string());
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let result = publisher.publish(&test_msg, None);
+
+        // then
+        assert!(matches!(result, Err(ClientError::General(_))));
+    }
+
+    #[test]
+    fn should_send_fail_when_broker_is_unreachable() {
+        // given
+        let port = unused_port();
+        let client = MqttClient::new("127.0.0.1", port, "orders".to_string())
+            .ack_timeout(Duration::from_millis(500));
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let result = publisher.publish(&test_msg, None);
+
+        // then
+        assert!(matches!(result, Err(ClientError::IO(_))));
+    }
+
+    #[tokio::test]
+    async fn should_async_send_fail_when_broker_is_unreachable() {
+        // given
+        let port = unused_port();
+        let client = MqttClientAsync::new("127.0.0.1", port, "orders".to_string())
+            .version(MqttVersion::V5)
+            .ack_timeout(Duration::from_millis(500));
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let result = publisher.publish(&test_msg, None).await;
+
+        // then
+        assert!(matches!(result, Err(ClientError::IO(_))));
+    }
+
+    fn unused_port() -> u16 {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    }
+
+    const NAME: &str = "eclipse-mosquitto";
+    const TAG: &str = "1.6.15";
+
+    #[derive(Debug, Default)]
+    pub struct Mosquitto;
+
+    impl Image for Mosquitto {
+        type Args = ();
+
+        fn name(&self) -> String {
+            NAME.to_owned()
+        }
+
+        fn tag(&self) -> String {
+            TAG.to_owned()
+        }
+
+        fn ready_conditions(&self) -> Vec<WaitFor> {
+            vec![WaitFor::message_on_stderr("running")]
+        }
+    }
+}
@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc, time::Duration};
+
+    use async_trait::async_trait;
+    use bus_rs::{
+        builder::{self, Builder},
+        listener_async::ListenerAsync,
+        message_handler_async::MessageHandlerAsync,
+        publisher_async::PublisherAsync,
+        RawMessage,
+    };
+    use bus_rs_nats::{NatsClientAsync, NatsJetStreamClientAsync, REPLY_TO_HEADER};
+    use testcontainers::{core::WaitFor, *};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandlerAsync};
+
+    #[tokio::test]
+    async fn should_listener_with_nats_client_receive_message_with_headers() {
+        // given
+        let docker_client = clients::Cli::default();
+        let node = docker_client.run(Nats);
+        let url = nats_url(&node);
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let client = NatsClientAsync::new(&url, "orders.*".to_string()).await;
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        tokio::spawn(async move { listener.listen().await });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let client = NatsClientAsync::new(&url, "orders.created".to_string()).await;
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let headers = HashMap::from([("trace-id".to_owned(), "123".to_owned())]);
+        publisher.publish(&test_msg, Some(headers)).await.unwrap();
+
+        // then
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            vec!["msg: test_data headers: trace-id=123"],
+            *logger.lock().await.get()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_request_receive_reply_of_listener() {
+        // given responder
+        let docker_client = clients::Cli::default();
+        let node = docker_client.run(Nats);
+        let url = nats_url(&node);
+        let responder = NatsClientAsync::new(&url, "echo".to_string()).await;
+        let client = NatsClientAsync::new(&url, "echo".to_string()).await;
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener.register_handler(EchoHandler { responder }).await;
+        tokio::spawn(async move { listener.listen().await });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // when
+        let client = NatsClientAsync::new(&url, "echo".to_string()).await;
+        let request = RawMessage::from_message(
+            &TestMessage {
+                data: "ping".to_string(),
+            },
+            None,
+        );
+        let reply = client
+            .request(&request, Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!("TestMessage", reply.msg_type);
+        assert_eq!(r#"{"data":"echo: ping"}"#, reply.payload);
+    }
+
+    #[tokio::test]
+    async fn should_send_message_with_forwarded_reply_header_to_client_subject() {
+        // given
+        let docker_client = clients::Cli::default();
+        let node = docker_client.run(Nats);
+        let url = nats_url(&node);
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let client = NatsClientAsync::new(&url, "orders.created".to_string()).await;
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        tokio::spawn(async move { listener.listen().await });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let client = NatsClientAsync::new(&url, "orders.created".to_string()).await;
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when headers of the received request are forwarded
+        let headers = HashMap::from([(REPLY_TO_HEADER.to_owned(), "_INBOX.1".to_owned())]);
+        publisher.publish(&test_msg, Some(headers)).await.unwrap();
+
+        // then
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(vec!["msg: test_data headers: "], *logger.lock().await.get());
+    }
+
+    #[tokio::test]
+    async fn should_jetstream_keep_messages_until_durable_consumer_is_started() {
+        // given messages published before the listener is started
+        let docker_client = clients::Cli::default();
+        let node = docker_client.run(Nats);
+        let url = nats_url(&node);
+        let client =
+            NatsJetStreamClientAsync::new(&url, "ORDERS", "orders.created".to_string()).await;
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        for i in 0..3 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+
+        // when
+        let client = NatsJetStreamClientAsync::new(&url, "ORDERS", "orders.created".to_string())
+            .await
+            .durable("billing");
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        tokio::spawn(async move { listener.listen().await });
+
+        // then
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(
+            vec!["msg: 0 headers: ", "msg: 1 headers: ", "msg: 2 headers: "],
+            *logger.lock().await.get()
+        );
+    }
+
+    // Helpers
+
+    struct EchoHandler {
+        responder: NatsClientAsync,
+    }
+
+    #[async_trait]
+    impl MessageHandlerAsync<TestMessage> for EchoHandler {
+        async fn handle(&mut self, msg: TestMessage, headers: Option<HashMap<String, String>>) {
+            let reply_to = headers.unwrap()[REPLY_TO_HEADER].clone();
+            let reply = TestMessage {
+                data: format!("echo: {}", msg.data),
+            };
+            self.responder
+                .reply(&reply_to, &RawMessage::from_message(&reply, None))
+                .await
+                .unwrap();
+        }
+    }
+
+    fn nats_url(node: &Container<'_, Nats>) -> String {
+        format!("nats://127.0.0.1:{}", node.get_host_port_ipv4(4222))
+    }
+
+    const NAME: &str = "nats";
+    const TAG: &str = "2.10-alpine";
+
+    #[derive(Debug, Default)]
+    pub struct Nats;
+
+    #[derive(Clone, Debug, Default)]
+    pub struct NatsArgs;
+
+    impl ImageArgs for NatsArgs {
+        fn into_iterator(self) -> Box<dyn Iterator<Item = String>> {
+            // JetStream is enabled
+            Box::new(vec!["-js".to_string()].into_iter())
+        }
+    }
+
+    impl Image for Nats {
+        type Args = NatsArgs;
+
+        fn name(&self) -> String {
+            NAME.to_owned()
+        }
+
+        fn tag(&self) -> String {
+            TAG.to_owned()
+        }
+
+        fn ready_conditions(&self) -> Vec<WaitFor> {
+            vec![WaitFor::message_on_stderr("Server is ready")]
+        }
+    }
+}
@@ -0,0 +1,276 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        thread::{sleep, spawn},
+        time::{Duration, Instant},
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+    };
+    use bus_rs_postgres::{PostgresNotifyClient, PostgresQueueClient, PostgresQueueClientAsync};
+    use testcontainers::{core::WaitFor, *};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_listener_with_postgres_notify_client_receive_message_correctly() {
+        // given
+        let docker_client = clients::Cli::default();
+        let (_node, params) = prepare_postgres_container(&docker_client);
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let mut listener: Listener = builder::pubsub(Box::new(PostgresNotifyClient::new(
+            &params,
+            "test_channel".to_string(),
+        )))
+        .build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+        sleep(Duration::from_millis(200));
+
+        let publisher: Publisher = builder::pubsub(Box::new(PostgresNotifyClient::new(
+            &params,
+            "test_channel".to_string(),
+        )))
+        .build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let headers = HashMap::from([("trace-id".to_owned(), "123".to_owned())]);
+        publisher.publish(&test_msg, Some(headers)).unwrap();
+
+        // then
+        sleep(Duration::from_millis(200));
+        let logger = logger.lock().unwrap();
+        assert_eq!(1, logger.get().len());
+        assert_eq!("msg: test_data headers: trace-id=123", logger.get()[0]);
+    }
+
+    #[test]
+    fn should_postgres_queue_deliver_every_message_to_exactly_one_listener() {
+        // given two listeners
+        let docker_client = clients::Cli::default();
+        let (_node, params) = prepare_postgres_container(&docker_client);
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        for _ in 0..2 {
+            let params = params.clone();
+            let logger = logger.clone();
+            spawn(move || {
+                let client =
+                    PostgresQueueClient::new(&params, "bus_messages", "test_queue".to_string());
+                let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+                listener.register_handler(TestMessageHandler { logger });
+                listener.listen().unwrap();
+            });
+        }
+        sleep(Duration::from_millis(200));
+
+        // given publisher
+        let client = PostgresQueueClient::new(&params, "bus_messages", "test_queue".to_string());
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+
+        // when
+        for i in 0..10 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).unwrap();
+        }
+
+        // then
+        sleep(Duration::from_millis(500));
+        let logger = logger.lock().unwrap();
+        let mut handled = logger.get().clone();
+        handled.sort();
+        handled.dedup();
+        assert_eq!(10, logger.get().len());
+        assert_eq!(10, handled.len());
+    }
+
+    #[tokio::test]
+    async fn should_postgres_queue_keep_messages_until_listener_is_started() {
+        // given messages published before the listener is started
+        let docker_client = clients::Cli::default();
+        let (_node, params) = prepare_postgres_container(&docker_client);
+        let client =
+            PostgresQueueClientAsync::new(&params, "bus_messages", "test_queue".to_string()).await;
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        for i in 0..3 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+
+        // when
+        let client =
+            PostgresQueueClientAsync::new(&params, "bus_messages", "test_queue".to_string())
+                .await
+                .poll_interval(Duration::from_millis(100));
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        tokio::spawn(async move { listener.listen().await });
+
+        // then
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let logger = logger.lock().await;
+        assert_eq!(
+            vec!["msg: 0 headers: ", "msg: 1 headers: ", "msg: 2 headers: "],
+            *logger.get()
+        );
+    }
+
+    #[test]
+    fn should_postgres_notify_client_report_undecodable_notification() {
+        // given
+        let docker_client = clients::Cli::default();
+        let (_node, params) = prepare_postgres_container(&docker_client);
+        let reported = Arc::new(Mutex::new(vec![]));
+        let reported_ref = reported.clone();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let mut listener: Listener = builder::pubsub(Box::new(PostgresNotifyClient::new(
+            &params,
+            "test_channel".to_string(),
+        )))
+        .on_error(move |raw_msg, _| {
+            reported_ref.lock().unwrap().push(raw_msg.payload.clone());
+        })
+        .build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+        sleep(Duration::from_millis(200));
+
+        // when
+        let mut client = postgres::Client::connect(&params, postgres::NoTls).unwrap();
+        client
+            .execute("SELECT pg_notify('test_channel', 'not a message')", &[])
+            .unwrap();
+
+        // then listener is still running
+        sleep(Duration::from_millis(200));
+        assert_eq!(vec!["not a message"], *reported.lock().unwrap());
+        let publisher: Publisher = builder::pubsub(Box::new(PostgresNotifyClient::new(
+            &params,
+            "test_channel".to_string(),
+        )))
+        .build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+        publisher.publish(&test_msg, None).unwrap();
+        sleep(Duration::from_millis(200));
+        assert_eq!(1, logger.lock().unwrap().get().len());
+    }
+
+    #[test]
+    fn should_postgres_queue_use_table_name_which_needs_quoting() {
+        // given
+        let docker_client = clients::Cli::default();
+        let (_node, params) = prepare_postgres_container(&docker_client);
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let listener_params = params.clone();
+        let listener_logger = logger.clone();
+        spawn(move || {
+            let client = PostgresQueueClient::new(
+                &listener_params,
+                "Bus-Messages",
+                "test_queue".to_string(),
+            );
+            let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+            listener.register_handler(TestMessageHandler {
+                logger: listener_logger,
+            });
+            listener.listen().unwrap();
+        });
+        sleep(Duration::from_millis(200));
+        let client = PostgresQueueClient::new(&params, "Bus-Messages", "test_queue".to_string());
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+
+        // when
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+        publisher.publish(&test_msg, None).unwrap();
+
+        // then
+        sleep(Duration::from_millis(500));
+        assert_eq!(
+            vec!["msg: test_data headers: "],
+            *logger.lock().unwrap().get()
+        );
+    }
+
+    fn prepare_postgres_container<'a>(
+        docker: &'a clients::Cli,
+    ) -> (Container<'a, Postgres>, String) {
+        let node = docker.run(Postgres::default());
+        let host_port = node.get_host_port_ipv4(5432);
+        let params = format!("host=127.0.0.1 port={} user=postgres", host_port);
+
+        // the server is restarted after initialization, so it may not accept connections yet
+        let started_at = Instant::now();
+        while postgres::Client::connect(&params, postgres::NoTls).is_err() {
+            assert!(started_at.elapsed() < Duration::from_secs(10));
+            sleep(Duration::from_millis(100));
+        }
+        (node, params)
+    }
+
+    const NAME: &str = "postgres";
+    const TAG: &str = "16.1-alpine";
+
+    #[derive(Debug)]
+    pub struct Postgres {
+        env_vars: HashMap<String, String>,
+    }
+
+    impl Default for Postgres {
+        fn default() -> Self {
+            Postgres {
+                env_vars: HashMap::from([(
+                    "POSTGRES_HOST_AUTH_METHOD".to_owned(),
+                    "trust".to_owned(),
+                )]),
+            }
+        }
+    }
+
+    impl Image for Postgres {
+        type Args = ();
+
+        fn name(&self) -> String {
+            NAME.to_owned()
+        }
+
+        fn tag(&self) -> String {
+            TAG.to_owned()
+        }
+
+        fn ready_conditions(&self) -> Vec<WaitFor> {
+            vec![WaitFor::message_on_stderr(
+                "database system is ready to accept connections",
+            )]
+        }
+
+        fn env_vars(&self) -> Box<dyn Iterator<Item = (&String, &String)> + '_> {
+            Box::new(self.env_vars.iter())
+        }
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bus_rs::{
+        builder::{self, Builder},
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+        Client, ClientAsync, ClientCallbackFnAsync, ClientError, LayerError, PubSubLayer,
+        RawMessage,
+    };
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::{TestLayer, TestLogger, TestMessage};
+
+    #[test]
+    fn should_publish_batch_in_one_client_call_and_report_result_per_message() {
+        // given
+        let client = MockClient::new();
+        let (sent, batches) = (client.sent.clone(), client.batches.clone());
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let publisher: Publisher = builder::pubsub(Box::new(client))
+            .add_layer(Box::new(TestLayer {
+                logger: logger.clone(),
+            }))
+            .add_layer(Box::new(RejectingLayer))
+            .build();
+
+        // when
+        let results = publisher.publish_batch(
+            &messages(),
+            Some(HashMap::from([("trace-id".to_string(), "123".to_string())])),
+        );
+
+        // then
+        assert_eq!(vec![2], *batches.lock().unwrap());
+        assert_eq!(3, results.len());
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            results[1],
+            Err(ClientError::Layer(LayerError::Rejected(_)))
+        ));
+        assert!(results[2].is_ok());
+
+        // and
+        let sent = sent.lock().unwrap();
+        assert_eq!(r#"{"data":"first"}"#, sent[0].payload);
+        assert_eq!(r#"{"data":"third"}"#, sent[1].payload);
+        assert_eq!("123", sent[1].headers["trace-id"]);
+
+        // and after action is called only for the sent messages
+        let logger = logger.lock().unwrap();
+        assert_eq!(
+            2,
+            logger
+                .get()
+                .iter()
+                .filter(|l| l.starts_with("TestLayer after"))
+                .count()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_publisher_async_send_batch_and_report_client_failures() {
+        // given
+        let client = MockClient::new();
+        *client.failing_payload.lock().unwrap() = Some(r#"{"data":"third"}"#.to_string());
+        let (sent, batches) = (client.sent.clone(), client.batches.clone());
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client))
+            .add_layer(Box::new(RejectingLayer))
+            .build();
+
+        // when
+        let results = publisher.publish_batch(&messages(), None).await;
+
+        // then
+        assert_eq!(vec![2], *batches.lock().unwrap());
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(ClientError::Layer(_))));
+        assert!(matches!(results[2], Err(ClientError::IO(_))));
+        assert_eq!(1, sent.lock().unwrap().len());
+    }
+
+    fn messages() -> Vec<TestMessage> {
+        ["first", "reject", "third"]
+            .iter()
+            .map(|data| TestMessage {
+                data: data.to_string(),
+            })
+            .collect()
+    }
+
+    // Helpers
+    struct RejectingLayer;
+
+    impl PubSubLayer for RejectingLayer {
+        fn before(&self, raw_msg: &mut RawMessage) -> Result<(), LayerError> {
+            if raw_msg.payload.contains("reject") {
+                return Err(LayerError::Rejected("rejected".to_string()));
+            }
+            Ok(())
+        }
+
+        fn after(&self, _raw_msg: &RawMessage) {}
+    }
+
+    struct MockClient {
+        sent: Arc<Mutex<Vec<RawMessage>>>,
+        batches: Arc<Mutex<Vec<usize>>>,
+        failing_payload: Arc<Mutex<Option<String>>>,
+    }
+
+    impl MockClient {
+        fn new() -> Self {
+            MockClient {
+                sent: Arc::new(Mutex::new(vec![])),
+                batches: Arc::new(Mutex::new(vec![])),
+                failing_payload: Arc::new(Mutex::new(None)),
+            }
+        }
+
+        fn send_one(&self, msg: &RawMessage) -> Result<(), ClientError> {
+            if self.failing_payload.lock().unwrap().as_ref() == Some(&msg.payload) {
+                return Err(ClientError::IO("broken pipe".to_string()));
+            }
+            self.sent.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+
+    impl Client for MockClient {
+        fn receiver(
+            &mut self,
+            _recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+        ) -> Result<(), ClientError> {
+            todo!("not implemented");
+        }
+
+        fn send(&mut self, _msg: &RawMessage) -> Result<(), ClientError> {
+            panic!("batch should be sent at once");
+        }
+
+        fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+            self.batches.lock().unwrap().push(msgs.len());
+            msgs.iter().map(|msg| self.send_one(msg)).collect()
+        }
+    }
+
+    #[async_trait]
+    impl ClientAsync for MockClient {
+        async fn receiver(
+            &mut self,
+            _recv_callback: Arc<ClientCallbackFnAsync>,
+        ) -> Result<(), ClientError> {
+            todo!("not implemented");
+        }
+
+        async fn send(&mut self, _msg: &RawMessage) -> Result<(), ClientError> {
+            panic!("batch should be sent at once");
+        }
+
+        async fn send_batch(&mut self, msgs: &[RawMessage]) -> Vec<Result<(), ClientError>> {
+            self.batches.lock().unwrap().push(msgs.len());
+            msgs.iter().map(|msg| self.send_one(msg)).collect()
+        }
+    }
+}
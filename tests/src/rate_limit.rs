@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher_async::PublisherAsync,
+        rate_limit::{RateLimiter, TokenBucket},
+        ClientAsync, ClientCallbackFnAsync, ClientError, RawMessage,
+    };
+    use bus_rs_test::{MockClient, MockClientAsync};
+
+    use std::{
+        num::NonZeroU32,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use crate::{
+        EmptyTestMessage, EmptyTestMessageHandler, TestLogger, TestMessage, TestMessageHandler,
+        TestMessageHandlerAsync, WrongTestMessage, WrongTestMessageHandlerAsync,
+    };
+
+    #[test]
+    fn should_token_bucket_allow_burst_and_then_throttle() {
+        // given
+        let mut bucket = TokenBucket::new(NonZeroU32::new(10).unwrap(), 2);
+
+        // when
+        let delays: Vec<Duration> = (0..4).map(|_| bucket.acquire()).collect();
+
+        // then
+        assert_eq!(Duration::ZERO, delays[0]);
+        assert_eq!(Duration::ZERO, delays[1]);
+        assert!(delays[2] > Duration::from_millis(90) && delays[2] <= Duration::from_millis(100));
+        assert!(delays[3] > Duration::from_millis(190) && delays[3] <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn should_listener_throttle_only_limited_message_type() {
+        // given
+        let mock = MockClient::new();
+        for i in 0..3 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            mock.inject(&test_msg, None);
+            let empty_msg = EmptyTestMessage {
+                data: format!("{}", i),
+            };
+            mock.inject(&empty_msg, None);
+        }
+        mock.close();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let rate_limiter =
+            RateLimiter::new().limit_message::<TestMessage>(NonZeroU32::new(20).unwrap(), 1);
+        let mut listener: Listener = builder::pubsub(Box::new(mock.clone()))
+            .rate_limit(rate_limiter)
+            .build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        listener.register_handler(EmptyTestMessageHandler);
+
+        // when
+        let started_at = Instant::now();
+        listener.listen().unwrap();
+
+        // then 3 test messages with burst of 1 need at least 2 * 50ms
+        assert!(started_at.elapsed() >= Duration::from_millis(100));
+        assert_eq!(3, logger.lock().unwrap().get().len());
+    }
+
+    #[tokio::test]
+    async fn should_listener_async_throttle_all_messages() {
+        // given
+        let mock = MockClientAsync::new();
+        for i in 0..4 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            mock.inject(&test_msg, None);
+        }
+        mock.close();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(mock.clone()))
+            .rate_limit(RateLimiter::new().limit(NonZeroU32::new(20).unwrap(), 2))
+            .build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+
+        // when
+        let started_at = Instant::now();
+        listener.listen().await.unwrap();
+
+        // then 4 messages with burst of 2 need at least 2 * 50ms
+        assert!(started_at.elapsed() >= Duration::from_millis(100));
+        assert_eq!(4, logger.lock().await.get().len());
+    }
+
+    #[tokio::test]
+    async fn should_listener_async_handle_other_messages_while_throttled_message_waits() {
+        // given
+        let messages = vec![
+            TestMessage {
+                data: "0".to_string(),
+            }
+            .into(),
+            TestMessage {
+                data: "1".to_string(),
+            }
+            .into(),
+            WrongTestMessage {
+                data: "2".to_string(),
+            }
+            .into(),
+        ];
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let mut listener: ListenerAsync =
+            builder::pubsub_async(Box::new(ConcurrentMockClient { messages }))
+                .rate_limit(
+                    RateLimiter::new().limit_message::<TestMessage>(NonZeroU32::new(5).unwrap(), 1),
+                )
+                .build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        listener
+            .register_handler(WrongTestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+
+        // when
+        listener.listen().await.unwrap();
+
+        // then
+        assert_eq!(
+            &vec![
+                "msg: 0 headers: ".to_string(),
+                "wrong test 2".to_string(),
+                "msg: 1 headers: ".to_string(),
+            ],
+            logger.lock().await.get()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_publisher_async_throttle_sent_messages() {
+        // given
+        let mock = MockClientAsync::new();
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(mock.clone()))
+            .rate_limit(RateLimiter::new().limit(NonZeroU32::new(20).unwrap(), 1))
+            .build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let started_at = Instant::now();
+        for _ in 0..3 {
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+
+        // then
+        assert!(started_at.elapsed() >= Duration::from_millis(100));
+        assert_eq!(3, mock.published::<TestMessage>().len());
+    }
+
+    // Helpers
+    /// Delivers all messages at once, like the transports which handle messages concurrently.
+    struct ConcurrentMockClient {
+        messages: Vec<RawMessage>,
+    }
+
+    #[async_trait]
+    impl ClientAsync for ConcurrentMockClient {
+        async fn receiver(
+            &mut self,
+            recv_callback: Arc<ClientCallbackFnAsync>,
+        ) -> Result<(), ClientError> {
+            let handled = self.messages.iter().map(|msg| recv_callback(msg.clone()));
+            for result in futures_util::future::join_all(handled).await {
+                result?;
+            }
+            Ok(())
+        }
+
+        async fn send(&mut self, _msg: &RawMessage) -> Result<(), ClientError> {
+            Ok(())
+        }
+    }
+}
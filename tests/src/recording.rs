@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        fs::OpenOptions,
+        io::Write,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        recording::{
+            read_recording, Record, RecordingLayer, ReplayClient, ReplayClientAsync, ReplayTiming,
+        },
+        Client, ClientError, RawMessage,
+    };
+    use bus_rs_file::FileLogClient;
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_fail_to_create_recording_layer_when_file_cannot_be_opened() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+
+        // when
+        let result = RecordingLayer::new(dir.path().to_str().unwrap());
+
+        // then
+        assert!(matches!(result, Err(ClientError::IO(_))));
+    }
+
+    #[test]
+    fn should_replay_recorded_messages_into_listener() {
+        // given messages recorded by the publisher
+        let dir = tempfile::tempdir().unwrap();
+        let log_dir = dir.path().to_str().unwrap().to_string();
+        let recording = dir.path().join("orders.rec");
+        let recording = recording.to_str().unwrap();
+        let publisher: Publisher =
+            builder::pubsub(Box::new(FileLogClient::new(&log_dir, "orders".to_string())))
+                .add_layer(Box::new(RecordingLayer::new(recording).unwrap()))
+                .build();
+        for i in 0..3 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            let headers = HashMap::from([("tenant".to_string(), "acme".to_string())]);
+            publisher.publish(&test_msg, Some(headers)).unwrap();
+        }
+
+        // when
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let mut listener: Listener =
+            builder::pubsub(Box::new(ReplayClient::new(recording))).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        listener.listen().unwrap();
+
+        // then
+        assert_eq!(
+            vec![
+                "msg: 0 headers: tenant=acme",
+                "msg: 1 headers: tenant=acme",
+                "msg: 2 headers: tenant=acme"
+            ],
+            *logger.lock().unwrap().get()
+        );
+        let records = read_recording(recording).unwrap();
+        assert_eq!(3, records.len());
+        assert!(records.windows(2).all(|r| r[0].timestamp <= r[1].timestamp));
+    }
+
+    #[tokio::test]
+    async fn should_replay_with_original_timing_or_immediately() {
+        // given the recording with 300ms gap between messages
+        let dir = tempfile::tempdir().unwrap();
+        let recording = dir.path().join("orders.rec");
+        let recording = recording.to_str().unwrap();
+        write_records(recording, &[(1_000, "first"), (1_300, "second")]);
+
+        // when
+        let original =
+            replay(ReplayClientAsync::new(recording).timing(ReplayTiming::Original)).await;
+        let immediate = replay(ReplayClientAsync::new(recording)).await;
+
+        // then
+        assert_eq!(
+            vec!["msg: first headers: ", "msg: second headers: "],
+            original.0
+        );
+        assert!(original.1 >= Duration::from_millis(300));
+        assert_eq!(original.0, immediate.0);
+        assert!(immediate.1 < Duration::from_millis(300));
+    }
+
+    #[test]
+    fn should_ignore_incomplete_last_record() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let recording = dir.path().join("orders.rec");
+        let recording = recording.to_str().unwrap();
+        write_records(recording, &[(1_000, "first")]);
+        let mut file = OpenOptions::new().append(true).open(recording).unwrap();
+        file.write_all(br#"{"timestamp":1001,"mess"#).unwrap();
+
+        // when
+        let records = read_recording(recording).unwrap();
+        let send_result = ReplayClient::new(recording).send(&records[0].message);
+
+        // then
+        assert_eq!(1, records.len());
+        assert_eq!(r#"{"data":"first"}"#, records[0].message.payload);
+        assert!(send_result.is_err());
+    }
+
+    // Helpers
+
+    fn write_records(path: &str, records: &[(u64, &str)]) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        for (timestamp, data) in records {
+            let record = Record {
+                timestamp: *timestamp,
+                message: RawMessage {
+                    msg_type: "TestMessage".to_string(),
+                    headers: HashMap::new(),
+                    payload: format!(r#"{{"data":"{}"}}"#, data),
+                },
+            };
+            writeln!(file, "{}", serde_json::to_string(&record).unwrap()).unwrap();
+        }
+    }
+
+    async fn replay(client: ReplayClientAsync) -> (Vec<String>, Duration) {
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        let started = Instant::now();
+        listener.listen().await.unwrap();
+        let elapsed = started.elapsed();
+        let messages = logger.lock().await.get().clone();
+        (messages, elapsed)
+    }
+}
@@ -9,11 +9,13 @@ mod tests {
 
     use bus_rs::{
         builder::{self, Builder},
+        deduplication::DeduplicationStore,
         listener::Listener,
+        message_handler::MessageHandler,
         publisher::Publisher,
-        ClientError, RawMessage,
+        Client, ClientError, RawMessage,
     };
-    use bus_rs_redis::RedisClient;
+    use bus_rs_redis::{RedisClient, RedisDeduplicationStore, RedisQueueClient, RedisScheduler};
     use redis::Commands;
     use testcontainers::{core::WaitFor, *};
 
@@ -175,7 +177,7 @@ mod tests {
 
         // when
         let headers = HashMap::from([("trace-id".to_owned(), "trace123".to_owned())]);
-        publisher.publish(&test_msg, Some(headers));
+        publisher.publish(&test_msg, Some(headers)).unwrap();
 
         // then
         sleep(Duration::from_millis(200));
@@ -229,7 +231,7 @@ mod tests {
         expected_msg.headers = headers.clone();
 
         // when
-        publisher.publish(&test_msg, Some(headers));
+        publisher.publish(&test_msg, Some(headers)).unwrap();
 
         // then
         sleep(Duration::from_millis(200));
@@ -254,6 +256,249 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_redis_deduplication_store_remember_ids_until_expiration() {
+        // given
+        let docker_client = clients::Cli::default();
+        let (_node, url) = prepare_redis_container(&docker_client);
+        let store = RedisDeduplicationStore::new(url.as_ref(), "processed", Duration::from_secs(1));
+
+        // when
+        let claimed = store.claim("id1").unwrap();
+
+        // then
+        assert!(claimed);
+        assert!(!store.claim("id1").unwrap());
+        assert!(store.claim("id2").unwrap());
+
+        // and
+        sleep(Duration::from_millis(1100));
+        assert!(store.claim("id1").unwrap());
+    }
+
+    #[test]
+    fn should_redis_scheduler_publish_message_when_it_is_due() {
+        // given test receiver
+        let docker_client = clients::Cli::default();
+        let (_node, url) = prepare_redis_container(&docker_client);
+        let client = redis::Client::open(url.as_ref()).unwrap();
+        let mut connection = client.clone().get_connection().unwrap();
+        let mut check_connection = client.get_connection().unwrap();
+
+        let raw_messages: Arc<Mutex<Vec<RawMessage>>> = Arc::new(Mutex::new(vec![]));
+        let caught_raw_messages = raw_messages.clone();
+        spawn(move || {
+            let mut pubsub = connection.as_pubsub();
+            pubsub.subscribe("test_channel").unwrap();
+
+            loop {
+                let msg = pubsub.get_message().unwrap();
+                caught_raw_messages
+                    .lock()
+                    .unwrap()
+                    .push(RawMessage::from(msg.get_payload::<String>().unwrap()));
+            }
+        });
+        sleep(Duration::from_millis(200));
+
+        // given publisher with scheduler and scheduler task
+        let publisher: Publisher = builder::pubsub(Box::new(RedisClient::new(
+            url.as_ref(),
+            "test_channel".to_string(),
+        )))
+        .scheduler(Box::new(RedisScheduler::new(
+            url.as_ref(),
+            "test_channel".to_string(),
+        )))
+        .build();
+        let mut scheduler_task = RedisScheduler::new(url.as_ref(), "test_channel".to_string());
+
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        publisher
+            .publish_after(&test_msg, None, Duration::from_millis(300))
+            .unwrap();
+        publisher
+            .publish_after(&test_msg, None, Duration::from_millis(300))
+            .unwrap();
+
+        // then messages are parked
+        assert_eq!(0, scheduler_task.dispatch_due().unwrap());
+        let parked: usize = check_connection.zcard("test_channel:scheduled").unwrap();
+        assert_eq!(2, parked);
+
+        // and published when due
+        sleep(Duration::from_millis(350));
+        assert_eq!(2, scheduler_task.dispatch_due().unwrap());
+        sleep(Duration::from_millis(200));
+
+        let raw_messages = raw_messages.lock().unwrap();
+        assert_eq!(2, raw_messages.len());
+        assert_eq!(r#"{"data":"test_data"}"#, raw_messages[0].payload);
+        let parked: usize = check_connection.zcard("test_channel:scheduled").unwrap();
+        assert_eq!(0, parked);
+    }
+
+    #[test]
+    fn should_redis_queue_deliver_every_message_to_exactly_one_listener() {
+        // given two listeners
+        let docker_client = clients::Cli::default();
+        let (_node, url) = prepare_redis_container(&docker_client);
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        for _ in 0..2 {
+            let url = url.clone();
+            let logger = logger.clone();
+            spawn(move || {
+                let client = RedisQueueClient::new(url.as_ref(), "test_queue".to_string());
+                let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+                listener.register_handler(TestMessageHandler { logger });
+                listener.listen().unwrap();
+            });
+        }
+
+        // given publisher
+        let client = RedisQueueClient::new(url.as_ref(), "test_queue".to_string());
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+
+        // when
+        for i in 0..10 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).unwrap();
+        }
+
+        // then
+        sleep(Duration::from_millis(500));
+        let logger = logger.lock().unwrap();
+        let mut handled = logger.get().clone();
+        handled.sort();
+        handled.dedup();
+        assert_eq!(10, logger.get().len());
+        assert_eq!(10, handled.len());
+    }
+
+    #[test]
+    fn should_redis_queue_recover_message_of_crashed_worker() {
+        // given
+        let docker_client = clients::Cli::default();
+        let (_node, url) = prepare_redis_container(&docker_client);
+        let mut connection = redis::Client::open(url.as_ref())
+            .unwrap()
+            .get_connection()
+            .unwrap();
+        let mut queue_client = RedisQueueClient::new(url.as_ref(), "test_queue".to_string())
+            .visibility_timeout(Duration::from_secs(1));
+        let raw_msg: RawMessage = TestMessage {
+            data: "test_data".to_string(),
+        }
+        .into();
+        queue_client.send(&raw_msg).unwrap();
+
+        // when worker takes the message and crashes before it's acknowledged
+        let _: String = connection
+            .rpoplpush("test_queue", "test_queue:processing")
+            .unwrap();
+
+        // then message is leased first
+        assert_eq!(0, queue_client.recover().unwrap());
+        let queued: usize = connection.llen("test_queue").unwrap();
+        assert_eq!(0, queued);
+
+        // and moved back to the queue when the lease expires
+        sleep(Duration::from_millis(1100));
+        assert_eq!(1, queue_client.recover().unwrap());
+        let queued: Vec<String> = connection.lrange("test_queue", 0, -1).unwrap();
+        assert_eq!(1, queued.len());
+        assert!(queued[0].ends_with(r#""payload":"{\"data\":\"test_data\"}"}"#));
+        let processing: usize = connection.llen("test_queue:processing").unwrap();
+        assert_eq!(0, processing);
+    }
+
+    #[test]
+    fn should_redis_queue_report_and_remove_undecodable_message() {
+        // given
+        let docker_client = clients::Cli::default();
+        let (_node, url) = prepare_redis_container(&docker_client);
+        let mut connection = redis::Client::open(url.as_ref())
+            .unwrap()
+            .get_connection()
+            .unwrap();
+        let _: usize = connection.lpush("test_queue", "1:not a message").unwrap();
+        let reported = Arc::new(Mutex::new(vec![]));
+        let reported_ref = reported.clone();
+        let listener_url = url.clone();
+        spawn(move || {
+            let client = RedisQueueClient::new(listener_url.as_ref(), "test_queue".to_string());
+            let mut listener: Listener = builder::pubsub(Box::new(client))
+                .on_error(move |raw_msg, _| {
+                    reported_ref.lock().unwrap().push(raw_msg.payload.clone());
+                })
+                .build();
+            listener.register_handler(TestMessageHandler {
+                logger: Arc::new(Mutex::new(TestLogger::new())),
+            });
+            listener.listen().unwrap();
+        });
+
+        // when
+        sleep(Duration::from_millis(500));
+
+        // then
+        assert_eq!(vec!["1:not a message"], *reported.lock().unwrap());
+        let queued: usize = connection.llen("test_queue").unwrap();
+        assert_eq!(0, queued);
+        let processing: usize = connection.llen("test_queue:processing").unwrap();
+        assert_eq!(0, processing);
+    }
+
+    #[test]
+    fn should_redis_queue_renew_lease_of_message_which_is_handled() {
+        // given slow listener
+        let docker_client = clients::Cli::default();
+        let (_node, url) = prepare_redis_container(&docker_client);
+        let mut connection = redis::Client::open(url.as_ref())
+            .unwrap()
+            .get_connection()
+            .unwrap();
+        let listener_url = url.clone();
+        spawn(move || {
+            let client = RedisQueueClient::new(listener_url.as_ref(), "test_queue".to_string())
+                .visibility_timeout(Duration::from_secs(1));
+            let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+            listener.register_handler(SlowTestMessageHandler);
+            listener.listen().unwrap();
+        });
+        let mut queue_client = RedisQueueClient::new(url.as_ref(), "test_queue".to_string())
+            .visibility_timeout(Duration::from_secs(1));
+        let raw_msg: RawMessage = TestMessage {
+            data: "test_data".to_string(),
+        }
+        .into();
+
+        // when handling takes longer than the visibility timeout
+        queue_client.send(&raw_msg).unwrap();
+        sleep(Duration::from_millis(2000));
+
+        // then message isn't moved back to the queue
+        assert_eq!(0, queue_client.recover().unwrap());
+        let queued: usize = connection.llen("test_queue").unwrap();
+        assert_eq!(0, queued);
+    }
+
+    // Helpers
+
+    struct SlowTestMessageHandler;
+
+    impl MessageHandler<TestMessage> for SlowTestMessageHandler {
+        fn handle(&mut self, _msg: TestMessage, _headers: Option<HashMap<String, String>>) {
+            sleep(Duration::from_millis(3000));
+        }
+    }
+
     fn prepare_redis_container<'a>(docker: &'a clients::Cli) -> (Container<'a, Redis>, String) {
         let node = docker.run(Redis::default());
         let host_port = node.get_host_port_ipv4(6379);
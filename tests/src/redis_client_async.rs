@@ -178,7 +178,10 @@ mod tests {
 
         // when
         let headers = HashMap::from([("trace-id".to_owned(), "trace123".to_owned())]);
-        publisher.publish(&test_msg, Some(headers.clone())).await;
+        publisher
+            .publish(&test_msg, Some(headers.clone()))
+            .await
+            .unwrap();
 
         // then
         tokio::time::sleep(Duration::from_millis(200)).await;
@@ -234,7 +237,7 @@ mod tests {
         expected_msg.headers = headers.clone();
 
         // when
-        publisher.publish(&test_msg, Some(headers)).await;
+        publisher.publish(&test_msg, Some(headers)).await.unwrap();
 
         // then
         tokio::time::sleep(Duration::from_millis(200)).await;
@@ -259,6 +262,54 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn should_publisher_with_redis_client_async_send_batch_in_pipeline() {
+        // given test receiver
+        let docker_client = clients::Cli::default();
+        let (_node, url) = prepare_redis_container(&docker_client);
+        let client = redis::Client::open(url.as_ref()).unwrap();
+        let connection = client.clone().get_async_connection().await.unwrap();
+
+        // given publisher
+        let redis_client =
+            RedisClientAsync::new_sender(url.as_ref(), "test_channel".to_string()).await;
+        let client = Box::new(redis_client);
+        let publisher: PublisherAsync = builder::pubsub_async(client).build();
+
+        let raw_messages: Arc<Mutex<Vec<bus_rs::RawMessage>>> = Arc::new(Mutex::new(vec![]));
+        let caught_raw_messages = raw_messages.clone();
+        tokio::spawn(async move {
+            let mut pubsub = connection.into_pubsub();
+            pubsub.subscribe("test_channel").await.unwrap();
+            let mut pubsub_stream = pubsub.on_message();
+
+            loop {
+                let msg = pubsub_stream.next().await.unwrap();
+                let raw_message = bus_rs::RawMessage::from(msg.get_payload::<String>().unwrap());
+                caught_raw_messages.lock().await.push(raw_message);
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let test_msgs: Vec<TestMessage> = (0..3)
+            .map(|i| TestMessage {
+                data: format!("test_data_{}", i),
+            })
+            .collect();
+
+        // when
+        let results = publisher.publish_batch(&test_msgs, None).await;
+
+        // then
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert!(results.iter().all(|r| r.is_ok()));
+        let raw_messages = raw_messages.lock().await;
+        assert_eq!(3, raw_messages.len());
+        assert_eq!(r#"{"data":"test_data_0"}"#, raw_messages[0].payload);
+        assert_eq!(r#"{"data":"test_data_2"}"#, raw_messages[2].payload);
+    }
+
     fn prepare_redis_container<'a>(docker: &'a clients::Cli) -> (Container<'a, Redis>, String) {
         let node = docker.run(Redis::default());
         let host_port = node.get_host_port_ipv4(6379);
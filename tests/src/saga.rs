@@ -0,0 +1,426 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bus_rs::{
+        builder::{self, Builder},
+        listener_async::ListenerAsync,
+        publisher_async::PublisherAsync,
+        saga::{
+            InMemorySagaRepository, Saga, SagaContext, SagaHandler, SagaManager, SagaRecord,
+            SagaRepository,
+        },
+        scheduler::InMemoryScheduler,
+        ClientAsync, ClientCallbackFnAsync, ClientError, RawMessage,
+    };
+    use bus_rs_macros::message;
+    use bus_rs_sqlite::SqliteSagaRepository;
+    use serde::{Deserialize, Serialize};
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn should_saga_correlate_messages_and_send_command_when_order_is_paid() {
+        // given
+        let repository = Arc::new(InMemorySagaRepository::new());
+        let scheduler = InMemoryScheduler::new();
+        let (publisher, sent) = publisher(&scheduler);
+        let mut listener = listener(vec![
+            OrderPlaced { order_id: 1 }.into(),
+            OrderPlaced { order_id: 2 }.into(),
+            PaymentReceived { order_id: 1 }.into(),
+        ]);
+        register_saga(&mut listener, repository.clone(), publisher).await;
+
+        // when
+        listener.listen().await.unwrap();
+
+        // then
+        let sent = sent.lock().unwrap().clone();
+        assert_eq!(1, sent.len());
+        assert_eq!("ShipOrder", sent[0].msg_type);
+        assert_eq!(r#"{"order_id":1}"#, sent[0].payload);
+
+        // and timeouts are scheduled
+        assert_eq!(2, scheduler.pending());
+
+        // and completed saga is removed
+        assert!(repository.load("OrderSaga", "1").await.unwrap().is_none());
+        let state = repository.load("OrderSaga", "2").await.unwrap().unwrap();
+        assert_eq!(r#"{"placed":true}"#, state.state);
+        assert!(state.commands.is_empty());
+        assert_eq!(2, state.version);
+    }
+
+    #[tokio::test]
+    async fn should_saga_cancel_only_not_paid_order_on_timeout() {
+        // given
+        let repository = Arc::new(InMemorySagaRepository::new());
+        let scheduler = InMemoryScheduler::new();
+        let (publisher, sent) = publisher(&scheduler);
+        let mut listener = listener(vec![
+            OrderPlaced { order_id: 1 }.into(),
+            OrderPlaced { order_id: 2 }.into(),
+            PaymentReceived { order_id: 1 }.into(),
+            PaymentTimeout { order_id: 1 }.into(),
+            PaymentTimeout { order_id: 2 }.into(),
+        ]);
+        register_saga(&mut listener, repository.clone(), publisher).await;
+
+        // when
+        listener.listen().await.unwrap();
+
+        // then
+        let sent = sent.lock().unwrap().clone();
+        assert_eq!(2, sent.len());
+        assert_eq!("ShipOrder", sent[0].msg_type);
+        assert_eq!("CancelOrder", sent[1].msg_type);
+        assert_eq!(r#"{"order_id":2}"#, sent[1].payload);
+        assert!(repository.load("OrderSaga", "1").await.unwrap().is_none());
+        assert!(repository.load("OrderSaga", "2").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_repository_reject_state_saved_with_outdated_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sagas.db");
+        for repository in repositories(path.to_str().unwrap()) {
+            reject_state_saved_with_outdated_version(repository).await;
+        }
+    }
+
+    async fn reject_state_saved_with_outdated_version(repository: Arc<dyn SagaRepository>) {
+        // given
+        repository
+            .save("OrderSaga", "1", record(), None)
+            .await
+            .unwrap();
+
+        // when
+        let result = repository.save("OrderSaga", "1", record(), None).await;
+
+        // then
+        assert!(matches!(result, Err(ClientError::General(_))));
+        assert!(repository
+            .save("OrderSaga", "1", record(), Some(1))
+            .await
+            .is_ok());
+        assert_eq!(
+            2,
+            repository
+                .load("OrderSaga", "1")
+                .await
+                .unwrap()
+                .unwrap()
+                .version
+        );
+    }
+
+    #[tokio::test]
+    async fn should_repository_reject_delete_with_outdated_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sagas.db");
+        for repository in repositories(path.to_str().unwrap()) {
+            reject_delete_with_outdated_version(repository).await;
+        }
+    }
+
+    async fn reject_delete_with_outdated_version(repository: Arc<dyn SagaRepository>) {
+        // given
+        repository
+            .save("OrderSaga", "1", record(), None)
+            .await
+            .unwrap();
+        repository
+            .save("OrderSaga", "1", record(), Some(1))
+            .await
+            .unwrap();
+
+        // when
+        let result = repository.delete("OrderSaga", "1", Some(1)).await;
+
+        // then
+        assert!(matches!(result, Err(ClientError::General(_))));
+        assert!(repository.load("OrderSaga", "1").await.unwrap().is_some());
+        assert!(repository.delete("OrderSaga", "1", Some(2)).await.is_ok());
+        assert!(repository.load("OrderSaga", "1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_sqlite_repository_keep_saga_state_across_managers() {
+        // given state of the order stored by the first instance of the service
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sagas.db");
+        let scheduler = InMemoryScheduler::new();
+        let (publisher, sent) = publisher(&scheduler);
+        let mut first_listener = listener(vec![OrderPlaced { order_id: 1 }.into()]);
+        let repository = Arc::new(SqliteSagaRepository::new(path.to_str().unwrap()));
+        register_saga(&mut first_listener, repository, publisher.clone()).await;
+        first_listener.listen().await.unwrap();
+
+        // when the timeout is handled by the other instance
+        let mut other_listener = listener(vec![PaymentTimeout { order_id: 1 }.into()]);
+        let repository = Arc::new(SqliteSagaRepository::new(path.to_str().unwrap()));
+        register_saga(&mut other_listener, repository.clone(), publisher).await;
+        other_listener.listen().await.unwrap();
+
+        // then
+        let sent = sent.lock().unwrap().clone();
+        assert_eq!(1, sent.len());
+        assert_eq!("CancelOrder", sent[0].msg_type);
+        assert!(repository.load("OrderSaga", "1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_keep_commands_with_state_until_they_are_published() {
+        // given publisher which fails to send the first command
+        let repository = Arc::new(InMemorySagaRepository::new());
+        let scheduler = InMemoryScheduler::new();
+        let client = MockClient::new(vec![]);
+        let sent = client.messages.clone();
+        *client.failures.lock().unwrap() = 1;
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client))
+            .scheduler_async(Box::new(scheduler.clone()))
+            .build();
+        let publisher = Arc::new(publisher);
+        let mut first_listener = listener(vec![
+            OrderPlaced { order_id: 1 }.into(),
+            PaymentReceived { order_id: 1 }.into(),
+        ]);
+        register_saga(&mut first_listener, repository.clone(), publisher.clone()).await;
+        first_listener.listen().await.unwrap();
+        let record = repository.load("OrderSaga", "1").await.unwrap().unwrap();
+        assert!(record.completed);
+        assert_eq!("ShipOrder", record.commands[0].message.msg_type);
+
+        // when the next message of the order is handled
+        let mut next_listener = listener(vec![PaymentTimeout { order_id: 1 }.into()]);
+        register_saga(&mut next_listener, repository.clone(), publisher).await;
+        next_listener.listen().await.unwrap();
+
+        // then the stored command is published and the completed saga is removed
+        let msg_types: Vec<String> = sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|msg| msg.msg_type.clone())
+            .collect();
+        assert_eq!(vec!["ShipOrder"], msg_types);
+        assert!(repository.load("OrderSaga", "1").await.unwrap().is_none());
+    }
+
+    fn repositories(sqlite_path: &str) -> Vec<Arc<dyn SagaRepository>> {
+        vec![
+            Arc::new(InMemorySagaRepository::new()),
+            Arc::new(SqliteSagaRepository::new(sqlite_path)),
+        ]
+    }
+
+    fn record() -> SagaRecord {
+        SagaRecord {
+            state: "{}".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn publisher(
+        scheduler: &InMemoryScheduler,
+    ) -> (Arc<PublisherAsync>, Arc<Mutex<Vec<RawMessage>>>) {
+        let client = MockClient::new(vec![]);
+        let sent = client.messages.clone();
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client))
+            .scheduler_async(Box::new(scheduler.clone()))
+            .build();
+        (Arc::new(publisher), sent)
+    }
+
+    fn listener(messages: Vec<RawMessage>) -> ListenerAsync {
+        builder::pubsub_async(Box::new(MockClient::new(messages))).build()
+    }
+
+    async fn register_saga(
+        listener: &mut ListenerAsync,
+        repository: Arc<dyn SagaRepository>,
+        publisher: Arc<PublisherAsync>,
+    ) {
+        let sagas = SagaManager::new(OrderSaga, repository, publisher);
+        listener
+            .register_handler(sagas.handler::<OrderPlaced>())
+            .await;
+        listener
+            .register_handler(sagas.handler::<PaymentReceived>())
+            .await;
+        listener
+            .register_handler(sagas.handler::<PaymentTimeout>())
+            .await;
+    }
+
+    // Helpers
+    #[message]
+    #[derive(Serialize, Deserialize)]
+    struct OrderPlaced {
+        order_id: u32,
+    }
+
+    #[message]
+    #[derive(Serialize, Deserialize)]
+    struct PaymentReceived {
+        order_id: u32,
+    }
+
+    #[message]
+    #[derive(Serialize, Deserialize)]
+    struct PaymentTimeout {
+        order_id: u32,
+    }
+
+    #[message]
+    #[derive(Serialize, Deserialize)]
+    struct ShipOrder {
+        order_id: u32,
+    }
+
+    #[message]
+    #[derive(Serialize, Deserialize)]
+    struct CancelOrder {
+        order_id: u32,
+    }
+
+    #[derive(Default, Serialize, Deserialize)]
+    struct OrderState {
+        placed: bool,
+    }
+
+    struct OrderSaga;
+
+    impl Saga for OrderSaga {
+        type State = OrderState;
+
+        fn name() -> &'static str {
+            "OrderSaga"
+        }
+    }
+
+    #[async_trait]
+    impl SagaHandler<OrderPlaced> for OrderSaga {
+        fn correlation_key(
+            &self,
+            msg: &OrderPlaced,
+            _headers: &Option<HashMap<String, String>>,
+        ) -> String {
+            msg.order_id.to_string()
+        }
+
+        async fn handle(
+            &mut self,
+            msg: OrderPlaced,
+            _headers: Option<HashMap<String, String>>,
+            context: &mut SagaContext<OrderState>,
+        ) {
+            context.state.placed = true;
+            context.schedule_timeout(
+                &PaymentTimeout {
+                    order_id: msg.order_id,
+                },
+                None,
+                Duration::from_secs(60),
+            );
+        }
+    }
+
+    #[async_trait]
+    impl SagaHandler<PaymentReceived> for OrderSaga {
+        fn correlation_key(
+            &self,
+            msg: &PaymentReceived,
+            _headers: &Option<HashMap<String, String>>,
+        ) -> String {
+            msg.order_id.to_string()
+        }
+
+        async fn handle(
+            &mut self,
+            msg: PaymentReceived,
+            _headers: Option<HashMap<String, String>>,
+            context: &mut SagaContext<OrderState>,
+        ) {
+            context.send(
+                &ShipOrder {
+                    order_id: msg.order_id,
+                },
+                None,
+            );
+            context.complete();
+        }
+    }
+
+    #[async_trait]
+    impl SagaHandler<PaymentTimeout> for OrderSaga {
+        fn correlation_key(
+            &self,
+            msg: &PaymentTimeout,
+            _headers: &Option<HashMap<String, String>>,
+        ) -> String {
+            msg.order_id.to_string()
+        }
+
+        async fn handle(
+            &mut self,
+            msg: PaymentTimeout,
+            _headers: Option<HashMap<String, String>>,
+            context: &mut SagaContext<OrderState>,
+        ) {
+            // saga of the paid order is already completed
+            if !context.is_new() {
+                context.send(
+                    &CancelOrder {
+                        order_id: msg.order_id,
+                    },
+                    None,
+                );
+            }
+            context.complete();
+        }
+    }
+
+    struct MockClient {
+        messages: Arc<Mutex<Vec<RawMessage>>>,
+        failures: Arc<Mutex<u32>>,
+    }
+
+    impl MockClient {
+        fn new(messages: Vec<RawMessage>) -> Self {
+            MockClient {
+                messages: Arc::new(Mutex::new(messages)),
+                failures: Arc::new(Mutex::new(0)),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ClientAsync for MockClient {
+        async fn receiver(
+            &mut self,
+            recv_callback: Arc<ClientCallbackFnAsync>,
+        ) -> Result<(), ClientError> {
+            let messages = self.messages.lock().unwrap().clone();
+            for msg in messages {
+                recv_callback(msg).await?;
+            }
+            Ok(())
+        }
+
+        async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(ClientError::IO("connection refused".to_string()));
+            }
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+}
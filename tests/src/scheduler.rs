@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bus_rs::{
+        builder::{self, Builder},
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+        scheduler::InMemoryScheduler,
+        signing::{SigningLayer, VerificationLayer},
+        Client, ClientAsync, ClientCallbackFnAsync, ClientError, LayerError, PubSubLayer,
+        RawMessage,
+    };
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        thread::sleep,
+        time::{Duration, SystemTime},
+    };
+
+    use crate::{TestLayer, TestLogger, TestMessage};
+
+    #[test]
+    fn should_publisher_send_message_by_scheduler_when_it_is_due() {
+        // given
+        let scheduler = InMemoryScheduler::new();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let publisher: Publisher = builder::pubsub(Box::new(MockClient::new()))
+            .add_layer(Box::new(TestLayer {
+                logger: logger.clone(),
+            }))
+            .scheduler(Box::new(scheduler.clone()))
+            .build();
+        let mut scheduler_client = MockClient::new();
+        let sent = scheduler_client.messages.clone();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        publisher
+            .publish_after(
+                &test_msg,
+                Some(HashMap::from([("trace-id".to_string(), "123".to_string())])),
+                Duration::from_millis(100),
+            )
+            .unwrap();
+
+        // then message is scheduled and layers are called
+        assert_eq!(1, scheduler.pending());
+        assert_eq!(2, logger.lock().unwrap().get().len());
+        assert_eq!(0, scheduler.dispatch_due(&mut scheduler_client).unwrap());
+
+        // and sent when it's due
+        sleep(Duration::from_millis(110));
+        assert_eq!(1, scheduler.dispatch_due(&mut scheduler_client).unwrap());
+        assert_eq!(0, scheduler.pending());
+        let sent = sent.lock().unwrap();
+        assert_eq!(r#"{"data":"test_data"}"#, sent[0].payload);
+        assert_eq!("123", sent[0].headers["trace-id"]);
+    }
+
+    #[tokio::test]
+    async fn should_publisher_async_dispatch_only_due_messages_in_order() {
+        // given
+        let scheduler = InMemoryScheduler::new();
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(MockClient::new()))
+            .scheduler_async(Box::new(scheduler.clone()))
+            .build();
+        let mut scheduler_client = MockClient::new();
+        let sent = scheduler_client.messages.clone();
+        let now = SystemTime::now();
+
+        // when
+        for (data, when) in [
+            ("later", now + Duration::from_secs(60)),
+            ("second", now - Duration::from_secs(1)),
+            ("first", now - Duration::from_secs(2)),
+        ] {
+            let msg = TestMessage {
+                data: data.to_string(),
+            };
+            publisher.publish_at(&msg, None, when).await.unwrap();
+        }
+        let dispatched = scheduler
+            .dispatch_due_async(&mut scheduler_client)
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(2, dispatched);
+        assert_eq!(1, scheduler.pending());
+        let sent = sent.lock().unwrap();
+        assert_eq!(r#"{"data":"first"}"#, sent[0].payload);
+        assert_eq!(r#"{"data":"second"}"#, sent[1].payload);
+    }
+
+    #[test]
+    fn should_publish_at_fail_when_scheduler_is_not_configured() {
+        // given
+        let client = MockClient::new();
+        let sent = client.messages.clone();
+        let publisher: Publisher = builder::pubsub(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+
+        // when
+        let result = publisher.publish_after(&test_msg, None, Duration::from_secs(1));
+
+        // then
+        assert!(matches!(result, Err(ClientError::General(_))));
+        assert!(sent.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_keep_due_messages_scheduled_when_sending_fails() {
+        // given
+        let scheduler = InMemoryScheduler::new();
+        let publisher: Publisher = builder::pubsub(Box::new(MockClient::new()))
+            .scheduler(Box::new(scheduler.clone()))
+            .build();
+        let now = SystemTime::now();
+        for (data, when) in [
+            ("first", now - Duration::from_secs(2)),
+            ("second", now - Duration::from_secs(1)),
+        ] {
+            let msg = TestMessage {
+                data: data.to_string(),
+            };
+            publisher.publish_at(&msg, None, when).unwrap();
+        }
+
+        // when
+        let result = scheduler.dispatch_due(&mut MockClient::failing());
+
+        // then nothing is lost and messages are sent by the next dispatch in order
+        assert!(result.is_err());
+        assert_eq!(2, scheduler.pending());
+        let mut scheduler_client = MockClient::new();
+        let sent = scheduler_client.messages.clone();
+        assert_eq!(2, scheduler.dispatch_due(&mut scheduler_client).unwrap());
+        let sent = sent.lock().unwrap();
+        assert_eq!(r#"{"data":"first"}"#, sent[0].payload);
+        assert_eq!(r#"{"data":"second"}"#, sent[1].payload);
+    }
+
+    #[test]
+    fn should_signed_message_be_stale_when_delay_exceeds_max_age() {
+        // given the message signed when it's scheduled
+        let key = b"secret";
+        let scheduler = InMemoryScheduler::new();
+        let publisher: Publisher = builder::pubsub(Box::new(MockClient::new()))
+            .add_layer(Box::new(SigningLayer::new(key)))
+            .scheduler(Box::new(scheduler.clone()))
+            .build();
+        let test_msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+        publisher
+            .publish_after(&test_msg, None, Duration::from_millis(100))
+            .unwrap();
+
+        // when
+        sleep(Duration::from_millis(150));
+        let mut scheduler_client = MockClient::new();
+        let sent = scheduler_client.messages.clone();
+        scheduler.dispatch_due(&mut scheduler_client).unwrap();
+
+        // then max_age shorter than the delay rejects it
+        let mut short = sent.lock().unwrap()[0].clone();
+        let mut long = short.clone();
+        let result = VerificationLayer::new(key, Duration::from_millis(50)).before(&mut short);
+        assert!(matches!(result, Err(LayerError::Rejected(reason)) if reason.starts_with("stale")));
+        assert!(VerificationLayer::new(key, Duration::from_secs(1))
+            .before(&mut long)
+            .is_ok());
+    }
+
+    // Helpers
+    struct MockClient {
+        messages: Arc<Mutex<Vec<RawMessage>>>,
+        failing: bool,
+    }
+
+    impl MockClient {
+        fn new() -> Self {
+            MockClient {
+                messages: Arc::new(Mutex::new(vec![])),
+                failing: false,
+            }
+        }
+
+        fn failing() -> Self {
+            MockClient {
+                messages: Arc::new(Mutex::new(vec![])),
+                failing: true,
+            }
+        }
+    }
+
+    impl Client for MockClient {
+        fn receiver(
+            &mut self,
+            _recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+        ) -> Result<(), ClientError> {
+            todo!("not implemented");
+        }
+
+        fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            if self.failing {
+                return Err(ClientError::IO("connection refused".to_string()));
+            }
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ClientAsync for MockClient {
+        async fn receiver(
+            &mut self,
+            _recv_callback: Arc<ClientCallbackFnAsync>,
+        ) -> Result<(), ClientError> {
+            todo!("not implemented");
+        }
+
+        async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+}
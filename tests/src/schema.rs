@@ -0,0 +1,222 @@
+#[cfg(test)]
+mod tests {
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        message_handler::MessageHandler,
+        schema::{SchemaRegistry, SchemaValidationLayer},
+        Client, ClientError, LayerError, MessageTypeName, RawMessage,
+    };
+    use bus_rs_macros::message;
+    use serde::{Deserialize, Serialize};
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::{TestLogger, TestMessageHandler};
+
+    #[message(schema)]
+    #[derive(Deserialize, Serialize)]
+    struct OrderPlacedMessage {
+        order_id: String,
+        quantity: u32,
+    }
+
+    struct OrderPlacedMessageHandler {
+        logger: Arc<Mutex<TestLogger>>,
+    }
+
+    impl MessageHandler<OrderPlacedMessage> for OrderPlacedMessageHandler {
+        fn handle(&mut self, msg: OrderPlacedMessage, _headers: Option<HashMap<String, String>>) {
+            let mut l = self.logger.lock().unwrap();
+            l.info(format!("order: {} x{}", msg.order_id, msg.quantity));
+        }
+    }
+
+    #[test]
+    fn should_listener_expose_schemas_of_registered_messages() {
+        // given
+        let client = Box::new(MockClient::new(vec![]));
+        let mut listener: Listener = builder::pubsub(client).build();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+
+        // when
+        listener.register_handler(OrderPlacedMessageHandler {
+            logger: logger.clone(),
+        });
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+
+        // then
+        let schemas = listener.schemas();
+        assert_eq!(1, schemas.len());
+        assert_eq!(
+            OrderPlacedMessage::schema().unwrap(),
+            schemas["OrderPlacedMessage"]
+        );
+        assert_eq!(
+            "integer",
+            schemas["OrderPlacedMessage"]["properties"]["quantity"]["type"]
+        );
+    }
+
+    #[test]
+    fn should_reject_messages_which_dont_match_registered_schema() {
+        // given
+        let registry = SchemaRegistry::new();
+        registry.register::<OrderPlacedMessage>().unwrap();
+
+        let client = MockClient::new(vec![
+            raw_message(r#"{ "order_id": "o1", "quantity": 2 }"#),
+            raw_message(r#"{ "order_id": "o2", "quantity": "two" }"#),
+            raw_message(r#"{ "quantity": 3 }"#),
+            raw_message("not a json"),
+        ]);
+        let results = client.results.clone();
+        let mut listener: Listener = builder::pubsub(Box::new(client))
+            .add_layer(Box::new(SchemaValidationLayer::new(registry.clone())))
+            .build();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        listener.register_handler(OrderPlacedMessageHandler {
+            logger: logger.clone(),
+        });
+
+        // when
+        listener.listen().unwrap();
+
+        // then
+        let logger = logger.lock().unwrap();
+        assert_eq!(vec!["order: o1 x2".to_string()], *logger.get());
+
+        let results = results.lock().unwrap();
+        assert!(results[0].is_ok());
+        assert!(
+            matches!(&results[1], Err(ClientError::Layer(LayerError::Invalid(e))) if e.len() == 1 && e[0].starts_with("/quantity: "))
+        );
+        assert!(
+            matches!(&results[2], Err(ClientError::Layer(LayerError::Invalid(e))) if e[0].contains("order_id"))
+        );
+        assert!(matches!(
+            &results[3],
+            Err(ClientError::Layer(LayerError::Invalid(_)))
+        ));
+        assert_eq!(
+            listener.schemas()["OrderPlacedMessage"],
+            registry.schemas()["OrderPlacedMessage"]
+        );
+    }
+
+    #[test]
+    fn should_validate_schemas_of_messages_registered_by_listener() {
+        // given
+        let client = MockClient::new(vec![
+            raw_message(r#"{ "order_id": "o1", "quantity": 2 }"#),
+            raw_message(r#"{ "order_id": "o2", "quantity": "two" }"#),
+        ]);
+        let results = client.results.clone();
+        let mut listener: Listener = builder::pubsub(Box::new(client)).validate_schemas().build();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        listener.register_handler(OrderPlacedMessageHandler {
+            logger: logger.clone(),
+        });
+
+        // when
+        listener.listen().unwrap();
+
+        // then
+        assert_eq!(
+            vec!["order: o1 x2".to_string()],
+            *logger.lock().unwrap().get()
+        );
+        let results = results.lock().unwrap();
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            &results[1],
+            Err(ClientError::Layer(LayerError::Invalid(_)))
+        ));
+    }
+
+    #[test]
+    fn should_report_schema_which_cant_be_compiled() {
+        // given
+        let registry = SchemaRegistry::new();
+        let client = MockClient::new(vec![raw_message("{}")]);
+        let results = client.results.clone();
+        let mut listener: Listener = builder::pubsub(Box::new(client)).validate_schemas().build();
+        listener.register_handler(BrokenSchemaMessageHandler);
+
+        // when
+        let registered = registry.register_schema("Broken", serde_json::json!({ "type": 5 }));
+        let listened = listener.listen();
+
+        // then
+        assert!(matches!(registered, Err(e) if e.msg_type == "Broken"));
+        assert!(
+            matches!(listened, Err(ClientError::General(e)) if e.starts_with("invalid schema of BrokenSchemaMessage"))
+        );
+        assert!(results.lock().unwrap().is_empty());
+    }
+
+    fn raw_message(payload: &str) -> RawMessage {
+        RawMessage {
+            msg_type: "OrderPlacedMessage".to_string(),
+            headers: HashMap::new(),
+            payload: payload.to_string(),
+        }
+    }
+
+    // Helpers
+    #[derive(Deserialize, Serialize)]
+    struct BrokenSchemaMessage {}
+
+    impl MessageTypeName for BrokenSchemaMessage {
+        fn name() -> &'static str {
+            "BrokenSchemaMessage"
+        }
+
+        fn schema() -> Option<serde_json::Value> {
+            Some(serde_json::json!({ "type": 5 }))
+        }
+    }
+
+    struct BrokenSchemaMessageHandler;
+
+    impl MessageHandler<BrokenSchemaMessage> for BrokenSchemaMessageHandler {
+        fn handle(&mut self, _msg: BrokenSchemaMessage, _headers: Option<HashMap<String, String>>) {
+        }
+    }
+
+    struct MockClient {
+        messages: Vec<RawMessage>,
+        results: Arc<Mutex<Vec<Result<(), ClientError>>>>,
+    }
+
+    impl MockClient {
+        fn new(messages: Vec<RawMessage>) -> Self {
+            MockClient {
+                messages,
+                results: Arc::new(Mutex::new(vec![])),
+            }
+        }
+    }
+
+    impl Client for MockClient {
+        fn receiver(
+            &mut self,
+            recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+        ) -> Result<(), ClientError> {
+            for msg in self.messages.iter() {
+                let result = recv_callback(msg.clone());
+                self.results.lock().unwrap().push(result);
+            }
+            Ok(())
+        }
+
+        fn send(&mut self, _msg: &RawMessage) -> Result<(), ClientError> {
+            todo!("not implemented");
+        }
+    }
+}
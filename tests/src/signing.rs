@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests {
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        publisher::Publisher,
+        signing::{SigningLayer, VerificationLayer, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+        Client, ClientError, LayerError, RawMessage,
+    };
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler};
+
+    const KEY: &[u8] = b"test_signing_key";
+
+    #[test]
+    fn should_verify_signed_message_and_pass_it_to_handler() {
+        // given
+        let transport = publish_signed(HashMap::from([
+            ("trace-id".to_string(), "123".to_string()),
+            ("tenant".to_string(), "acme".to_string()),
+        ]));
+
+        // when
+        let (logger, results) = listen(transport, KEY, Duration::from_secs(60));
+
+        // then
+        assert!(results[0].is_ok());
+        assert_eq!(1, logger.lock().unwrap().get().len());
+    }
+
+    #[test]
+    fn should_reject_tampered_unsigned_and_foreign_messages() {
+        // given
+        let transport =
+            publish_signed(HashMap::from([("trace-id".to_string(), "123".to_string())]));
+        let signed = transport.lock().unwrap()[0].clone();
+
+        let mut tampered_payload = signed.clone();
+        tampered_payload.payload = r#"{"data":"injected"}"#.to_string();
+        let mut tampered_header = signed.clone();
+        tampered_header
+            .headers
+            .insert("trace-id".to_string(), "456".to_string());
+        let mut tampered_timestamp = signed.clone();
+        tampered_timestamp
+            .headers
+            .insert(TIMESTAMP_HEADER.to_string(), "1".to_string());
+        let mut unsigned = signed.clone();
+        unsigned.headers.remove(SIGNATURE_HEADER);
+        *transport.lock().unwrap() = vec![
+            tampered_payload,
+            tampered_header,
+            tampered_timestamp,
+            unsigned,
+        ];
+
+        // when
+        let (logger, results) = listen(transport.clone(), KEY, Duration::from_secs(60));
+        *transport.lock().unwrap() = vec![signed];
+        let (foreign_logger, foreign_results) =
+            listen(transport, b"other_key", Duration::from_secs(60));
+
+        // then
+        assert_eq!(0, logger.lock().unwrap().get().len());
+        assert_eq!(0, foreign_logger.lock().unwrap().get().len());
+        assert!(results
+            .iter()
+            .chain(foreign_results.iter())
+            .all(|r| matches!(r, Err(ClientError::Layer(LayerError::Rejected(_))))));
+    }
+
+    #[test]
+    fn should_reject_stale_message() {
+        // given
+        let transport = publish_signed(HashMap::new());
+        std::thread::sleep(Duration::from_millis(20));
+
+        // when
+        let (logger, results) = listen(transport, KEY, Duration::from_millis(10));
+
+        // then
+        assert_eq!(0, logger.lock().unwrap().get().len());
+        assert!(
+            matches!(&results[0], Err(ClientError::Layer(LayerError::Rejected(e))) if e.starts_with("stale message"))
+        );
+    }
+
+    fn publish_signed(headers: HashMap<String, String>) -> Arc<Mutex<Vec<RawMessage>>> {
+        let transport = Arc::new(Mutex::new(vec![]));
+        let publisher: Publisher = builder::pubsub(Box::new(MockClient::new(transport.clone())))
+            .add_layer(Box::new(
+                SigningLayer::new(KEY).with_signed_headers(&["trace-id", "tenant"]),
+            ))
+            .build();
+        publisher
+            .publish(
+                &TestMessage {
+                    data: "test_data".to_string(),
+                },
+                Some(headers),
+            )
+            .unwrap();
+        transport
+    }
+
+    fn listen(
+        transport: Arc<Mutex<Vec<RawMessage>>>,
+        key: &[u8],
+        max_age: Duration,
+    ) -> (Arc<Mutex<TestLogger>>, Vec<Result<(), ClientError>>) {
+        let client = MockClient::new(transport);
+        let results = client.results.clone();
+        let mut listener: Listener = builder::pubsub(Box::new(client))
+            .add_layer(Box::new(VerificationLayer::new(key, max_age)))
+            .build();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+
+        listener.listen().unwrap();
+
+        let results = results.lock().unwrap().drain(..).collect();
+        (logger, results)
+    }
+
+    // Helpers
+    struct MockClient {
+        messages: Arc<Mutex<Vec<RawMessage>>>,
+        results: Arc<Mutex<Vec<Result<(), ClientError>>>>,
+    }
+
+    impl MockClient {
+        fn new(messages: Arc<Mutex<Vec<RawMessage>>>) -> Self {
+            MockClient {
+                messages,
+                results: Arc::new(Mutex::new(vec![])),
+            }
+        }
+    }
+
+    impl Client for MockClient {
+        fn receiver(
+            &mut self,
+            recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+        ) -> Result<(), ClientError> {
+            let messages = self.messages.lock().unwrap().clone();
+            for msg in messages {
+                let result = recv_callback(msg);
+                self.results.lock().unwrap().push(result);
+            }
+            Ok(())
+        }
+
+        fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+}
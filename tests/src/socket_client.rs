@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        thread::{sleep, spawn},
+        time::Duration,
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+        ClientError,
+    };
+    use bus_rs_socket::{SocketAddress, SocketClient, SocketClientAsync};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_listener_receive_messages_of_many_publishers_over_tcp() {
+        // given
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let mut listener: Listener =
+            builder::pubsub(Box::new(SocketClient::new("tcp://127.0.0.1:17043"))).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+        sleep(Duration::from_millis(100));
+
+        // when
+        let first_publisher: Publisher =
+            builder::pubsub(Box::new(SocketClient::new("tcp://127.0.0.1:17043"))).build();
+        let second_publisher: Publisher =
+            builder::pubsub(Box::new(SocketClient::new("tcp://127.0.0.1:17043"))).build();
+        let first_msg = TestMessage {
+            data: "first".to_string(),
+        };
+        first_publisher.publish(&first_msg, None).unwrap();
+        let second_msg = TestMessage {
+            data: "second".to_string(),
+        };
+        second_publisher.publish(&second_msg, None).unwrap();
+
+        // then
+        sleep(Duration::from_millis(200));
+        let mut messages = logger.lock().unwrap().get().clone();
+        messages.sort();
+        assert_eq!(
+            vec!["msg: first headers: ", "msg: second headers: "],
+            messages
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn should_listener_receive_messages_in_order_over_unix_socket() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let addr = format!("unix://{}", dir.path().join("bus.sock").to_str().unwrap());
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let mut listener: ListenerAsync =
+            builder::pubsub_async(Box::new(SocketClientAsync::new(&addr))).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        tokio::spawn(async move {
+            let _ = listener.listen().await;
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // when
+        let publisher: PublisherAsync =
+            builder::pubsub_async(Box::new(SocketClientAsync::new(&addr))).build();
+        for i in 0..3 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+
+        // then
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            vec!["msg: 0 headers: ", "msg: 1 headers: ", "msg: 2 headers: "],
+            *logger.lock().await.get()
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_address_scheme() {
+        // when
+        let result = SocketAddress::parse("udp://127.0.0.1:17043");
+
+        // then
+        assert!(matches!(result, Err(ClientError::General(_))));
+    }
+}
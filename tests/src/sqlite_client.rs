@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        thread::{sleep, spawn},
+        time::Duration,
+    };
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener::Listener,
+        listener_async::ListenerAsync,
+        publisher::Publisher,
+        publisher_async::PublisherAsync,
+    };
+    use bus_rs_sqlite::{SqliteClient, SqliteClientAsync, StartFrom};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandler, TestMessageHandlerAsync};
+
+    #[test]
+    fn should_every_named_consumer_receive_all_messages_of_its_channel() {
+        // given listeners of two consumers on the same channel
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bus.db").to_str().unwrap().to_string();
+        let loggers: Vec<_> = ["first", "second"]
+            .into_iter()
+            .map(|consumer| {
+                let logger = Arc::new(Mutex::new(TestLogger::new()));
+                let client = SqliteClient::new(&path, "orders".to_string())
+                    .consumer(consumer)
+                    .poll_interval(Duration::from_millis(10));
+                let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+                listener.register_handler(TestMessageHandler {
+                    logger: logger.clone(),
+                });
+                spawn(move || listener.listen());
+                logger
+            })
+            .collect();
+
+        // given publishers of two channels
+        let publisher: Publisher =
+            builder::pubsub(Box::new(SqliteClient::new(&path, "orders".to_string()))).build();
+        let other_publisher: Publisher =
+            builder::pubsub(Box::new(SqliteClient::new(&path, "other".to_string()))).build();
+
+        // when
+        for i in 0..3 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).unwrap();
+        }
+        let other_msg = TestMessage {
+            data: "other".to_string(),
+        };
+        other_publisher.publish(&other_msg, None).unwrap();
+
+        // then
+        sleep(Duration::from_millis(300));
+        for logger in loggers {
+            assert_eq!(
+                vec!["msg: 0 headers: ", "msg: 1 headers: ", "msg: 2 headers: "],
+                *logger.lock().unwrap().get()
+            );
+        }
+    }
+
+    #[test]
+    fn should_consumer_starting_from_latest_receive_only_new_messages() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bus.db").to_str().unwrap().to_string();
+        let publisher: Publisher =
+            builder::pubsub(Box::new(SqliteClient::new(&path, "orders".to_string()))).build();
+        let old_msg = TestMessage {
+            data: "old".to_string(),
+        };
+        publisher.publish(&old_msg, None).unwrap();
+
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let client = SqliteClient::new(&path, "orders".to_string())
+            .start_from(StartFrom::Latest)
+            .poll_interval(Duration::from_millis(10));
+        let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+        sleep(Duration::from_millis(100));
+
+        // when
+        let new_msg = TestMessage {
+            data: "new".to_string(),
+        };
+        publisher.publish(&new_msg, None).unwrap();
+
+        // then
+        sleep(Duration::from_millis(200));
+        assert_eq!(vec!["msg: new headers: "], *logger.lock().unwrap().get());
+    }
+
+    #[tokio::test]
+    async fn should_restarted_consumer_continue_after_last_handled_message() {
+        // given messages handled by the first listener
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bus.db").to_str().unwrap().to_string();
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(SqliteClientAsync::new(
+            &path,
+            "orders".to_string(),
+        )))
+        .build();
+        for i in 0..2 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).await.unwrap();
+        }
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let listener_task = start_listener(&path, logger.clone()).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        listener_task.abort();
+        assert_eq!(2, logger.lock().await.get().len());
+
+        // when message is published while consumer is stopped
+        let test_msg = TestMessage {
+            data: "2".to_string(),
+        };
+        publisher.publish(&test_msg, None).await.unwrap();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let _listener_task = start_listener(&path, logger.clone()).await;
+
+        // then
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(vec!["msg: 2 headers: "], *logger.lock().await.get());
+    }
+
+    #[test]
+    fn should_purge_only_messages_handled_by_all_consumers() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bus.db").to_str().unwrap().to_string();
+        let publisher: Publisher =
+            builder::pubsub(Box::new(SqliteClient::new(&path, "orders".to_string()))).build();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let client =
+            SqliteClient::new(&path, "orders".to_string()).poll_interval(Duration::from_millis(10));
+        let mut listener: Listener = builder::pubsub(Box::new(client)).build();
+        listener.register_handler(TestMessageHandler {
+            logger: logger.clone(),
+        });
+        spawn(move || listener.listen());
+
+        // when
+        for i in 0..3 {
+            let test_msg = TestMessage {
+                data: format!("{}", i),
+            };
+            publisher.publish(&test_msg, None).unwrap();
+        }
+        sleep(Duration::from_millis(200));
+        let mut client = SqliteClient::new(&path, "orders".to_string());
+        let purged = client.purge_consumed().unwrap();
+
+        // then
+        assert_eq!(3, purged);
+        assert_eq!(3, logger.lock().unwrap().get().len());
+    }
+
+    // Helpers
+
+    async fn start_listener(
+        path: &str,
+        logger: Arc<tokio::sync::Mutex<TestLogger>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let client = SqliteClientAsync::new(path, "orders".to_string())
+            .poll_interval(Duration::from_millis(10));
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(client)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync { logger })
+            .await;
+        tokio::spawn(async move {
+            let _ = listener.listen().await;
+        })
+    }
+}
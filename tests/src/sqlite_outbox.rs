@@ -0,0 +1,238 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bus_rs::{
+        outbox::OutboxRelay, Client, ClientAsync, ClientCallbackFnAsync, ClientError, RawMessage,
+    };
+    use bus_rs_sqlite::{SqliteOutbox, SqliteOutboxStore};
+    use rusqlite::Connection;
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::{TestLayer, TestLogger, TestMessage};
+
+    #[test]
+    fn should_relay_only_messages_of_committed_transactions() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.db");
+        let mut connection = Connection::open(&path).unwrap();
+        connection
+            .execute("CREATE TABLE orders (name TEXT NOT NULL)", [])
+            .unwrap();
+        let outbox = SqliteOutbox::new("outbox");
+        outbox.create_table(&connection).unwrap();
+        let mut relay = OutboxRelay::new(Box::new(SqliteOutboxStore::new(
+            path.to_str().unwrap(),
+            "outbox",
+        )));
+        let mut client = MockClient::new();
+        let sent = client.messages.clone();
+
+        // when
+        for (name, commit) in [("committed", true), ("rolled back", false)] {
+            let tx = connection.transaction().unwrap();
+            tx.execute("INSERT INTO orders (name) VALUES (?1)", [name])
+                .unwrap();
+            outbox
+                .publish(
+                    &tx,
+                    &TestMessage {
+                        data: name.to_string(),
+                    },
+                    Some(HashMap::from([("trace-id".to_string(), "123".to_string())])),
+                )
+                .unwrap();
+            if commit {
+                tx.commit().unwrap();
+            } else {
+                tx.rollback().unwrap();
+            }
+        }
+        let dispatched = relay.relay(&mut client).unwrap();
+
+        // then
+        assert_eq!(1, dispatched);
+        let sent = sent.lock().unwrap();
+        assert_eq!("TestMessage", sent[0].msg_type);
+        assert_eq!(r#"{"data":"committed"}"#, sent[0].payload);
+        assert_eq!("123", sent[0].headers["trace-id"]);
+
+        // and message is not sent again
+        assert_eq!(0, relay.relay(&mut client).unwrap());
+    }
+
+    #[test]
+    fn should_relay_keep_failed_message_pending_and_retry_it_in_order() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.db");
+        let connection = Connection::open(&path).unwrap();
+        let logger = Arc::new(Mutex::new(TestLogger::new()));
+        let outbox = SqliteOutbox::new("outbox").add_layer(Box::new(TestLayer {
+            logger: logger.clone(),
+        }));
+        outbox.create_table(&connection).unwrap();
+        for data in ["first", "second", "third"] {
+            let msg = TestMessage {
+                data: data.to_string(),
+            };
+            outbox.publish(&connection, &msg, None).unwrap();
+        }
+        let mut relay = OutboxRelay::new(Box::new(SqliteOutboxStore::new(
+            path.to_str().unwrap(),
+            "outbox",
+        )))
+        .batch_size(2);
+        let mut client = MockClient::new();
+        *client.failing_payload.lock().unwrap() = Some(r#"{"data":"second"}"#.to_string());
+        let sent = client.messages.clone();
+
+        // when
+        let result = relay.relay(&mut client);
+
+        // then
+        assert!(matches!(result, Err(ClientError::IO(_))));
+        assert_eq!(1, sent.lock().unwrap().len());
+        assert_eq!(6, logger.lock().unwrap().get().len());
+
+        // and remaining messages are sent when client works again
+        *client.failing_payload.lock().unwrap() = None;
+        assert_eq!(2, relay.relay(&mut client).unwrap());
+        let payloads: Vec<String> = sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|m| m.payload.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                r#"{"data":"first"}"#,
+                r#"{"data":"second"}"#,
+                r#"{"data":"third"}"#
+            ],
+            payloads
+        );
+    }
+
+    #[test]
+    fn should_relay_fail_on_message_with_malformed_headers_instead_of_publishing_it() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.db");
+        let connection = Connection::open(&path).unwrap();
+        let outbox = SqliteOutbox::new("outbox");
+        outbox.create_table(&connection).unwrap();
+        let msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+        outbox.publish(&connection, &msg, None).unwrap();
+        connection
+            .execute("UPDATE outbox SET headers = 'not json'", [])
+            .unwrap();
+        let mut relay = OutboxRelay::new(Box::new(SqliteOutboxStore::new(
+            path.to_str().unwrap(),
+            "outbox",
+        )));
+        let mut client = MockClient::new();
+        let sent = client.messages.clone();
+
+        // when
+        let result = relay.relay(&mut client);
+
+        // then
+        match result {
+            Err(ClientError::General(e)) => assert!(e.contains("outbox message 1")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(sent.lock().unwrap().is_empty());
+        let pending: i64 = connection
+            .query_row(
+                "SELECT COUNT(*) FROM outbox WHERE dispatched_at IS NULL",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(1, pending);
+    }
+
+    #[tokio::test]
+    async fn should_relay_messages_to_async_client() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.db");
+        let connection = Connection::open(&path).unwrap();
+        let outbox = SqliteOutbox::new("events");
+        outbox.create_table(&connection).unwrap();
+        let msg = TestMessage {
+            data: "test_data".to_string(),
+        };
+        outbox.publish(&connection, &msg, None).unwrap();
+        let mut relay = OutboxRelay::new(Box::new(SqliteOutboxStore::new(
+            path.to_str().unwrap(),
+            "events",
+        )));
+        let mut client = MockClient::new();
+        let sent = client.messages.clone();
+
+        // when
+        let dispatched = relay.relay_async(&mut client).await.unwrap();
+
+        // then
+        assert_eq!(1, dispatched);
+        assert_eq!(r#"{"data":"test_data"}"#, sent.lock().unwrap()[0].payload);
+    }
+
+    // Helpers
+    struct MockClient {
+        messages: Arc<Mutex<Vec<RawMessage>>>,
+        failing_payload: Arc<Mutex<Option<String>>>,
+    }
+
+    impl MockClient {
+        fn new() -> Self {
+            MockClient {
+                messages: Arc::new(Mutex::new(vec![])),
+                failing_payload: Arc::new(Mutex::new(None)),
+            }
+        }
+
+        fn send_one(&self, msg: &RawMessage) -> Result<(), ClientError> {
+            if self.failing_payload.lock().unwrap().as_ref() == Some(&msg.payload) {
+                return Err(ClientError::IO("broken pipe".to_string()));
+            }
+            self.messages.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+    }
+
+    impl Client for MockClient {
+        fn receiver(
+            &mut self,
+            _recv_callback: &dyn Fn(RawMessage) -> Result<(), ClientError>,
+        ) -> Result<(), ClientError> {
+            todo!("not implemented");
+        }
+
+        fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            self.send_one(msg)
+        }
+    }
+
+    #[async_trait]
+    impl ClientAsync for MockClient {
+        async fn receiver(
+            &mut self,
+            _recv_callback: Arc<ClientCallbackFnAsync>,
+        ) -> Result<(), ClientError> {
+            todo!("not implemented");
+        }
+
+        async fn send(&mut self, msg: &RawMessage) -> Result<(), ClientError> {
+            self.send_one(msg)
+        }
+    }
+}
@@ -0,0 +1,278 @@
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc, time::Duration};
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener_async::ListenerAsync,
+        publisher_async::PublisherAsync,
+    };
+    use bus_rs_webhook::{Endpoint, WebhookClientAsync, WebhookIngressAsync};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use crate::{TestLogger, TestMessage, TestMessageHandlerAsync};
+
+    #[tokio::test]
+    async fn should_deliver_signed_message_to_ingress() {
+        // given
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let ingress = WebhookIngressAsync::new("127.0.0.1:17053", "/hooks").secret("secret");
+        start_listener(ingress, logger.clone()).await;
+        let client = WebhookClientAsync::new(vec![
+            Endpoint::new("http://127.0.0.1:17053/hooks").secret("secret")
+        ]);
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+
+        // when
+        let test_msg = TestMessage {
+            data: "test".to_string(),
+        };
+        let headers = HashMap::from([("tenant".to_string(), "acme".to_string())]);
+        publisher.publish(&test_msg, Some(headers)).await.unwrap();
+
+        // then
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(
+            vec!["msg: test headers: tenant=acme"],
+            *logger.lock().await.get()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_ingress_reject_request_with_invalid_signature() {
+        // given
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let ingress = WebhookIngressAsync::new("127.0.0.1:17054", "/hooks")
+            .secret("secret")
+            .msg_type("TestMessage");
+        start_listener(ingress, logger.clone()).await;
+
+        // when
+        let response = reqwest::Client::new()
+            .post("http://127.0.0.1:17054/hooks")
+            .header("bus-timestamp", "1700000000")
+            .header("bus-signature", "sha256=00")
+            .body(r#"{"data":"forged"}"#)
+            .send()
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(401, response.status().as_u16());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(logger.lock().await.get().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_ingress_reject_signed_request_with_changed_type_or_headers() {
+        // given request signed by the client
+        let capture = tokio::spawn(capture_request("127.0.0.1:17057"));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let client = WebhookClientAsync::new(vec![
+            Endpoint::new("http://127.0.0.1:17057/hooks").secret("secret")
+        ]);
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test".to_string(),
+        };
+        let headers = HashMap::from([("tenant".to_string(), "acme".to_string())]);
+        publisher.publish(&test_msg, Some(headers)).await.unwrap();
+        let (signed_headers, body) = capture.await.unwrap();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let ingress = WebhookIngressAsync::new("127.0.0.1:17058", "/hooks").secret("secret");
+        start_listener(ingress, logger.clone()).await;
+
+        // when
+        let mut statuses = vec![];
+        for (name, value) in [
+            ("bus-msg-type", "TestMessage"),
+            ("bus-msg-type", "OtherMessage"),
+            ("bus-header-tenant", "other"),
+            ("bus-header-role", "admin"),
+        ] {
+            let mut request = reqwest::Client::new().post("http://127.0.0.1:17058/hooks");
+            for (signed_name, signed_value) in &signed_headers {
+                if signed_name.starts_with("bus-") && signed_name != name {
+                    request = request.header(signed_name, signed_value);
+                }
+            }
+            let response = request
+                .header(name, value)
+                .body(body.clone())
+                .send()
+                .await
+                .unwrap();
+            statuses.push(response.status().as_u16());
+        }
+
+        // then only the unchanged request is accepted
+        assert_eq!(vec![202, 401, 401, 401], statuses);
+        assert_eq!(
+            vec!["msg: test headers: tenant=acme"],
+            *logger.lock().await.get()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_ingress_verify_type_from_custom_header() {
+        // given request signed by the client and ingress taking the type from a custom header
+        let capture = tokio::spawn(capture_request("127.0.0.1:17059"));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let client = WebhookClientAsync::new(vec![
+            Endpoint::new("http://127.0.0.1:17059/hooks").secret("secret")
+        ]);
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        let test_msg = TestMessage {
+            data: "test".to_string(),
+        };
+        publisher.publish(&test_msg, None).await.unwrap();
+        let (signed_headers, body) = capture.await.unwrap();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let ingress = WebhookIngressAsync::new("127.0.0.1:17060", "/hooks")
+            .secret("secret")
+            .msg_type_header("x-event");
+        start_listener(ingress, logger.clone()).await;
+
+        // when
+        let mut statuses = vec![];
+        for msg_type in ["OtherMessage", "TestMessage"] {
+            let mut request = reqwest::Client::new().post("http://127.0.0.1:17060/hooks");
+            for (name, value) in &signed_headers {
+                if name.starts_with("bus-") {
+                    request = request.header(name, value);
+                }
+            }
+            let response = request
+                .header("x-event", msg_type)
+                .body(body.clone())
+                .send()
+                .await
+                .unwrap();
+            statuses.push(response.status().as_u16());
+        }
+
+        // then only the type covered by the signature is dispatched
+        assert_eq!(vec![401, 202], statuses);
+        assert_eq!(vec!["msg: test headers: "], *logger.lock().await.get());
+    }
+
+    #[tokio::test]
+    async fn should_not_retry_invalid_request() {
+        // given endpoint with invalid URL
+        let client = WebhookClientAsync::new(vec![Endpoint::new("not a url")])
+            .retries(3)
+            .backoff(Duration::from_secs(1));
+        let failures = client.failures();
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+
+        // when
+        let started = std::time::Instant::now();
+        let test_msg = TestMessage {
+            data: "test".to_string(),
+        };
+        let result = publisher.publish(&test_msg, None).await;
+
+        // then fails without waiting for retries
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            1,
+            failures.status("not a url").unwrap().consecutive_failures
+        );
+    }
+
+    #[tokio::test]
+    async fn should_retry_delivery_and_track_failures_per_endpoint() {
+        // given endpoint which is started later and endpoint which is down
+        let client = WebhookClientAsync::new(vec![
+            Endpoint::new("http://127.0.0.1:17055/hooks"),
+            Endpoint::new("http://127.0.0.1:17056/hooks"),
+        ])
+        .retries(3)
+        .backoff(Duration::from_millis(200));
+        let failures = client.failures();
+        let publisher: PublisherAsync = builder::pubsub_async(Box::new(client)).build();
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        tokio::spawn({
+            let logger = logger.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                start_listener(
+                    WebhookIngressAsync::new("127.0.0.1:17055", "/hooks"),
+                    logger,
+                )
+                .await;
+            }
+        });
+
+        // when
+        let test_msg = TestMessage {
+            data: "test".to_string(),
+        };
+        let result = publisher.publish(&test_msg, None).await;
+
+        // then
+        assert!(result.is_err());
+        assert_eq!(vec!["msg: test headers: "], *logger.lock().await.get());
+        let available = failures.status("http://127.0.0.1:17055/hooks").unwrap();
+        assert_eq!(0, available.consecutive_failures);
+        assert!(available.last_success.is_some());
+        let down = failures.status("http://127.0.0.1:17056/hooks").unwrap();
+        assert_eq!(1, down.consecutive_failures);
+        assert!(down.last_error.is_some());
+    }
+
+    // Helpers
+
+    /// Accept one HTTP request, respond 200 and return its headers and body.
+    async fn capture_request(addr: &str) -> (Vec<(String, String)>, String) {
+        let listener = TcpListener::bind(addr).await.unwrap();
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = vec![];
+        let mut buf = [0u8; 4096];
+        let (head, body) = loop {
+            let read = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..read]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length: usize = head
+                    .lines()
+                    .filter_map(|line| line.split_once(": "))
+                    .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                    .map(|(_, value)| value.parse().unwrap())
+                    .unwrap_or_default();
+                if body.len() >= length {
+                    break (head.to_string(), body.to_string());
+                }
+            }
+        };
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+        let headers = head
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(": "))
+            .map(|(name, value)| (name.to_lowercase(), value.to_string()))
+            .collect();
+        (headers, body)
+    }
+
+    async fn start_listener(
+        ingress: WebhookIngressAsync,
+        logger: Arc<tokio::sync::Mutex<TestLogger>>,
+    ) {
+        let mut listener: ListenerAsync = builder::pubsub_async(Box::new(ingress)).build();
+        listener
+            .register_handler(TestMessageHandlerAsync { logger })
+            .await;
+        tokio::spawn(async move {
+            let _ = listener.listen().await;
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
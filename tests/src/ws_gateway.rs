@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, path::Path, sync::Arc, time::Duration};
+
+    use bus_rs::{
+        builder::{self, Builder},
+        listener_async::ListenerAsync,
+        publisher_async::PublisherAsync,
+    };
+    use bus_rs_gateway::{Permissions, TokenAuthenticator, WsGateway};
+    use bus_rs_socket::SocketClientAsync;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::{TcpStream, UnixStream};
+    use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
+
+    use crate::{TestLogger, TestMessage, TestMessageHandlerAsync, WrongTestMessage};
+
+    type WebSocket = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    #[tokio::test]
+    async fn should_forward_subscribed_message_types_of_listener_to_client() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("bus.sock");
+        let addr = format!("unix://{}", socket.to_str().unwrap());
+        let gateway = WsGateway::new("127.0.0.1:17062", Box::new(authenticator()));
+        let mut listener: ListenerAsync =
+            builder::pubsub_async(Box::new(SocketClientAsync::new(&addr)))
+                .add_layer(Box::new(gateway.layer()))
+                .build();
+        tokio::spawn(gateway.run());
+        tokio::spawn(async move {
+            let _ = listener.listen().await;
+        });
+        wait_for_socket(&socket).await;
+        let mut ws = connect("ws://127.0.0.1:17062/?token=dashboard").await;
+        ws.send(Message::Text(
+            r#"{"op":"subscribe","msg_types":["TestMessage"]}"#.to_string(),
+        ))
+        .await
+        .unwrap();
+        assert_eq!(r#"{"op":"ok"}"#, next_text(&mut ws).await);
+
+        // when
+        let publisher: PublisherAsync =
+            builder::pubsub_async(Box::new(SocketClientAsync::new(&addr))).build();
+        let wrong_msg = WrongTestMessage {
+            data: "wrong".to_string(),
+        };
+        publisher.publish(&wrong_msg, None).await.unwrap();
+        let test_msg = TestMessage {
+            data: "test".to_string(),
+        };
+        publisher.publish(&test_msg, None).await.unwrap();
+
+        // then
+        let frame: serde_json::Value = serde_json::from_str(&next_text(&mut ws).await).unwrap();
+        assert_eq!("message", frame["op"]);
+        assert_eq!("TestMessage", frame["message"]["msg_type"]);
+        assert_eq!(r#"{"data":"test"}"#, frame["message"]["payload"]);
+    }
+
+    #[tokio::test]
+    async fn should_reject_client_with_invalid_token() {
+        // given
+        let gateway = WsGateway::new("127.0.0.1:17063", Box::new(authenticator()));
+        tokio::spawn(gateway.run());
+        eventually(|| async { TcpStream::connect("127.0.0.1:17063").await.is_ok() }).await;
+
+        // when
+        let mut request = "ws://127.0.0.1:17063".into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert("authorization", "Bearer unknown".parse().unwrap());
+        let result = tokio_tungstenite::connect_async(request).await;
+
+        // then
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_republish_allowed_message_and_refuse_not_permitted_subscription() {
+        // given
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("bus.sock");
+        let addr = format!("unix://{}", socket.to_str().unwrap());
+        let logger = Arc::new(tokio::sync::Mutex::new(TestLogger::new()));
+        let mut listener: ListenerAsync =
+            builder::pubsub_async(Box::new(SocketClientAsync::new(&addr))).build();
+        listener
+            .register_handler(TestMessageHandlerAsync {
+                logger: logger.clone(),
+            })
+            .await;
+        tokio::spawn(async move {
+            let _ = listener.listen().await;
+        });
+        let publisher: PublisherAsync =
+            builder::pubsub_async(Box::new(SocketClientAsync::new(&addr))).build();
+        let gateway =
+            WsGateway::new("127.0.0.1:17064", Box::new(authenticator())).publisher(publisher);
+        tokio::spawn(gateway.run());
+        wait_for_socket(&socket).await;
+        let mut ws = connect("ws://127.0.0.1:17064/?token=dashboard").await;
+
+        // when
+        ws.send(Message::Text(
+            r#"{"op":"subscribe","msg_types":["WrongTestMessage"]}"#.to_string(),
+        ))
+        .await
+        .unwrap();
+        let subscribe_response = next_text(&mut ws).await;
+        let publish = r#"{"op":"publish","message":{"msg_type":"TestMessage","headers":{},"payload":"{\"data\":\"from browser\"}"}}"#;
+        ws.send(Message::Text(publish.to_string())).await.unwrap();
+        let publish_response = next_text(&mut ws).await;
+
+        // then
+        assert_eq!(
+            r#"{"op":"error","reason":"not allowed to subscribe to WrongTestMessage"}"#,
+            subscribe_response
+        );
+        assert_eq!(r#"{"op":"ok"}"#, publish_response);
+        eventually(|| async { !logger.lock().await.get().is_empty() }).await;
+        assert_eq!(
+            vec!["msg: from browser headers: "],
+            *logger.lock().await.get()
+        );
+    }
+
+    // Helpers
+
+    fn authenticator() -> TokenAuthenticator {
+        TokenAuthenticator::new().token(
+            "dashboard",
+            Permissions {
+                subscribe: vec!["TestMessage".to_string()],
+                publish: vec!["TestMessage".to_string()],
+            },
+        )
+    }
+
+    /// The gateway is started in the background, so the connection is retried until it's accepted.
+    async fn connect(url: &str) -> WebSocket {
+        let mut attempts = 0;
+        loop {
+            match tokio_tungstenite::connect_async(url).await {
+                Ok((ws, _)) => return ws,
+                Err(e) if attempts == 250 => panic!("gateway is not ready: {}", e),
+                Err(_) => attempts += 1,
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    /// Wait until the listener accepts connections on the socket, so published messages are not lost.
+    async fn wait_for_socket(socket: &Path) {
+        eventually(|| async { UnixStream::connect(socket).await.is_ok() }).await;
+    }
+
+    async fn eventually<F, Fut>(condition: F)
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        for _ in 0..250 {
+            if condition().await {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("condition is not met in time");
+    }
+
+    async fn next_text(ws: &mut WebSocket) -> String {
+        let message = tokio::time::timeout(Duration::from_secs(1), ws.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        message.into_text().unwrap()
+    }
+}